@@ -4,9 +4,17 @@
 // may not be copied, modified, or distributed
 // except according to those terms.
 #![macro_use]
-use std::ffi::OsStr;
-use std::os::windows::ffi::OsStrExt;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::mem;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::ptr;
 use std::slice;
+use windows_sys::core::PCWSTR;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::Security;
+use windows_sys::Win32::System::Environment;
+use windows_sys::Win32::System::Threading;
 
 macro_rules! werr {
     ($e:expr) => {
@@ -21,3 +29,109 @@ pub(crate) fn to_utf16<P: AsRef<OsStr>>(s: P) -> Vec<u16> {
 pub(crate) fn v16_to_v8(v: &[u16]) -> Vec<u8> {
     unsafe { slice::from_raw_parts(v.as_ptr() as *const u8, v.len() * 2).to_vec() }
 }
+
+/// Expand `%VAR%` references using the environment of the current process
+pub(crate) fn expand_env_strings<S: AsRef<OsStr>>(s: S) -> io::Result<OsString> {
+    let src = to_utf16(s);
+    let mut len =
+        unsafe { Environment::ExpandEnvironmentStringsW(src.as_ptr(), ptr::null_mut(), 0) };
+    loop {
+        if len == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut buf: Vec<u16> = vec![0; len as usize];
+        let written =
+            unsafe { Environment::ExpandEnvironmentStringsW(src.as_ptr(), buf.as_mut_ptr(), len) };
+        if written == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if written <= len {
+            // `written` includes the terminating NULL
+            buf.truncate(written as usize - 1);
+            return Ok(OsString::from_wide(&buf));
+        }
+        // the environment changed between the calls
+        len = written;
+    }
+}
+
+/// Token handle of the current process, closed on drop
+pub(crate) struct ProcessToken(pub(crate) Foundation::HANDLE);
+
+impl ProcessToken {
+    pub(crate) fn open(access: Security::TOKEN_ACCESS_MASK) -> io::Result<ProcessToken> {
+        let mut handle: Foundation::HANDLE = ptr::null_mut();
+        if unsafe {
+            Threading::OpenProcessToken(Threading::GetCurrentProcess(), access, &mut handle)
+        } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ProcessToken(handle))
+    }
+}
+
+impl Drop for ProcessToken {
+    fn drop(&mut self) {
+        unsafe { Foundation::CloseHandle(self.0) };
+    }
+}
+
+/// Privilege enabled by `enable_privilege`, restored to its previous state on drop
+pub(crate) struct PrivilegeGuard {
+    token: ProcessToken,
+    previous: Security::TOKEN_PRIVILEGES,
+}
+
+/// Enable a privilege such as `SE_BACKUP_NAME` in the process token.
+/// Fails with `ERROR_PRIVILEGE_NOT_HELD` if the token doesn't have it.
+pub(crate) fn enable_privilege(name: PCWSTR) -> io::Result<PrivilegeGuard> {
+    let token = ProcessToken::open(Security::TOKEN_ADJUST_PRIVILEGES | Security::TOKEN_QUERY)?;
+    let mut luid: Foundation::LUID = unsafe { mem::zeroed() };
+    if unsafe { Security::LookupPrivilegeValueW(ptr::null(), name, &mut luid) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let new = Security::TOKEN_PRIVILEGES {
+        PrivilegeCount: 1,
+        Privileges: [Security::LUID_AND_ATTRIBUTES {
+            Luid: luid,
+            Attributes: Security::SE_PRIVILEGE_ENABLED,
+        }],
+    };
+    let mut previous: Security::TOKEN_PRIVILEGES = unsafe { mem::zeroed() };
+    let mut len: u32 = 0;
+    if unsafe {
+        Security::AdjustTokenPrivileges(
+            token.0,
+            0,
+            &new,
+            mem::size_of::<Security::TOKEN_PRIVILEGES>() as u32,
+            &mut previous,
+            &mut len,
+        )
+    } == 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    // succeeds without enabling privileges the token doesn't have
+    if unsafe { Foundation::GetLastError() } == Foundation::ERROR_NOT_ALL_ASSIGNED {
+        return werr!(Foundation::ERROR_PRIVILEGE_NOT_HELD);
+    }
+    Ok(PrivilegeGuard { token, previous })
+}
+
+impl Drop for PrivilegeGuard {
+    fn drop(&mut self) {
+        // `previous` is empty if the privilege was already enabled
+        unsafe {
+            Security::AdjustTokenPrivileges(
+                self.token.0,
+                0,
+                &self.previous,
+                0,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Copying of key trees with control over conflicts, security and progress.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::copy::{CopyOptions, OnExisting};
+//!use winreg2::enums::*;
+//!
+//!fn main() -> io::Result<()> {
+//!    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+//!    let (dst, _disp) = hkcu.create_subkey("Software\\MyProduct\\Backup")?;
+//!    let mut copied = 0;
+//!    let options = CopyOptions::new()
+//!        .on_existing(OnExisting::Skip)
+//!        .security(true)
+//!        .progress(|path| {
+//!            copied += 1;
+//!            println!("{}", path);
+//!        });
+//!    hkcu.copy_tree_with_options("Software\\MyProduct\\Settings", &dst, options)?;
+//!    println!("{} keys copied", copied);
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::RegKey;
+use std::fmt;
+use std::io;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::System::Registry;
+
+/// Callback of `CopyOptions::progress`
+type Progress<'a> = Box<dyn FnMut(&str) + 'a>;
+
+/// What a copy does with values that already exist in the destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnExisting {
+    /// Replace them with the source values
+    Overwrite,
+    /// Keep them as they are
+    Skip,
+    /// Stop the copy with `ERROR_ALREADY_EXISTS`
+    Error,
+}
+
+impl Default for OnExisting {
+    fn default() -> OnExisting {
+        OnExisting::Overwrite
+    }
+}
+
+/// Options of `RegKey::copy_tree_with_options`, created with `CopyOptions::new()`
+#[derive(Default)]
+pub struct CopyOptions<'a> {
+    on_existing: OnExisting,
+    security: bool,
+    progress: Option<Progress<'a>>,
+}
+
+impl<'a> CopyOptions<'a> {
+    pub fn new() -> CopyOptions<'a> {
+        CopyOptions::default()
+    }
+
+    /// Handling of values that exist in the destination, `Overwrite` by default
+    pub fn on_existing(mut self, on_existing: OnExisting) -> Self {
+        self.on_existing = on_existing;
+        self
+    }
+
+    /// Copy the DACL of every key, otherwise copied keys get the default security
+    /// of their new parent
+    pub fn security(mut self, copy: bool) -> Self {
+        self.security = copy;
+        self
+    }
+
+    /// Call `f` with the path of every key before it is copied, relative to the
+    /// source key, which has an empty path
+    pub fn progress<F: FnMut(&str) + 'a>(mut self, f: F) -> Self {
+        self.progress = Some(Box::new(f));
+        self
+    }
+}
+
+impl fmt::Debug for CopyOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CopyOptions")
+            .field("on_existing", &self.on_existing)
+            .field("security", &self.security)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// Copy the content of `src` to `dest`, symbolic links are copied as links
+pub(crate) fn copy_tree(
+    src: &RegKey,
+    dest: &RegKey,
+    path: &str,
+    options: &mut CopyOptions,
+) -> io::Result<()> {
+    if let Some(ref mut progress) = options.progress {
+        progress(path);
+    }
+    if options.security {
+        let sd = src.get_security(DACL_SECURITY_INFORMATION)?;
+        dest.set_security(sd.information()?, &sd)?;
+    }
+    for value in src.enum_values() {
+        let (name, value) = value?;
+        if options.on_existing != OnExisting::Overwrite {
+            match dest.get_raw_value(&name) {
+                Ok(_) if options.on_existing == OnExisting::Skip => continue,
+                Ok(_) => return werr!(Foundation::ERROR_ALREADY_EXISTS),
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        dest.set_raw_value(name, &value)?;
+    }
+    for name in src.enum_keys() {
+        let name = name?;
+        let child = src.open_subkey_nofollow(&name, KEY_READ)?;
+        let flags = child.key_flags()?;
+        if flags.link {
+            dest.create_link(&name, child.read_link()?)?;
+            continue;
+        }
+        // volatile keys can only have volatile subkeys
+        let reg_options = if flags.volatile {
+            Registry::REG_OPTION_VOLATILE
+        } else {
+            Registry::REG_OPTION_NON_VOLATILE
+        };
+        let (dest_child, _disp) =
+            dest.create_subkey_with_class(&name, child.class()?, reg_options, KEY_ALL_ACCESS)?;
+        let child_path = if path.is_empty() {
+            name
+        } else {
+            format!("{}\\{}", path, name)
+        };
+        copy_tree(&child, &dest_child, &child_path, options)?;
+    }
+    Ok(())
+}
@@ -3,10 +3,11 @@
 // http://opensource.org/licenses/MIT>. This file
 // may not be copied, modified, or distributed
 // except according to those terms.
+pub use crate::encoder::NamingConvention;
 use crate::enums::*;
 use crate::reg_key::RegKey;
 use crate::reg_value::RegValue;
-use crate::types::FromRegValue;
+use crate::types::{ExpandString, FromRegValue};
 use std::error::Error;
 use std::fmt;
 use std::io;
@@ -64,26 +65,169 @@ enum DecoderCursor {
     FieldVal(u32, String),
 }
 
+/// How `deserialize_any` presents `REG_BINARY` values and values of types
+/// without a natural representation, e.g. when transcoding to JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    /// A sequence of numbers
+    Array,
+    /// A byte buffer, for formats with a bytes type; untyped values like
+    /// `serde_json::Value` don't accept it
+    Bytes,
+    /// A string of lowercase hex digits
+    Hex,
+    /// A string in standard base64 with padding
+    Base64,
+}
+
+impl Default for BinaryEncoding {
+    fn default() -> BinaryEncoding {
+        BinaryEncoding::Array
+    }
+}
+
+/// What the `Decoder` does with values and subkeys that match no field of a struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownFields {
+    /// Pass them on, `serde` skips them unless the struct has `deny_unknown_fields`
+    Ignore,
+    /// Fail with `DecoderError::DeserializerError`
+    Error,
+}
+
+impl Default for UnknownFields {
+    fn default() -> UnknownFields {
+        UnknownFields::Ignore
+    }
+}
+
+/// Options of `Decoder::from_key_with_options`, created with `DecoderOptions::new()`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DecoderOptions {
+    binary_encoding: BinaryEncoding,
+    expand_strings: bool,
+    case_insensitive: bool,
+    unknown_fields: UnknownFields,
+    naming_convention: NamingConvention,
+}
+
+impl DecoderOptions {
+    pub fn new() -> DecoderOptions {
+        DecoderOptions::default()
+    }
+
+    /// How `deserialize_any` presents binary values, `BinaryEncoding::Array` by default
+    pub fn binary_encoding(mut self, binary_encoding: BinaryEncoding) -> Self {
+        self.binary_encoding = binary_encoding;
+        self
+    }
+
+    /// Expand the environment variables of `REG_EXPAND_SZ` values read as strings
+    pub fn expand_strings(mut self, expand: bool) -> Self {
+        self.expand_strings = expand;
+        self
+    }
+
+    /// Match value and subkey names to struct fields ignoring ASCII case,
+    /// e.g. to read `ServerName` into `servername`
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Handling of values and subkeys that match no struct field, `Ignore` by default
+    pub fn unknown_fields(mut self, unknown_fields: UnknownFields) -> Self {
+        self.unknown_fields = unknown_fields;
+        self
+    }
+
+    /// Naming of the values and subkeys of struct fields, `NamingConvention::AsIs`
+    /// by default, e.g. `PascalCase` to read `ServerName` into `server_name`
+    pub fn naming_convention(mut self, naming_convention: NamingConvention) -> Self {
+        self.naming_convention = naming_convention;
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct Decoder {
     key: RegKey,
     cursor: DecoderCursor,
+    options: DecoderOptions,
+    /// Fields of the struct being decoded from the key
+    fields: Option<&'static [&'static str]>,
+    /// The default value holds the variant name of an enum, it isn't a field
+    skip_default: bool,
 }
 
 const DECODER_SAM: u32 = KEY_QUERY_VALUE | KEY_ENUMERATE_SUB_KEYS;
 
 impl Decoder {
     pub fn from_key(key: &RegKey) -> DecodeResult<Decoder> {
+        Decoder::from_key_with_options(key, DecoderOptions::new())
+    }
+
+    pub fn from_key_with_options(key: &RegKey, options: DecoderOptions) -> DecodeResult<Decoder> {
         key.open_subkey_with_flags("", DECODER_SAM)
-            .map(Decoder::new)
+            .map(|k| Decoder::new(k, options))
             .map_err(DecoderError::IoError)
     }
 
-    fn new(key: RegKey) -> Decoder {
+    fn new(key: RegKey, options: DecoderOptions) -> Decoder {
         Decoder {
             key,
             cursor: DecoderCursor::Start,
+            options,
+            fields: None,
+            skip_default: false,
+        }
+    }
+
+    /// Decoder of a subkey with the same options
+    fn nested(&self, key: RegKey) -> Decoder {
+        Decoder::new(key, self.options)
+    }
+
+    /// Name of the struct field a value or subkey name maps to
+    fn field_name(&self, name: String) -> DecodeResult<String> {
+        let fields = match self.fields {
+            Some(fields) => fields,
+            None => return Ok(name),
+        };
+        let naming = self.options.naming_convention;
+        if let Some(field) = fields.iter().find(|f| naming.apply(f) == name) {
+            return Ok((*field).to_owned());
+        }
+        if self.options.case_insensitive {
+            let found = fields
+                .iter()
+                .find(|f| naming.apply(f).eq_ignore_ascii_case(&name));
+            if let Some(field) = found {
+                return Ok((*field).to_owned());
+            }
+        }
+        match self.options.unknown_fields {
+            UnknownFields::Ignore => Ok(name),
+            UnknownFields::Error => Err(DecoderError::DeserializerError(format!(
+                "Unknown field {:?}",
+                name
+            ))),
+        }
+    }
+
+    /// Convert a string value, expanding it if requested
+    fn string_value(&self, value: &RegValue) -> DecodeResult<String> {
+        if value.vtype == REG_EXPAND_SZ && self.options.expand_strings {
+            return ExpandString::from_reg_value(value)
+                .and_then(|s| s.expand())
+                .map_err(DecoderError::IoError);
         }
+        Ok(String::from_reg_value(value)?)
+    }
+
+    fn read_string(&mut self) -> DecodeResult<String> {
+        let value = self.read_raw()?;
+        self.string_value(&value)
     }
 
     fn read_value<T: FromRegValue>(&mut self) -> Result<T, DecoderError> {
@@ -99,18 +243,41 @@ impl Decoder {
     }
 
     fn read_bytes(&mut self) -> Result<Vec<u8>, DecoderError> {
+        self.read_raw().map(|v| v.bytes)
+    }
+
+    fn read_raw(&mut self) -> Result<RegValue, DecoderError> {
         use self::DecoderCursor::*;
         let cursor = self.cursor.clone();
         match cursor {
             FieldVal(index, name) => {
                 self.cursor = DecoderCursor::Field(index + 1);
-                let RegValue { bytes, .. } = self
-                    .key
-                    .get_raw_value(name)
-                    .map_err(DecoderError::IoError)?;
-                Ok(bytes)
+                self.key.get_raw_value(name).map_err(DecoderError::IoError)
             }
             _ => Err(DecoderError::DeserializerError("Not a value".to_owned())),
         }
     }
 }
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn to_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -3,10 +3,15 @@
 // http://opensource.org/licenses/MIT>. This file
 // may not be copied, modified, or distributed
 // except according to those terms.
-use super::{DecodeResult, Decoder, DecoderCursor, DecoderError, DECODER_SAM};
+use super::{
+    to_base64, to_hex, BinaryEncoding, DecodeResult, Decoder, DecoderCursor, DecoderError,
+    DECODER_SAM,
+};
+use crate::enums::*;
 use crate::types::FromRegValue;
 use serde::de::*;
 use std::fmt;
+use std::io;
 
 impl Error for DecoderError {
     fn custom<T: fmt::Display>(msg: T) -> Self {
@@ -30,17 +35,23 @@ impl<'de> Deserializer<'de> for &mut Decoder {
                 let v = self.key.get_raw_value(name)?;
                 self.cursor = Field(index + 1);
                 match v.vtype {
-                    REG_SZ | REG_EXPAND_SZ | REG_MULTI_SZ => {
-                        visitor.visit_string(String::from_reg_value(&v)?)
+                    REG_SZ | REG_EXPAND_SZ => visitor.visit_string(self.string_value(&v)?),
+                    REG_MULTI_SZ => {
+                        let strings = Vec::<String>::from_reg_value(&v)?;
+                        visitor.visit_seq(value::SeqDeserializer::new(strings.into_iter()))
                     }
-                    REG_DWORD => visitor.visit_u32(u32::from_reg_value(&v)?),
+                    REG_DWORD | REG_DWORD_BIG_ENDIAN => visitor.visit_u32(u32::from_reg_value(&v)?),
                     REG_QWORD => visitor.visit_u64(u64::from_reg_value(&v)?),
-                    REG_BINARY => visitor.visit_byte_buf(v.bytes),
                     REG_NONE => visitor.visit_none(),
-                    _ => no_impl!(format!(
-                        "value type deserialization not implemented {:?}",
-                        v.vtype
-                    )),
+                    // binary and the rest of the types
+                    _ => match self.options.binary_encoding {
+                        BinaryEncoding::Array => {
+                            visitor.visit_seq(value::SeqDeserializer::new(v.bytes.into_iter()))
+                        }
+                        BinaryEncoding::Bytes => visitor.visit_byte_buf(v.bytes),
+                        BinaryEncoding::Hex => visitor.visit_string(to_hex(&v.bytes)),
+                        BinaryEncoding::Base64 => visitor.visit_string(to_base64(&v.bytes)),
+                    },
                 }
             }
             _ => no_impl!("deserialize_any"),
@@ -79,7 +90,16 @@ impl<'de> Deserializer<'de> for &mut Decoder {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u64(self.read_value()?)
+        let v = self.read_raw()?;
+        // written as a string with `EncoderOptions::u64_as_string`
+        if v.vtype == REG_SZ {
+            let s = String::from_reg_value(&v)?;
+            return visitor.visit_u64(
+                s.parse()
+                    .map_err(|e| DecoderError::ParseError(format!("{:?}", e)))?,
+            );
+        }
+        visitor.visit_u64(u64::from_reg_value(&v)?)
     }
 
     fn deserialize_i8<V>(self, visitor: V) -> DecodeResult<V::Value>
@@ -153,7 +173,7 @@ impl<'de> Deserializer<'de> for &mut Decoder {
                 self.cursor = DecoderCursor::FieldVal(index, name.clone());
                 visitor.visit_string(name)
             }
-            FieldVal(..) => visitor.visit_string(self.read_value()?),
+            FieldVal(..) => visitor.visit_string(self.read_string()?),
             _ => Err(DecoderError::NoFieldName),
         }
     }
@@ -187,6 +207,10 @@ impl<'de> Deserializer<'de> for &mut Decoder {
         };
         match v {
             Ok(..) => visitor.visit_some(&mut *self),
+            // only a missing value is `None`, e.g. a denied access is an error
+            Err(DecoderError::IoError(e)) if e.kind() != io::ErrorKind::NotFound => {
+                Err(DecoderError::IoError(e))
+            }
             Err(..) => visitor.visit_none(),
         }
     }
@@ -216,11 +240,24 @@ impl<'de> Deserializer<'de> for &mut Decoder {
         no_impl!("deserialize_newtype_struct")
     }
 
-    fn deserialize_seq<V>(self, _visitor: V) -> DecodeResult<V::Value>
+    fn deserialize_seq<V>(self, visitor: V) -> DecodeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        no_impl!("deserialize_seq")
+        use super::DecoderCursor::*;
+        match self.cursor {
+            // a REG_MULTI_SZ value
+            FieldVal(..) => {
+                let strings: Vec<String> = self.read_value()?;
+                visitor.visit_seq(value::SeqDeserializer::new(strings.into_iter()))
+            }
+            // elements named `0`, `1`, ... in a subkey or the root key
+            Start => visitor.visit_seq(IndexedSeq {
+                decoder: self,
+                index: 0,
+            }),
+            _ => no_impl!("deserialize_seq"),
+        }
     }
 
     fn deserialize_tuple<V>(self, _len: usize, _visitor: V) -> DecodeResult<V::Value>
@@ -252,12 +289,13 @@ impl<'de> Deserializer<'de> for &mut Decoder {
     fn deserialize_struct<V>(
         self,
         _name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V,
     ) -> DecodeResult<V::Value>
     where
         V: Visitor<'de>,
     {
+        self.fields = Some(fields);
         visitor.visit_map(self)
     }
 
@@ -272,12 +310,28 @@ impl<'de> Deserializer<'de> for &mut Decoder {
         self,
         _name: &'static str,
         _variants: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> DecodeResult<V::Value>
     where
         V: Visitor<'de>,
     {
-        no_impl!("deserialize_enum")
+        use super::DecoderCursor::*;
+        match self.cursor {
+            // a unit variant
+            FieldVal(..) => {
+                let variant: String = self.read_value()?;
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            // a variant with data, named by the default value of its key
+            Start => {
+                let variant: String = self.key.get_value("")?;
+                visitor.visit_enum(VariantKey {
+                    decoder: self,
+                    variant,
+                })
+            }
+            _ => no_impl!("deserialize_enum"),
+        }
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> DecodeResult<V::Value>
@@ -288,6 +342,104 @@ impl<'de> Deserializer<'de> for &mut Decoder {
     }
 }
 
+/// Elements of a sequence written with `SeqEncoding::Indexed`
+struct IndexedSeq<'a> {
+    decoder: &'a mut Decoder,
+    index: usize,
+}
+
+impl<'de> SeqAccess<'de> for IndexedSeq<'_> {
+    type Error = DecoderError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let name = self.index.to_string();
+        self.index += 1;
+        match self.decoder.key.get_raw_value(&name) {
+            Ok(_) => {
+                self.decoder.cursor = DecoderCursor::FieldVal(0, name);
+                return seed.deserialize(&mut *self.decoder).map(Some);
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(DecoderError::IoError(e)),
+        }
+        match self.decoder.key.open_subkey_with_flags(&name, DECODER_SAM) {
+            Ok(subkey) => seed.deserialize(&mut self.decoder.nested(subkey)).map(Some),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(DecoderError::IoError(e)),
+        }
+    }
+}
+
+/// A key holding an enum variant with data
+struct VariantKey<'a> {
+    decoder: &'a mut Decoder,
+    variant: String,
+}
+
+impl<'de, 'a> EnumAccess<'de> for VariantKey<'a> {
+    type Error = DecoderError;
+    type Variant = &'a mut Decoder;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant: value::StringDeserializer<DecoderError> = self.variant.into_deserializer();
+        let variant = seed.deserialize(variant)?;
+        Ok((variant, self.decoder))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut Decoder {
+    type Error = DecoderError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let mut elements = IndexedSeq {
+            decoder: self,
+            index: 0,
+        };
+        match elements.next_element_seed(seed)? {
+            Some(value) => Ok(value),
+            None => Err(DecoderError::DeserializerError(
+                "Missing variant value".to_owned(),
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(IndexedSeq {
+            decoder: self,
+            index: 0,
+        })
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.fields = Some(fields);
+        self.skip_default = true;
+        visitor.visit_map(self)
+    }
+}
+
 impl<'de> MapAccess<'de> for Decoder {
     type Error = DecoderError;
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
@@ -302,7 +454,7 @@ impl<'de> MapAccess<'de> for Decoder {
             }
             Key(index) => match self.key.enum_key(index) {
                 Some(res) => {
-                    self.cursor = KeyName(index, res?);
+                    self.cursor = KeyName(index, self.field_name(res?)?);
                     seed.deserialize(&mut *self).map(Some)
                 }
                 None => {
@@ -314,7 +466,12 @@ impl<'de> MapAccess<'de> for Decoder {
                 let next_value = self.key.enum_value(index);
                 match next_value {
                     Some(res) => {
-                        self.cursor = FieldName(index, res?.0);
+                        let name = res?.0;
+                        if name.is_empty() && self.skip_default {
+                            self.cursor = Field(index + 1);
+                            return self.next_key_seed(seed);
+                        }
+                        self.cursor = FieldName(index, self.field_name(name)?);
                         seed.deserialize(&mut *self).map(Some)
                     }
                     None => Ok(None),
@@ -332,7 +489,7 @@ impl<'de> MapAccess<'de> for Decoder {
         match self.cursor {
             KeyVal(index, ref name) => match self.key.open_subkey_with_flags(name, DECODER_SAM) {
                 Ok(subkey) => {
-                    let mut nested = Decoder::new(subkey);
+                    let mut nested = self.nested(subkey);
                     self.cursor = Key(index + 1);
                     seed.deserialize(&mut nested)
                 }
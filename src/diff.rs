@@ -0,0 +1,238 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Comparison of two key trees.
+//!
+//! Both trees are read first, so keys and values are matched by their paths
+//! relative to the compared keys, e.g. the same subtree in the 32-bit and
+//! 64-bit views, or a copy taken before an install.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::diff;
+//!use winreg2::enums::*;
+//!
+//!fn main() -> io::Result<()> {
+//!    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+//!    let v32 = hklm.open_subkey_with_flags("SOFTWARE\\MyProduct", KEY_READ | KEY_WOW64_32KEY)?;
+//!    let v64 = hklm.open_subkey_with_flags("SOFTWARE\\MyProduct", KEY_READ | KEY_WOW64_64KEY)?;
+//!    let diff = diff::diff_keys(&v32, &v64)?;
+//!    print!("{}", diff.to_unified("32-bit", "64-bit"));
+//!    Ok(())
+//!}
+//!```
+use crate::serialization::reg_file;
+use crate::walker::{ErrorPolicy, WalkOptions};
+use crate::{RegKey, RegValue};
+use std::collections::BTreeMap;
+use std::io;
+
+/// Values of every key of a tree by key path, the starting key has an empty path
+pub(crate) type Tree = BTreeMap<String, BTreeMap<String, RegValue>>;
+
+/// A difference between the values of two trees.
+/// Key paths are relative to the compared keys, which have an empty path.
+#[derive(Debug, PartialEq)]
+pub enum ValueDiff {
+    /// Value only in the second tree
+    Added {
+        key: String,
+        name: String,
+        value: RegValue,
+    },
+    /// Value only in the first tree
+    Removed {
+        key: String,
+        name: String,
+        value: RegValue,
+    },
+    /// Value with another type or data in the second tree
+    Changed {
+        key: String,
+        name: String,
+        old: RegValue,
+        new: RegValue,
+    },
+}
+
+impl ValueDiff {
+    /// Path of the key holding the value
+    pub fn key(&self) -> &str {
+        match self {
+            ValueDiff::Added { key, .. }
+            | ValueDiff::Removed { key, .. }
+            | ValueDiff::Changed { key, .. } => key,
+        }
+    }
+}
+
+/// Differences between two trees, returned by `diff_keys`, sorted by key path
+#[derive(Debug, Default, PartialEq)]
+pub struct KeyDiff {
+    /// Keys only in the second tree
+    pub added_keys: Vec<String>,
+    /// Keys only in the first tree
+    pub removed_keys: Vec<String>,
+    /// Values added, removed or changed, including those of added and removed keys
+    pub values: Vec<ValueDiff>,
+}
+
+impl KeyDiff {
+    /// Check whether the trees are the same
+    pub fn is_empty(&self) -> bool {
+        self.added_keys.is_empty() && self.removed_keys.is_empty() && self.values.is_empty()
+    }
+
+    /// Render the differences in the style of a unified diff of two `.reg` files,
+    /// with `a` and `b` naming the compared trees:
+    ///
+    /// ```text
+    /// --- a
+    /// +++ b
+    /// -[Removed]
+    /// -"Name"="old"
+    /// @@ [Changed] @@
+    /// -"Version"=dword:00000001
+    /// +"Version"=dword:00000002
+    /// ```
+    pub fn to_unified(&self, a: &str, b: &str) -> String {
+        let mut out = format!("--- {}\n+++ {}\n", a, b);
+        let mut current: Option<&str> = None;
+        for value in &self.values {
+            let key = value.key();
+            if current != Some(key) {
+                current = Some(key);
+                let header = format!("[{}]", key);
+                if self.added_keys.iter().any(|k| k == key) {
+                    push_lines(&mut out, '+', &header);
+                } else if self.removed_keys.iter().any(|k| k == key) {
+                    push_lines(&mut out, '-', &header);
+                } else {
+                    out.push_str(&format!("@@ {} @@\n", header));
+                }
+            }
+            match value {
+                ValueDiff::Added { name, value, .. } => {
+                    push_lines(&mut out, '+', &reg_file::format_value(name, value))
+                }
+                ValueDiff::Removed { name, value, .. } => {
+                    push_lines(&mut out, '-', &reg_file::format_value(name, value))
+                }
+                ValueDiff::Changed { name, old, new, .. } => {
+                    push_lines(&mut out, '-', &reg_file::format_value(name, old));
+                    push_lines(&mut out, '+', &reg_file::format_value(name, new));
+                }
+            }
+        }
+        // keys without values
+        for key in &self.added_keys {
+            if !self.values.iter().any(|v| v.key() == key) {
+                push_lines(&mut out, '+', &format!("[{}]", key));
+            }
+        }
+        for key in &self.removed_keys {
+            if !self.values.iter().any(|v| v.key() == key) {
+                push_lines(&mut out, '-', &format!("[{}]", key));
+            }
+        }
+        out
+    }
+}
+
+/// Prefix every line of `text`, including continuation lines of long values
+fn push_lines(out: &mut String, prefix: char, text: &str) {
+    for line in text.split("\r\n") {
+        out.push(prefix);
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// Compare the trees of `a` and `b`, reporting what changes from `a` to `b`.
+/// Keys that can't be read are left out of both trees.
+pub fn diff_keys(a: &RegKey, b: &RegKey) -> io::Result<KeyDiff> {
+    Ok(diff_trees(&read_tree(a)?, &read_tree(b)?))
+}
+
+/// Read the values of `key` and all its subkeys, keys that can't be read are left out
+pub(crate) fn read_tree(key: &RegKey) -> io::Result<Tree> {
+    let mut tree = Tree::new();
+    tree.insert(String::new(), key.enum_values().collect::<io::Result<_>>()?);
+    // keys deleted during the walk are left out as well
+    let options = WalkOptions::new().error_policy(ErrorPolicy::Skip);
+    for entry in key.walk_with_options(options) {
+        let path = entry?.path;
+        match key
+            .open_subkey(&path)
+            .and_then(|k| k.enum_values().collect::<io::Result<_>>())
+        {
+            Ok(values) => {
+                tree.insert(path, values);
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(tree)
+}
+
+pub(crate) fn copy_value(value: &RegValue) -> RegValue {
+    RegValue {
+        bytes: value.bytes.clone(),
+        vtype: value.vtype.clone(),
+    }
+}
+
+pub(crate) fn diff_trees(a: &Tree, b: &Tree) -> KeyDiff {
+    let empty = BTreeMap::new();
+    let mut diff = KeyDiff::default();
+    let mut paths: Vec<&String> = a.keys().chain(b.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    for path in paths {
+        let old_values = match a.get(path) {
+            Some(values) => values,
+            None => {
+                diff.added_keys.push(path.clone());
+                &empty
+            }
+        };
+        let new_values = match b.get(path) {
+            Some(values) => values,
+            None => {
+                diff.removed_keys.push(path.clone());
+                &empty
+            }
+        };
+        for (name, old) in old_values {
+            match new_values.get(name) {
+                None => diff.values.push(ValueDiff::Removed {
+                    key: path.clone(),
+                    name: name.clone(),
+                    value: copy_value(old),
+                }),
+                Some(new) if new != old => diff.values.push(ValueDiff::Changed {
+                    key: path.clone(),
+                    name: name.clone(),
+                    old: copy_value(old),
+                    new: copy_value(new),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (name, new) in new_values {
+            if !old_values.contains_key(name) {
+                diff.values.push(ValueDiff::Added {
+                    key: path.clone(),
+                    name: name.clone(),
+                    value: copy_value(new),
+                });
+            }
+        }
+    }
+    diff
+}
@@ -0,0 +1,107 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Mirroring writes into both the 32-bit and 64-bit registry views.
+//!
+//! Registrations under redirected keys such as `HKLM\SOFTWARE\Classes\CLSID`
+//! are only seen by processes of the matching bitness; components serving both
+//! need the same data in each view.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::dual_view;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!
+//!fn main() -> io::Result<()> {
+//!    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+//!    dual_view::write_both(&hklm, "SOFTWARE\\MyProduct", |key| {
+//!        key.set_value("InstallDir", &"C:\\Program Files\\MyProduct")
+//!    })
+//!    .into_result()?;
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::RegKey;
+use std::io;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::System::Threading;
+
+/// Outcome of an operation performed in each registry view
+#[derive(Debug)]
+pub struct ViewResults<T> {
+    /// Result in the 32-bit view, the only view of a 32-bit OS
+    pub v32: io::Result<T>,
+    /// Result in the 64-bit view, `None` on a 32-bit OS
+    pub v64: Option<io::Result<T>>,
+}
+
+impl<T> ViewResults<T> {
+    /// Return both values, or the first error
+    pub fn into_result(self) -> io::Result<(T, Option<T>)> {
+        let v32 = self.v32?;
+        let v64 = match self.v64 {
+            Some(r) => Some(r?),
+            None => None,
+        };
+        Ok((v32, v64))
+    }
+}
+
+/// Create (or open) `path` under `root` in each view with `KEY_ALL_ACCESS`
+/// and run `f` on it. A failure in one view doesn't prevent the other one.
+pub fn write_both<T, F>(root: &RegKey, path: &str, mut f: F) -> ViewResults<T>
+where
+    F: FnMut(&RegKey) -> io::Result<T>,
+{
+    let mut run = |view: RegistryView| {
+        root.create_subkey_view(path, view)
+            .and_then(|(key, _disp)| f(&key))
+    };
+    if is_64bit_os() {
+        ViewResults {
+            v32: run(RegistryView::V32),
+            v64: Some(run(RegistryView::V64)),
+        }
+    } else {
+        ViewResults {
+            v32: run(RegistryView::Default),
+            v64: None,
+        }
+    }
+}
+
+/// Recursively delete `path` under `root` from each view, a missing key is not an error
+pub fn delete_both(root: &RegKey, path: &str) -> ViewResults<()> {
+    let delete = |view: RegistryView| match root.delete_subkey_all_view(path, view) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    };
+    if is_64bit_os() {
+        ViewResults {
+            v32: delete(RegistryView::V32),
+            v64: Some(delete(RegistryView::V64)),
+        }
+    } else {
+        ViewResults {
+            v32: delete(RegistryView::Default),
+            v64: None,
+        }
+    }
+}
+
+/// Check whether the OS has separate 32-bit and 64-bit views
+pub fn is_64bit_os() -> bool {
+    if cfg!(target_pointer_width = "64") {
+        return true;
+    }
+    // a 32-bit process on a 64-bit OS runs under WOW64
+    let mut wow64: Foundation::BOOL = 0;
+    unsafe {
+        Threading::IsWow64Process(Threading::GetCurrentProcess(), &mut wow64) != 0 && wow64 != 0
+    }
+}
@@ -63,30 +63,130 @@ enum EncoderState {
     // NextMapKey,
 }
 
+/// How the `Encoder` writes sequences like `Vec<T>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeqEncoding {
+    /// Sequences of strings and unit enum variants as a `REG_MULTI_SZ` value,
+    /// other sequences, and string sequences with empty strings, as `Indexed`
+    Auto,
+    /// A subkey named after the field with the elements named `0`, `1`, ...,
+    /// values for scalars and subkeys for structs and maps
+    Indexed,
+}
+
+impl Default for SeqEncoding {
+    fn default() -> SeqEncoding {
+        SeqEncoding::Auto
+    }
+}
+
+/// How struct field names map to value and subkey names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamingConvention {
+    /// Use field names as they are
+    AsIs,
+    /// `server_name` as `ServerName`
+    PascalCase,
+    /// `server_name` as `serverName`
+    CamelCase,
+}
+
+impl Default for NamingConvention {
+    fn default() -> NamingConvention {
+        NamingConvention::AsIs
+    }
+}
+
+impl NamingConvention {
+    /// Registry name of the snake_case field `name`
+    pub fn apply(self, name: &str) -> String {
+        if self == NamingConvention::AsIs {
+            return name.to_owned();
+        }
+        let mut out = String::with_capacity(name.len());
+        for word in name.split('_').filter(|w| !w.is_empty()) {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap();
+            if out.is_empty() && self == NamingConvention::CamelCase {
+                out.extend(first.to_lowercase());
+            } else {
+                out.extend(first.to_uppercase());
+            }
+            out.push_str(chars.as_str());
+        }
+        out
+    }
+}
+
+/// Options of `Encoder::from_key_with_options`, created with `EncoderOptions::new()`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EncoderOptions {
+    seq_encoding: SeqEncoding,
+    u64_as_string: bool,
+    naming_convention: NamingConvention,
+}
+
+impl EncoderOptions {
+    pub fn new() -> EncoderOptions {
+        EncoderOptions::default()
+    }
+
+    /// How sequences are written, `SeqEncoding::Auto` by default.
+    /// A sequence at the top level always has its elements written to the key itself
+    /// as with `SeqEncoding::Indexed`.
+    pub fn seq_encoding(mut self, seq_encoding: SeqEncoding) -> Self {
+        self.seq_encoding = seq_encoding;
+        self
+    }
+
+    /// Write `u64` values as decimal `REG_SZ` strings instead of `REG_QWORD`,
+    /// the `Decoder` reads both
+    pub fn u64_as_string(mut self, as_string: bool) -> Self {
+        self.u64_as_string = as_string;
+        self
+    }
+
+    /// Naming of the values and subkeys of struct fields, `NamingConvention::AsIs`
+    /// by default. Map keys are written as they are.
+    pub fn naming_convention(mut self, naming_convention: NamingConvention) -> Self {
+        self.naming_convention = naming_convention;
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct Encoder<Tr: AsRef<Transaction>> {
     keys: Vec<RegKey>,
     tr: Tr,
     state: EncoderState,
+    options: EncoderOptions,
 }
 
 const ENCODER_SAM: u32 = KEY_CREATE_SUB_KEY | KEY_SET_VALUE;
 
 impl Encoder<Transaction> {
     pub fn from_key(key: &RegKey) -> EncodeResult<Encoder<Transaction>> {
+        Encoder::from_key_with_options(key, EncoderOptions::new())
+    }
+
+    pub fn from_key_with_options(
+        key: &RegKey,
+        options: EncoderOptions,
+    ) -> EncodeResult<Encoder<Transaction>> {
         let tr = Transaction::new()?;
         key.open_subkey_transacted_with_flags("", &tr, ENCODER_SAM)
-            .map(|k| Encoder::new(k, tr))
+            .map(|k| Encoder::new(k, tr, options))
             .map_err(EncoderError::IoError)
     }
 
-    fn new(key: RegKey, tr: Transaction) -> Encoder<Transaction> {
+    fn new(key: RegKey, tr: Transaction, options: EncoderOptions) -> Encoder<Transaction> {
         let mut keys = Vec::with_capacity(5);
         keys.push(key);
         Encoder {
             keys,
             tr,
             state: Start,
+            options,
         }
     }
 
@@ -99,19 +199,32 @@ impl Encoder<&Transaction> {
     pub fn from_key_transacted<'a>(
         key: &RegKey,
         tr: &'a Transaction,
+    ) -> EncodeResult<Encoder<&'a Transaction>> {
+        Encoder::from_key_transacted_with_options(key, tr, EncoderOptions::new())
+    }
+
+    pub fn from_key_transacted_with_options<'a>(
+        key: &RegKey,
+        tr: &'a Transaction,
+        options: EncoderOptions,
     ) -> EncodeResult<Encoder<&'a Transaction>> {
         key.open_subkey_transacted_with_flags("", tr, ENCODER_SAM)
-            .map(|k| Encoder::new_transacted(k, tr))
+            .map(|k| Encoder::new_transacted(k, tr, options))
             .map_err(EncoderError::IoError)
     }
 
-    fn new_transacted(key: RegKey, tr: &Transaction) -> Encoder<&Transaction> {
+    fn new_transacted(
+        key: RegKey,
+        tr: &Transaction,
+        options: EncoderOptions,
+    ) -> Encoder<&Transaction> {
         let mut keys = Vec::with_capacity(5);
         keys.push(key);
         Encoder {
             keys,
             tr,
             state: Start,
+            options,
         }
     }
 }
@@ -4,7 +4,7 @@
 // may not be copied, modified, or distributed
 // except according to those terms.
 use super::EncoderState::*;
-use super::{EncodeResult, Encoder, EncoderError, ENCODER_SAM};
+use super::{EncodeResult, Encoder, EncoderError, SeqEncoding, ENCODER_SAM};
 use crate::enums::RegType;
 use crate::transaction::Transaction;
 use crate::RegValue;
@@ -22,13 +22,13 @@ impl<'a, Tr: AsRef<Transaction>> Serializer for &'a mut Encoder<Tr> {
     type Ok = ();
     type Error = EncoderError;
 
-    type SerializeSeq = SeqEncoder;
+    type SerializeSeq = SeqEncoder<'a, Tr>;
     type SerializeTuple = TupleEncoder;
     type SerializeTupleStruct = TupleStructEncoder;
-    type SerializeTupleVariant = TupleVariantEncoder;
+    type SerializeTupleVariant = TupleVariantEncoder<'a, Tr>;
     type SerializeMap = StructMapEncoder<'a, Tr>;
     type SerializeStruct = StructMapEncoder<'a, Tr>;
-    type SerializeStructVariant = StructVariantEncoder;
+    type SerializeStructVariant = StructVariantEncoder<'a, Tr>;
 
     fn serialize_bool(self, value: bool) -> EncodeResult<Self::Ok> {
         self.serialize_u32(value as u32)
@@ -64,6 +64,10 @@ impl<'a, Tr: AsRef<Transaction>> Serializer for &'a mut Encoder<Tr> {
     }
 
     fn serialize_u64(self, value: u64) -> EncodeResult<Self::Ok> {
+        if self.options.u64_as_string {
+            let value = value.to_string();
+            return emit_value!(self, value);
+        }
         emit_value!(self, value)
     }
 
@@ -125,9 +129,9 @@ impl<'a, Tr: AsRef<Transaction>> Serializer for &'a mut Encoder<Tr> {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> EncodeResult<Self::Ok> {
-        no_impl!("serialize_unit_variant")
+        emit_value!(self, variant)
     }
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
@@ -142,14 +146,35 @@ impl<'a, Tr: AsRef<Transaction>> Serializer for &'a mut Encoder<Tr> {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _value: &T,
+        variant: &'static str,
+        value: &T,
     ) -> EncodeResult<Self::Ok> {
-        no_impl!("serialize_newtype_variant")
+        let subkey = self.begin_variant(variant)?;
+        self.state = NextKey("0".to_owned());
+        value.serialize(&mut *self)?;
+        if subkey {
+            self.keys.pop();
+        }
+        Ok(())
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> EncodeResult<Self::SerializeSeq> {
-        no_impl!("serialize_seq")
+        let name = match mem::replace(&mut self.state, Start) {
+            NextKey(s) => Some(s),
+            // the elements of a root sequence are written to the root key
+            Start => None,
+        };
+        let strings = match (&name, self.options.seq_encoding) {
+            (Some(_), SeqEncoding::Auto) => Some(Vec::new()),
+            _ => None,
+        };
+        Ok(SeqEncoder {
+            enc: self,
+            name,
+            strings,
+            index: 0,
+            subkey: false,
+        })
     }
 
     fn serialize_tuple(self, _len: usize) -> EncodeResult<Self::SerializeTuple> {
@@ -168,10 +193,15 @@ impl<'a, Tr: AsRef<Transaction>> Serializer for &'a mut Encoder<Tr> {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> EncodeResult<Self::SerializeTupleVariant> {
-        no_impl!("serialize_tuple_variant")
+        let subkey = self.begin_variant(variant)?;
+        Ok(TupleVariantEncoder {
+            enc: self,
+            index: 0,
+            subkey,
+        })
     }
 
     fn serialize_map(self, _len: Option<usize>) -> EncodeResult<Self::SerializeMap> {
@@ -216,23 +246,106 @@ impl<'a, Tr: AsRef<Transaction>> Serializer for &'a mut Encoder<Tr> {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> EncodeResult<Self::SerializeStructVariant> {
-        no_impl!("serialize_struct_variant")
+        let subkey = self.begin_variant(variant)?;
+        Ok(StructVariantEncoder { enc: self, subkey })
     }
 }
 
-pub struct SeqEncoder {}
+impl<Tr: AsRef<Transaction>> Encoder<Tr> {
+    /// Start a variant with data: create the subkey of the field, or use the current key
+    /// at the top level, and write the variant name to its default value.
+    /// Returns whether a subkey was pushed.
+    fn begin_variant(&mut self, variant: &str) -> EncodeResult<bool> {
+        let subkey = match mem::replace(&mut self.state, Start) {
+            NextKey(ref s) => {
+                let (subkey, _disp) = self.keys[self.keys.len() - 1]
+                    .create_subkey_transacted_with_flags(s, self.tr.as_ref(), ENCODER_SAM)?;
+                self.keys.push(subkey);
+                true
+            }
+            Start => false,
+        };
+        self.keys[self.keys.len() - 1].set_value("", &variant)?;
+        Ok(subkey)
+    }
+}
 
-impl SerializeSeq for SeqEncoder {
+pub struct SeqEncoder<'a, Tr: AsRef<Transaction>> {
+    enc: &'a mut Encoder<Tr>,
+    name: Option<String>,
+    /// Elements collected for a `REG_MULTI_SZ` value, `None` when writing indexed elements
+    strings: Option<Vec<String>>,
+    index: usize,
+    subkey: bool,
+}
+
+impl<Tr: AsRef<Transaction>> SeqEncoder<'_, Tr> {
+    /// Switch to indexed elements, writing the strings collected so far
+    fn switch_to_indexed(&mut self) -> EncodeResult<()> {
+        let strings = self.strings.take().unwrap_or_default();
+        let name = self.name.as_ref().ok_or(EncoderError::NoFieldName)?;
+        let enc = &mut *self.enc;
+        let (subkey, _disp) = enc.keys[enc.keys.len() - 1].create_subkey_transacted_with_flags(
+            name,
+            enc.tr.as_ref(),
+            ENCODER_SAM,
+        )?;
+        for s in &strings {
+            subkey.set_value(self.index.to_string(), s)?;
+            self.index += 1;
+        }
+        enc.keys.push(subkey);
+        self.subkey = true;
+        Ok(())
+    }
+}
+
+impl<Tr: AsRef<Transaction>> SerializeSeq for SeqEncoder<'_, Tr> {
     type Ok = ();
     type Error = EncoderError;
-    fn serialize_element<T: ?Sized + Serialize>(&mut self, _value: &T) -> EncodeResult<Self::Ok> {
-        no_impl!("SerializeSeq::serialize_element")
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> EncodeResult<Self::Ok> {
+        if let Some(ref mut strings) = self.strings {
+            match value.serialize(MultiSzElementSerializer) {
+                // REG_MULTI_SZ can't hold empty strings
+                Ok(s) if !s.is_empty() => {
+                    strings.push(s);
+                    return Ok(());
+                }
+                _ => self.switch_to_indexed()?,
+            }
+        } else if self.name.is_some() && !self.subkey {
+            self.switch_to_indexed()?;
+        }
+        self.enc.state = NextKey(self.index.to_string());
+        self.index += 1;
+        value.serialize(&mut *self.enc)
     }
+
     fn end(self) -> EncodeResult<Self::Ok> {
-        no_impl!("SerializeSeq::end")
+        match (self.strings, self.name) {
+            (Some(strings), Some(name)) => self.enc.keys[self.enc.keys.len() - 1]
+                .set_value(name, &strings)
+                .map_err(EncoderError::IoError),
+            (_, Some(name)) if !self.subkey => {
+                // empty indexed sequence
+                self.enc.keys[self.enc.keys.len() - 1].create_subkey_transacted_with_flags(
+                    name,
+                    self.enc.tr.as_ref(),
+                    ENCODER_SAM,
+                )?;
+                Ok(())
+            }
+            _ => {
+                if self.subkey {
+                    self.enc.keys.pop();
+                }
+                Ok(())
+            }
+        }
     }
 }
 
@@ -266,18 +379,161 @@ impl SerializeTupleStruct for TupleStructEncoder {
     }
 }
 
-pub struct TupleVariantEncoder {}
+pub struct TupleVariantEncoder<'a, Tr: AsRef<Transaction>> {
+    enc: &'a mut Encoder<Tr>,
+    index: usize,
+    subkey: bool,
+}
 
-impl SerializeTupleVariant for TupleVariantEncoder {
+impl<Tr: AsRef<Transaction>> SerializeTupleVariant for TupleVariantEncoder<'_, Tr> {
     type Ok = ();
     type Error = EncoderError;
 
-    fn serialize_field<T: ?Sized + Serialize>(&mut self, _value: &T) -> EncodeResult<Self::Ok> {
-        no_impl!("SerializeTupleVariant::serialize_field")
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> EncodeResult<Self::Ok> {
+        self.enc.state = NextKey(self.index.to_string());
+        self.index += 1;
+        value.serialize(&mut *self.enc)
     }
 
     fn end(self) -> EncodeResult<Self::Ok> {
-        no_impl!("SerializeTupleVariant::end")
+        if self.subkey {
+            self.enc.keys.pop();
+        }
+        Ok(())
+    }
+}
+
+/// Accepts the elements `SeqEncoding::Auto` writes to `REG_MULTI_SZ`
+struct MultiSzElementSerializer;
+
+fn not_a_string() -> EncoderError {
+    EncoderError::SerializerError("not a string".to_owned())
+}
+
+macro_rules! not_string_fns {
+    ($($f:ident($($t:ty),*);)*) => {
+        $(
+            fn $f(self, $(_: $t),*) -> EncodeResult<String> {
+                Err(not_a_string())
+            }
+        )*
+    };
+}
+
+impl serde::Serializer for MultiSzElementSerializer {
+    type Ok = String;
+    type Error = EncoderError;
+
+    type SerializeSeq = Impossible<String, EncoderError>;
+    type SerializeTuple = Impossible<String, EncoderError>;
+    type SerializeTupleStruct = Impossible<String, EncoderError>;
+    type SerializeTupleVariant = Impossible<String, EncoderError>;
+    type SerializeMap = Impossible<String, EncoderError>;
+    type SerializeStruct = Impossible<String, EncoderError>;
+    type SerializeStructVariant = Impossible<String, EncoderError>;
+
+    fn serialize_str(self, value: &str) -> EncodeResult<String> {
+        Ok(value.to_owned())
+    }
+
+    fn serialize_char(self, value: char) -> EncodeResult<String> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> EncodeResult<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    not_string_fns! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> EncodeResult<String> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> EncodeResult<String> {
+        Err(not_a_string())
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> EncodeResult<String> {
+        Err(not_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> EncodeResult<Self::SerializeSeq> {
+        Err(not_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> EncodeResult<Self::SerializeTuple> {
+        Err(not_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> EncodeResult<Self::SerializeTupleStruct> {
+        Err(not_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> EncodeResult<Self::SerializeTupleVariant> {
+        Err(not_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> EncodeResult<Self::SerializeMap> {
+        Err(not_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> EncodeResult<Self::SerializeStruct> {
+        Err(not_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> EncodeResult<Self::SerializeStructVariant> {
+        Err(not_a_string())
     }
 }
 
@@ -477,7 +733,7 @@ impl<Tr: AsRef<Transaction>> SerializeStruct for StructMapEncoder<'_, Tr> {
         key: &'static str,
         value: &T,
     ) -> EncodeResult<Self::Ok> {
-        self.enc.state = NextKey(String::from(key));
+        self.enc.state = NextKey(self.enc.options.naming_convention.apply(key));
         value.serialize(&mut *self.enc)
     }
 
@@ -510,21 +766,28 @@ impl<Tr: AsRef<Transaction>> SerializeMap for StructMapEncoder<'_, Tr> {
     }
 }
 
-pub struct StructVariantEncoder {}
+pub struct StructVariantEncoder<'a, Tr: AsRef<Transaction>> {
+    enc: &'a mut Encoder<Tr>,
+    subkey: bool,
+}
 
-impl SerializeStructVariant for StructVariantEncoder {
+impl<Tr: AsRef<Transaction>> SerializeStructVariant for StructVariantEncoder<'_, Tr> {
     type Ok = ();
     type Error = EncoderError;
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _key: &'static str,
-        _value: &T,
+        key: &'static str,
+        value: &T,
     ) -> EncodeResult<Self::Ok> {
-        no_impl!("SerializeStructVariant::serialize_field")
+        self.enc.state = NextKey(self.enc.options.naming_convention.apply(key));
+        value.serialize(&mut *self.enc)
     }
 
     fn end(self) -> EncodeResult<Self::Ok> {
-        no_impl!("SerializeStructVariant::end")
+        if self.subkey {
+            self.enc.keys.pop();
+        }
+        Ok(())
     }
 }
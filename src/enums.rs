@@ -5,14 +5,27 @@
 // except according to those terms.
 
 //! `use winreg2::enums::*;` to import all needed enumerations and constants
+pub use windows_sys::Win32::Security::{
+    DACL_SECURITY_INFORMATION, GROUP_SECURITY_INFORMATION, LABEL_SECURITY_INFORMATION,
+    OWNER_SECURITY_INFORMATION, PROTECTED_DACL_SECURITY_INFORMATION, SACL_SECURITY_INFORMATION,
+    UNPROTECTED_DACL_SECURITY_INFORMATION,
+};
+pub use windows_sys::Win32::Storage::FileSystem::{READ_CONTROL, WRITE_DAC, WRITE_OWNER};
 pub use windows_sys::Win32::System::Registry::{
     HKEY_CLASSES_ROOT, HKEY_CURRENT_CONFIG, HKEY_CURRENT_USER, HKEY_CURRENT_USER_LOCAL_SETTINGS,
     HKEY_DYN_DATA, HKEY_LOCAL_MACHINE, HKEY_PERFORMANCE_DATA, HKEY_PERFORMANCE_NLSTEXT,
     HKEY_PERFORMANCE_TEXT, HKEY_USERS, KEY_ALL_ACCESS, KEY_CREATE_LINK, KEY_CREATE_SUB_KEY,
     KEY_ENUMERATE_SUB_KEYS, KEY_EXECUTE, KEY_NOTIFY, KEY_QUERY_VALUE, KEY_READ, KEY_SET_VALUE,
-    KEY_WOW64_32KEY, KEY_WOW64_64KEY, KEY_WOW64_RES, KEY_WRITE, REG_PROCESS_APPKEY,
+    KEY_WOW64_32KEY, KEY_WOW64_64KEY, KEY_WOW64_RES, KEY_WRITE, REG_FORCE_RESTORE,
+    REG_OPTION_BACKUP_RESTORE, REG_OPTION_CREATE_LINK, REG_OPTION_NON_VOLATILE,
+    REG_OPTION_OPEN_LINK, REG_OPTION_VOLATILE, REG_PROCESS_APPKEY, REG_WHOLE_HIVE_VOLATILE,
+    RRF_NOEXPAND, RRF_RT_ANY, RRF_RT_DWORD, RRF_RT_QWORD, RRF_RT_REG_BINARY, RRF_RT_REG_DWORD,
+    RRF_RT_REG_EXPAND_SZ, RRF_RT_REG_MULTI_SZ, RRF_RT_REG_NONE, RRF_RT_REG_QWORD, RRF_RT_REG_SZ,
+    RRF_SUBKEY_WOW6432KEY, RRF_SUBKEY_WOW6464KEY, RRF_ZEROONFAILURE,
 };
 
+use windows_sys::Win32::System::Registry;
+
 macro_rules! winapi_enum{
     ($t:ident, $doc:expr => [$($v:ident),*]) => (
         #[doc=$doc]
@@ -32,24 +45,252 @@ winapi_enum!(NotifyFilter, "Enumeration of possible changes that should be repor
     REG_NOTIFY_THREAD_AGNOSTIC
 ]);
 
-winapi_enum!(RegType, "Enumeration of possible registry value types" => [
-REG_NONE,
-REG_SZ,
-REG_EXPAND_SZ,
-REG_BINARY,
-REG_DWORD,
-REG_DWORD_BIG_ENDIAN,
-REG_LINK,
-REG_MULTI_SZ,
-REG_RESOURCE_LIST,
-REG_FULL_RESOURCE_DESCRIPTOR,
-REG_RESOURCE_REQUIREMENTS_LIST,
-REG_QWORD
-]);
+/// Enumeration of possible registry value types.
+///
+/// Types without a variant of their own, e.g. vendor-specific ones, are kept in `Unknown`,
+/// so such values can be read and written back unchanged.
+/// Convert from and to the raw `u32` with `From`, `Unknown` never holds
+/// the code of a type with its own variant.
+/// With the `serialization-serde` feature human-readable formats get the name
+/// of the type, e.g. `"REG_SZ"`, or the number of an `Unknown` type, others the number.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RegType {
+    REG_NONE,
+    REG_SZ,
+    REG_EXPAND_SZ,
+    REG_BINARY,
+    REG_DWORD,
+    REG_DWORD_BIG_ENDIAN,
+    REG_LINK,
+    REG_MULTI_SZ,
+    REG_RESOURCE_LIST,
+    REG_FULL_RESOURCE_DESCRIPTOR,
+    REG_RESOURCE_REQUIREMENTS_LIST,
+    REG_QWORD,
+    /// Any other type
+    Unknown(UnknownType),
+}
+
+/// Code of a value type without a variant in `RegType`, created with `RegType::from`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnknownType(u32);
+
+impl UnknownType {
+    /// The raw type code
+    pub fn code(self) -> u32 {
+        self.0
+    }
+}
+
+macro_rules! reg_type_conversions {
+    ($($v:ident),*) => {
+        impl From<u32> for RegType {
+            fn from(vtype: u32) -> RegType {
+                match vtype {
+                    $( Registry::$v => RegType::$v, )*
+                    other => RegType::Unknown(UnknownType(other)),
+                }
+            }
+        }
+
+        impl From<RegType> for u32 {
+            fn from(vtype: RegType) -> u32 {
+                match vtype {
+                    $( RegType::$v => Registry::$v, )*
+                    RegType::Unknown(other) => other.0,
+                }
+            }
+        }
+
+        #[cfg(feature = "serialization-serde")]
+        impl RegType {
+            /// Name of the type, `None` for `Unknown`
+            fn name(&self) -> Option<&'static str> {
+                match self {
+                    $( RegType::$v => Some(stringify!($v)), )*
+                    RegType::Unknown(_) => None,
+                }
+            }
+
+            fn from_name(name: &str) -> Option<RegType> {
+                match name {
+                    $( stringify!($v) => Some(RegType::$v), )*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+reg_type_conversions!(
+    REG_NONE,
+    REG_SZ,
+    REG_EXPAND_SZ,
+    REG_BINARY,
+    REG_DWORD,
+    REG_DWORD_BIG_ENDIAN,
+    REG_LINK,
+    REG_MULTI_SZ,
+    REG_RESOURCE_LIST,
+    REG_FULL_RESOURCE_DESCRIPTOR,
+    REG_RESOURCE_REQUIREMENTS_LIST,
+    REG_QWORD
+);
+
 pub use self::RegType::*;
 
+#[cfg(feature = "serialization-serde")]
+mod serialization_serde {
+    use super::RegType;
+    use serde::de::{self, Deserialize, Deserializer, Visitor};
+    use serde::ser::{Serialize, Serializer};
+    use std::fmt;
+
+    impl Serialize for RegType {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.name() {
+                Some(name) if serializer.is_human_readable() => serializer.serialize_str(name),
+                _ => serializer.serialize_u32(u32::from(self.clone())),
+            }
+        }
+    }
+
+    struct RegTypeVisitor;
+
+    impl<'de> Visitor<'de> for RegTypeVisitor {
+        type Value = RegType;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a registry value type name or number")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<RegType, E> {
+            u32::try_from(v)
+                .map(RegType::from)
+                .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<RegType, E> {
+            u32::try_from(v)
+                .map(RegType::from)
+                .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<RegType, E> {
+            RegType::from_name(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for RegType {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RegType, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_any(RegTypeVisitor)
+            } else {
+                deserializer.deserialize_u32(RegTypeVisitor)
+            }
+        }
+    }
+}
+
 winapi_enum!(RegDisposition, "Enumeration of possible disposition values" => [
 REG_CREATED_NEW_KEY,
 REG_OPENED_EXISTING_KEY
 ]);
 pub use self::RegDisposition::*;
+
+/// Registry view to operate on, see
+/// [Registry Redirector](https://learn.microsoft.com/en-us/windows/win32/winprog64/registry-redirector).
+/// On 32-bit Windows both views are the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegistryView {
+    /// The view native to the calling process
+    Default,
+    /// The 32-bit view (`KEY_WOW64_32KEY`), e.g. `Wow6432Node` for a 64-bit process
+    V32,
+    /// The 64-bit view (`KEY_WOW64_64KEY`), also accessible from 32-bit processes
+    V64,
+}
+
+impl RegistryView {
+    /// Access mask bits selecting this view, to be combined with the desired permissions
+    pub const fn flags(self) -> windows_sys::Win32::System::Registry::REG_SAM_FLAGS {
+        match self {
+            RegistryView::Default => 0,
+            RegistryView::V32 => KEY_WOW64_32KEY,
+            RegistryView::V64 => KEY_WOW64_64KEY,
+        }
+    }
+}
+
+impl Default for RegistryView {
+    fn default() -> RegistryView {
+        RegistryView::Default
+    }
+}
+
+/// Long and short names of the predefined keys
+const ROOTS: &[(&str, &str, windows_sys::Win32::System::Registry::HKEY)] = &[
+    ("HKEY_CLASSES_ROOT", "HKCR", HKEY_CLASSES_ROOT),
+    ("HKEY_CURRENT_USER", "HKCU", HKEY_CURRENT_USER),
+    ("HKEY_LOCAL_MACHINE", "HKLM", HKEY_LOCAL_MACHINE),
+    ("HKEY_USERS", "HKU", HKEY_USERS),
+    ("HKEY_CURRENT_CONFIG", "HKCC", HKEY_CURRENT_CONFIG),
+    ("HKEY_PERFORMANCE_DATA", "HKPD", HKEY_PERFORMANCE_DATA),
+    ("HKEY_PERFORMANCE_TEXT", "HKPT", HKEY_PERFORMANCE_TEXT),
+    ("HKEY_PERFORMANCE_NLSTEXT", "HKPN", HKEY_PERFORMANCE_NLSTEXT),
+    (
+        "HKEY_CURRENT_USER_LOCAL_SETTINGS",
+        "HKCULS",
+        HKEY_CURRENT_USER_LOCAL_SETTINGS,
+    ),
+    ("HKEY_DYN_DATA", "HKDD", HKEY_DYN_DATA),
+];
+
+/// Long name of a predefined key, e.g. `HKEY_LOCAL_MACHINE`
+pub fn root_name(hkey: windows_sys::Win32::System::Registry::HKEY) -> Option<&'static str> {
+    ROOTS
+        .iter()
+        .find(|&&(_, _, root)| root == hkey)
+        .map(|&(long, _, _)| long)
+}
+
+/// Predefined key with the given long (`HKEY_LOCAL_MACHINE`) or short (`HKLM`) name,
+/// compared case-insensitively
+pub fn root_from_name(name: &str) -> Option<windows_sys::Win32::System::Registry::HKEY> {
+    ROOTS
+        .iter()
+        .find(|&&(long, short, _)| {
+            name.eq_ignore_ascii_case(long) || name.eq_ignore_ascii_case(short)
+        })
+        .map(|&(_, _, hkey)| hkey)
+}
+
+/// Format of the hive files written by `RegKey::save_to_file`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HiveFormat {
+    /// Readable by every Windows version
+    Standard,
+    /// Smaller and faster to load, readable since Windows XP
+    Latest,
+    /// `Latest` without compressing free space, only for whole hives
+    /// such as `HKEY_USERS\<SID>`
+    NoCompression,
+}
+
+impl HiveFormat {
+    /// Flags of `RegSaveKeyExW` selecting this format
+    pub const fn flags(self) -> windows_sys::Win32::System::Registry::REG_SAVE_FORMAT {
+        match self {
+            HiveFormat::Standard => windows_sys::Win32::System::Registry::REG_STANDARD_FORMAT,
+            HiveFormat::Latest => windows_sys::Win32::System::Registry::REG_LATEST_FORMAT,
+            HiveFormat::NoCompression => windows_sys::Win32::System::Registry::REG_NO_COMPRESSION,
+        }
+    }
+}
+
+impl Default for HiveFormat {
+    fn default() -> HiveFormat {
+        HiveFormat::Latest
+    }
+}
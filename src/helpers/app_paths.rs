@@ -0,0 +1,185 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `App Paths` registration and Default Programs integration.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::app_paths::{self, Capabilities};
+//!use winreg2::helpers::Scope;
+//!
+//!fn main() -> io::Result<()> {
+//!    // `Win+R myapp` now starts C:\Program Files\MyApp\myapp.exe
+//!    app_paths::register("myapp.exe", "C:\\Program Files\\MyApp\\myapp.exe", &["C:\\Program Files\\MyApp\\lib"])?;
+//!
+//!    Capabilities::new("MyApp", "Software\\ACME\\MyApp\\Capabilities")
+//!        .description("My application")
+//!        .file_association(".myext", "MyApp.Document")
+//!        .url_association("myapp", "MyApp.Url")
+//!        .register(Scope::LocalMachine)?;
+//!    Ok(())
+//!}
+//!```
+use super::{delete_value_if_exists, ignore_not_found, Scope};
+use crate::enums::*;
+use std::io;
+
+/// Path of the `App Paths` key relative to `HKCU` or `HKLM`
+pub const APP_PATHS_PATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\App Paths";
+/// Path of the `RegisteredApplications` key relative to `HKCU` or `HKLM`
+pub const REGISTERED_APPLICATIONS_PATH: &str = "Software\\RegisteredApplications";
+
+/// Register `exe_name` (e.g. `myapp.exe`) per-machine, so it can be started
+/// by name from the Run dialog and `ShellExecute`.
+/// `path_dirs` are prepended to `PATH` of the started process.
+pub fn register<S: AsRef<str>>(exe_name: &str, full_path: &str, path_dirs: &[S]) -> io::Result<()> {
+    register_in(Scope::LocalMachine, exe_name, full_path, path_dirs)
+}
+
+/// Same as `register`, in the given scope
+pub fn register_in<S: AsRef<str>>(
+    scope: Scope,
+    exe_name: &str,
+    full_path: &str,
+    path_dirs: &[S],
+) -> io::Result<()> {
+    let (key, _disp) = scope
+        .root()
+        .create_subkey(format!("{}\\{}", APP_PATHS_PATH, exe_name))?;
+    key.set_value("", &full_path)?;
+    if path_dirs.is_empty() {
+        delete_value_if_exists(&key, "Path")
+    } else {
+        let dirs: Vec<&str> = path_dirs.iter().map(AsRef::as_ref).collect();
+        key.set_value("Path", &dirs.join(";"))
+    }
+}
+
+/// Remove a per-machine `App Paths` entry
+pub fn unregister(exe_name: &str) -> io::Result<()> {
+    unregister_in(Scope::LocalMachine, exe_name)
+}
+
+/// Same as `unregister`, in the given scope
+pub fn unregister_in(scope: Scope, exe_name: &str) -> io::Result<()> {
+    scope
+        .root()
+        .open_subkey_with_flags(APP_PATHS_PATH, KEY_ALL_ACCESS)?
+        .delete_subkey_all(exe_name)
+}
+
+/// Return the executable path registered for `exe_name`, looking in `HKCU` first
+pub fn lookup(exe_name: &str) -> io::Result<String> {
+    let path = format!("{}\\{}", APP_PATHS_PATH, exe_name);
+    match Scope::CurrentUser.root().open_subkey(&path) {
+        Ok(key) => key.get_value(""),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            Scope::LocalMachine.root().open_subkey(&path)?.get_value("")
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Builder for an application `Capabilities` key listed under `RegisteredApplications`,
+/// which makes the application show up in Default Programs / Default Apps
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    app_name: String,
+    key_path: String,
+    description: Option<String>,
+    icon: Option<String>,
+    file_associations: Vec<(String, String)>,
+    url_associations: Vec<(String, String)>,
+}
+
+impl Capabilities {
+    /// `app_name` is the name under `RegisteredApplications`,
+    /// `key_path` is where the capabilities are written, relative to the scope root
+    pub fn new<N: Into<String>, P: Into<String>>(app_name: N, key_path: P) -> Capabilities {
+        Capabilities {
+            app_name: app_name.into(),
+            key_path: key_path.into(),
+            description: None,
+            icon: None,
+            file_associations: Vec::new(),
+            url_associations: Vec::new(),
+        }
+    }
+
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Icon resource, e.g. `C:\Program Files\MyApp\myapp.exe,0`
+    pub fn icon<S: Into<String>>(mut self, icon: S) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Declare that the ProgID `prog_id` can open files with `extension` (e.g. `.txt`)
+    pub fn file_association<E: Into<String>, P: Into<String>>(
+        mut self,
+        extension: E,
+        prog_id: P,
+    ) -> Self {
+        self.file_associations
+            .push((extension.into(), prog_id.into()));
+        self
+    }
+
+    /// Declare that the ProgID `prog_id` can handle the URL `scheme` (e.g. `http`)
+    pub fn url_association<S: Into<String>, P: Into<String>>(
+        mut self,
+        scheme: S,
+        prog_id: P,
+    ) -> Self {
+        self.url_associations.push((scheme.into(), prog_id.into()));
+        self
+    }
+
+    /// Write the `Capabilities` key and list it under `RegisteredApplications`
+    pub fn register(&self, scope: Scope) -> io::Result<()> {
+        let root = scope.root();
+        let (key, _disp) = root.create_subkey(&self.key_path)?;
+        key.set_value("ApplicationName", &self.app_name)?;
+        match self.description {
+            Some(ref d) => key.set_value("ApplicationDescription", d)?,
+            None => delete_value_if_exists(&key, "ApplicationDescription")?,
+        }
+        match self.icon {
+            Some(ref i) => key.set_value("ApplicationIcon", i)?,
+            None => delete_value_if_exists(&key, "ApplicationIcon")?,
+        }
+        for &(name, list) in &[
+            ("FileAssociations", &self.file_associations),
+            ("URLAssociations", &self.url_associations),
+        ] {
+            key.delete_subkey_all(name).or_else(ignore_not_found)?;
+            if list.is_empty() {
+                continue;
+            }
+            let (sub, _disp) = key.create_subkey(name)?;
+            for (k, prog_id) in list.iter() {
+                sub.set_value(k, prog_id)?;
+            }
+        }
+        let (registered, _disp) = root.create_subkey(REGISTERED_APPLICATIONS_PATH)?;
+        registered.set_value(&self.app_name, &self.key_path)
+    }
+
+    /// Remove the application from `RegisteredApplications` and delete its `Capabilities` key
+    pub fn unregister(&self, scope: Scope) -> io::Result<()> {
+        let root = scope.root();
+        match root.open_subkey_with_flags(REGISTERED_APPLICATIONS_PATH, KEY_SET_VALUE) {
+            Ok(registered) => delete_value_if_exists(&registered, &self.app_name)?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        root.delete_subkey_all(&self.key_path)
+            .or_else(ignore_not_found)
+    }
+}
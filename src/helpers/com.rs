@@ -0,0 +1,215 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! COM class and type library registration under `Software\Classes`.
+//!
+//! `HKCU` registrations only affect the current user and don't require elevation.
+//! `wow64_32` selects the 32-bit view of `CLSID` and `TypeLib` (`Wow6432Node`),
+//! which is where 32-bit clients look for their servers.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::com::{self, ThreadingModel};
+//!use winreg2::helpers::Scope;
+//!
+//!fn main() -> io::Result<()> {
+//!    let clsid = "{0B0C1D58-3D4A-4A0E-8E5E-2B7B6C8D9E10}";
+//!    com::register_inproc_server(
+//!        Scope::CurrentUser,
+//!        false,
+//!        clsid,
+//!        "C:\\Program Files\\MyApp\\server.dll",
+//!        ThreadingModel::Both,
+//!        Some("MyApp.Server.1"),
+//!    )?;
+//!    com::unregister_server(Scope::CurrentUser, false, clsid, Some("MyApp.Server.1"))?;
+//!    Ok(())
+//!}
+//!```
+use super::{ignore_not_found, wow64_view, Scope};
+use crate::enums::*;
+use crate::RegKey;
+use std::io;
+use windows_sys::Win32::Foundation;
+
+/// Path of the classes key relative to `HKCU` or `HKLM`
+pub const CLASSES_PATH: &str = "Software\\Classes";
+
+/// `ThreadingModel` of an in-process server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadingModel {
+    Apartment,
+    Free,
+    Both,
+    Neutral,
+}
+
+impl ThreadingModel {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ThreadingModel::Apartment => "Apartment",
+            ThreadingModel::Free => "Free",
+            ThreadingModel::Both => "Both",
+            ThreadingModel::Neutral => "Neutral",
+        }
+    }
+}
+
+/// Register an in-process server (`InprocServer32`) and optionally its ProgID
+pub fn register_inproc_server(
+    scope: Scope,
+    wow64_32: bool,
+    clsid: &str,
+    dll_path: &str,
+    threading_model: ThreadingModel,
+    prog_id: Option<&str>,
+) -> io::Result<()> {
+    let class = create_class(scope, wow64_32, clsid, prog_id)?;
+    class
+        .delete_subkey_all("LocalServer32")
+        .or_else(ignore_not_found)?;
+    let (server, _disp) =
+        class.create_subkey_with_flags("InprocServer32", KEY_ALL_ACCESS | wow64_view(wow64_32))?;
+    server.set_value("", &dll_path)?;
+    server.set_value("ThreadingModel", &threading_model.as_str())
+}
+
+/// Register an out-of-process server (`LocalServer32`) and optionally its ProgID.
+/// `command` is the command line that starts the server, e.g. `"C:\app.exe" -Embedding`.
+pub fn register_local_server(
+    scope: Scope,
+    wow64_32: bool,
+    clsid: &str,
+    command: &str,
+    prog_id: Option<&str>,
+) -> io::Result<()> {
+    let class = create_class(scope, wow64_32, clsid, prog_id)?;
+    class
+        .delete_subkey_all("InprocServer32")
+        .or_else(ignore_not_found)?;
+    let (server, _disp) =
+        class.create_subkey_with_flags("LocalServer32", KEY_ALL_ACCESS | wow64_view(wow64_32))?;
+    server.set_value("", &command)
+}
+
+/// Remove a class registered with `register_inproc_server` or `register_local_server`.
+/// The ProgID is only removed if it still points to `clsid`.
+pub fn unregister_server(
+    scope: Scope,
+    wow64_32: bool,
+    clsid: &str,
+    prog_id: Option<&str>,
+) -> io::Result<()> {
+    let clsid = braced(clsid)?;
+    let classes = open_classes(scope, wow64_32)?;
+    classes
+        .delete_subkey_all(format!("CLSID\\{}", clsid))
+        .or_else(ignore_not_found)?;
+    if let Some(prog_id) = prog_id {
+        let owner = classes
+            .open_subkey(format!("{}\\CLSID", prog_id))
+            .and_then(|k| k.get_value::<String, _>(""));
+        if let Ok(owner) = owner {
+            if owner.eq_ignore_ascii_case(&clsid) {
+                classes.delete_subkey_all(prog_id)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Register a type library: `TypeLib\{libid}\{version}\0\win32|win64`
+pub fn register_typelib(
+    scope: Scope,
+    wow64_32: bool,
+    libid: &str,
+    version: &str,
+    description: &str,
+    path: &str,
+) -> io::Result<()> {
+    let libid = braced(libid)?;
+    let classes = open_classes(scope, wow64_32)?;
+    let (lib, _disp) = classes.create_subkey_with_flags(
+        format!("TypeLib\\{}\\{}", libid, version),
+        KEY_ALL_ACCESS | wow64_view(wow64_32),
+    )?;
+    lib.set_value("", &description)?;
+    let platform = if wow64_32 { "0\\win32" } else { "0\\win64" };
+    let (file, _disp) =
+        lib.create_subkey_with_flags(platform, KEY_ALL_ACCESS | wow64_view(wow64_32))?;
+    file.set_value("", &path)?;
+    let (flags, _disp) =
+        lib.create_subkey_with_flags("FLAGS", KEY_ALL_ACCESS | wow64_view(wow64_32))?;
+    flags.set_value("", &"0")
+}
+
+/// Remove a single version of a type library, and the library key if no versions remain
+pub fn unregister_typelib(
+    scope: Scope,
+    wow64_32: bool,
+    libid: &str,
+    version: &str,
+) -> io::Result<()> {
+    let libid = braced(libid)?;
+    let classes = open_classes(scope, wow64_32)?;
+    let lib_path = format!("TypeLib\\{}", libid);
+    classes
+        .delete_subkey_all(format!("{}\\{}", lib_path, version))
+        .or_else(ignore_not_found)?;
+    match classes.open_subkey_with_flags(&lib_path, KEY_READ | wow64_view(wow64_32)) {
+        Ok(lib) => {
+            if lib.query_info()?.sub_keys == 0 {
+                classes.delete_subkey_with_flags(&lib_path, wow64_view(wow64_32))?;
+            }
+            Ok(())
+        }
+        Err(e) => ignore_not_found(e),
+    }
+}
+
+fn open_classes(scope: Scope, wow64_32: bool) -> io::Result<RegKey> {
+    scope
+        .root()
+        .open_subkey_with_flags(CLASSES_PATH, KEY_ALL_ACCESS | wow64_view(wow64_32))
+}
+
+fn create_class(
+    scope: Scope,
+    wow64_32: bool,
+    clsid: &str,
+    prog_id: Option<&str>,
+) -> io::Result<RegKey> {
+    let clsid = braced(clsid)?;
+    let classes = open_classes(scope, wow64_32)?;
+    let (class, _disp) = classes.create_subkey_with_flags(
+        format!("CLSID\\{}", clsid),
+        KEY_ALL_ACCESS | wow64_view(wow64_32),
+    )?;
+    if let Some(prog_id) = prog_id {
+        let (class_prog_id, _disp) =
+            class.create_subkey_with_flags("ProgID", KEY_ALL_ACCESS | wow64_view(wow64_32))?;
+        class_prog_id.set_value("", &prog_id)?;
+        let (prog_id_clsid, _disp) = classes.create_subkey(format!("{}\\CLSID", prog_id))?;
+        prog_id_clsid.set_value("", &clsid)?;
+    }
+    Ok(class)
+}
+
+/// Normalize a GUID string to the `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}` form
+fn braced(guid: &str) -> io::Result<String> {
+    let inner = guid.trim().trim_start_matches('{').trim_end_matches('}');
+    let valid = inner.len() == 36
+        && inner.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        });
+    if !valid {
+        return Err(io::Error::from_raw_os_error(
+            Foundation::ERROR_INVALID_PARAMETER as i32,
+        ));
+    }
+    Ok(format!("{{{}}}", inner.to_ascii_uppercase()))
+}
@@ -0,0 +1,146 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Explorer context-menu verbs.
+//!
+//! Verbs are written to `Software\Classes` of the chosen scope: `HKCU` for
+//! the current user only (no elevation required), `HKLM` for all users.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::context_menu::{self, Target, Verb};
+//!use winreg2::helpers::Scope;
+//!
+//!fn main() -> io::Result<()> {
+//!    Verb::new("OpenWithMyApp", "\"C:\\Program Files\\MyApp\\myapp.exe\" \"%1\"")
+//!        .label("Open with MyApp")
+//!        .icon("C:\\Program Files\\MyApp\\myapp.exe,0")
+//!        .extended(true)
+//!        .register(Scope::CurrentUser, &Target::AllFiles)?;
+//!    context_menu::remove(Scope::CurrentUser, &Target::AllFiles, "OpenWithMyApp")?;
+//!    Ok(())
+//!}
+//!```
+use super::{delete_value_if_exists, Scope};
+use crate::enums::*;
+use std::io;
+
+/// Path of the classes key relative to `HKCU` or `HKLM`
+pub const CLASSES_PATH: &str = "Software\\Classes";
+
+/// Kind of item a verb is shown for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Every file (`*`)
+    AllFiles,
+    /// Folders (`Directory`)
+    Directory,
+    /// Background of an open folder (`Directory\Background`)
+    DirectoryBackground,
+    /// Drives (`Drive`)
+    Drive,
+    /// A specific ProgID (e.g. `txtfile`) or file extension (e.g. `.txt`)
+    ProgId(String),
+}
+
+impl Target {
+    /// Class key name relative to `Software\Classes`
+    pub fn class_path(&self) -> &str {
+        match *self {
+            Target::AllFiles => "*",
+            Target::Directory => "Directory",
+            Target::DirectoryBackground => "Directory\\Background",
+            Target::Drive => "Drive",
+            Target::ProgId(ref id) => id,
+        }
+    }
+
+    fn shell_path(&self) -> String {
+        format!("{}\\{}\\shell", CLASSES_PATH, self.class_path())
+    }
+}
+
+/// Builder for a `shell\<name>` verb
+#[derive(Debug, Clone)]
+pub struct Verb {
+    name: String,
+    command: String,
+    label: Option<String>,
+    icon: Option<String>,
+    extended: bool,
+}
+
+impl Verb {
+    /// `name` is the verb key name, `command` is the command line to run,
+    /// with `%1` standing for the selected item (`%V` for folder backgrounds)
+    pub fn new<N: Into<String>, C: Into<String>>(name: N, command: C) -> Verb {
+        Verb {
+            name: name.into(),
+            command: command.into(),
+            label: None,
+            icon: None,
+            extended: false,
+        }
+    }
+
+    /// Text shown in the menu (`MUIVerb`), may be an indirect string like `@myapp.dll,-101`
+    pub fn label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Icon resource, e.g. `C:\Program Files\MyApp\myapp.exe,0`
+    pub fn icon<S: Into<String>>(mut self, icon: S) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Only show the verb when Shift is held
+    pub fn extended(mut self, extended: bool) -> Self {
+        self.extended = extended;
+        self
+    }
+
+    /// Create or overwrite the verb for `target`
+    pub fn register(&self, scope: Scope, target: &Target) -> io::Result<()> {
+        let (key, _disp) =
+            scope
+                .root()
+                .create_subkey(format!("{}\\{}", target.shell_path(), self.name))?;
+        match self.label {
+            Some(ref label) => key.set_value("MUIVerb", label)?,
+            None => delete_value_if_exists(&key, "MUIVerb")?,
+        }
+        match self.icon {
+            Some(ref icon) => key.set_value("Icon", icon)?,
+            None => delete_value_if_exists(&key, "Icon")?,
+        }
+        if self.extended {
+            key.set_value("Extended", &"")?;
+        } else {
+            delete_value_if_exists(&key, "Extended")?;
+        }
+        let (command, _disp) = key.create_subkey("command")?;
+        command.set_value("", &self.command)
+    }
+}
+
+/// Delete the verb `name` of `target`
+pub fn remove(scope: Scope, target: &Target, name: &str) -> io::Result<()> {
+    scope
+        .root()
+        .open_subkey_with_flags(target.shell_path(), KEY_ALL_ACCESS)?
+        .delete_subkey_all(name)
+}
+
+/// Return names of the verbs registered for `target` in `scope`
+pub fn list(scope: Scope, target: &Target) -> io::Result<Vec<String>> {
+    match scope.root().open_subkey(target.shell_path()) {
+        Ok(key) => key.enum_keys().collect(),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Persistent user and system environment variables.
+//!
+//! Every modifying function broadcasts `WM_SETTINGCHANGE` with `"Environment"`
+//! afterwards, so that Explorer and newly started consoles pick up the changes.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::env;
+//!use winreg2::helpers::Scope;
+//!
+//!fn main() -> io::Result<()> {
+//!    env::set(Scope::CurrentUser, "MY_HOME", "%USERPROFILE%\\my")?;
+//!    env::append_to_path(Scope::CurrentUser, "%MY_HOME%\\bin")?;
+//!    println!("PATH = {}", env::get(Scope::CurrentUser, "PATH")?);
+//!    Ok(())
+//!}
+//!```
+use super::Scope;
+use crate::common::*;
+use crate::enums::*;
+use crate::{RegKey, RegValue};
+use std::io;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::System::Registry;
+use windows_sys::Win32::UI::WindowsAndMessaging;
+
+/// Path of the user environment key relative to `HKCU`
+pub const USER_ENVIRONMENT_PATH: &str = "Environment";
+/// Path of the system environment key relative to `HKLM`
+pub const SYSTEM_ENVIRONMENT_PATH: &str =
+    "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment";
+
+const PATH: &str = "Path";
+const BROADCAST_TIMEOUT_MS: u32 = 5000;
+
+fn environment_path(scope: Scope) -> &'static str {
+    match scope {
+        Scope::CurrentUser => USER_ENVIRONMENT_PATH,
+        Scope::LocalMachine => SYSTEM_ENVIRONMENT_PATH,
+    }
+}
+
+fn open(scope: Scope, perms: Registry::REG_SAM_FLAGS) -> io::Result<RegKey> {
+    scope
+        .root()
+        .open_subkey_with_flags(environment_path(scope), perms)
+}
+
+/// Get the unexpanded value of a variable, i.e. `%SystemRoot%` references are preserved
+pub fn get(scope: Scope, name: &str) -> io::Result<String> {
+    open(scope, KEY_READ)?.get_value(name)
+}
+
+/// Set a variable. The value is stored as `REG_EXPAND_SZ` if the variable already
+/// has that type or if it contains a `%` sign, otherwise as `REG_SZ`.
+pub fn set(scope: Scope, name: &str, value: &str) -> io::Result<()> {
+    let key = open(scope, KEY_READ | KEY_SET_VALUE)?;
+    write(&key, name, value)?;
+    broadcast_change()
+}
+
+/// Delete a variable
+pub fn remove(scope: Scope, name: &str) -> io::Result<()> {
+    open(scope, KEY_SET_VALUE)?.delete_value(name)?;
+    broadcast_change()
+}
+
+/// Split `PATH` into its entries, skipping empty ones
+pub fn split_path(path: &str) -> Vec<&str> {
+    path.split(';').filter(|p| !p.trim().is_empty()).collect()
+}
+
+/// Append a directory to `PATH` unless it's already there.
+/// Returns `true` if `PATH` was modified.
+pub fn append_to_path(scope: Scope, dir: &str) -> io::Result<bool> {
+    modify_path(scope, |entries| {
+        if entries.iter().any(|e| same_dir(e, dir)) {
+            return false;
+        }
+        entries.push(dir.to_owned());
+        true
+    })
+}
+
+/// Prepend a directory to `PATH`, moving it to the front if it's already there.
+/// Returns `true` if `PATH` was modified.
+pub fn prepend_to_path(scope: Scope, dir: &str) -> io::Result<bool> {
+    modify_path(scope, |entries| {
+        if entries.first().map_or(false, |e| same_dir(e, dir)) {
+            return false;
+        }
+        entries.retain(|e| !same_dir(e, dir));
+        entries.insert(0, dir.to_owned());
+        true
+    })
+}
+
+/// Remove every occurrence of a directory from `PATH`.
+/// Returns `true` if `PATH` was modified.
+pub fn remove_from_path(scope: Scope, dir: &str) -> io::Result<bool> {
+    modify_path(scope, |entries| {
+        let len = entries.len();
+        entries.retain(|e| !same_dir(e, dir));
+        entries.len() != len
+    })
+}
+
+/// Remove duplicate entries from `PATH`, keeping the first occurrence.
+/// Returns `true` if `PATH` was modified.
+pub fn dedup_path(scope: Scope) -> io::Result<bool> {
+    modify_path(scope, |entries| {
+        let len = entries.len();
+        let mut unique: Vec<String> = Vec::with_capacity(len);
+        for e in entries.drain(..) {
+            if !unique.iter().any(|u| same_dir(u, &e)) {
+                unique.push(e);
+            }
+        }
+        *entries = unique;
+        entries.len() != len
+    })
+}
+
+/// Notify top-level windows that the environment has changed
+pub fn broadcast_change() -> io::Result<()> {
+    let param = to_utf16("Environment");
+    let mut result: usize = 0;
+    match unsafe {
+        WindowsAndMessaging::SendMessageTimeoutW(
+            WindowsAndMessaging::HWND_BROADCAST,
+            WindowsAndMessaging::WM_SETTINGCHANGE,
+            0,
+            param.as_ptr() as isize,
+            WindowsAndMessaging::SMTO_ABORTIFHUNG,
+            BROADCAST_TIMEOUT_MS,
+            &mut result,
+        )
+    } {
+        0 => match io::Error::last_os_error() {
+            // some window not responding in time is not our failure
+            ref e if e.raw_os_error() == Some(Foundation::ERROR_TIMEOUT as i32) => Ok(()),
+            e => Err(e),
+        },
+        _ => Ok(()),
+    }
+}
+
+fn modify_path<F: FnOnce(&mut Vec<String>) -> bool>(scope: Scope, f: F) -> io::Result<bool> {
+    let key = open(scope, KEY_READ | KEY_SET_VALUE)?;
+    let current: String = match key.get_value(PATH) {
+        Ok(v) => v,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let mut entries: Vec<String> = split_path(&current)
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+    if !f(&mut entries) {
+        return Ok(false);
+    }
+    write(&key, PATH, &entries.join(";"))?;
+    broadcast_change()?;
+    Ok(true)
+}
+
+fn write(key: &RegKey, name: &str, value: &str) -> io::Result<()> {
+    let expand = value.contains('%')
+        || match key.get_raw_value(name) {
+            Ok(v) => v.vtype == REG_EXPAND_SZ,
+            Err(_) => false,
+        };
+    let vtype = if expand { REG_EXPAND_SZ } else { REG_SZ };
+    key.set_raw_value(
+        name,
+        &RegValue {
+            bytes: v16_to_v8(&to_utf16(value)),
+            vtype,
+        },
+    )
+}
+
+/// Compare two `PATH` entries ignoring case and trailing backslashes
+fn same_dir(a: &str, b: &str) -> bool {
+    let a = a.trim().trim_end_matches('\\');
+    let b = b.trim().trim_end_matches('\\');
+    a.to_lowercase() == b.to_lowercase()
+}
@@ -0,0 +1,84 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Event Log source registration under
+//! `HKLM\SYSTEM\CurrentControlSet\Services\EventLog\<Log>\<Source>`.
+//!
+//! Writing requires administrative rights, so this is usually done by an installer.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::eventlog;
+//!
+//!fn main() -> io::Result<()> {
+//!    eventlog::register_source(
+//!        "Application",
+//!        "MyService",
+//!        "%ProgramFiles%\\MyService\\messages.dll",
+//!        eventlog::ERROR | eventlog::WARNING | eventlog::INFORMATION,
+//!    )?;
+//!    eventlog::unregister_source("Application", "MyService")?;
+//!    Ok(())
+//!}
+//!```
+use super::ignore_not_found;
+use crate::common::*;
+use crate::enums::*;
+use crate::{RegKey, RegValue};
+use std::io;
+
+/// Path of the `EventLog` service key relative to `HKLM`
+pub const EVENTLOG_PATH: &str = "SYSTEM\\CurrentControlSet\\Services\\EventLog";
+
+/// `TypesSupported` flag for error events
+pub const ERROR: u32 = 0x0001;
+/// `TypesSupported` flag for warning events
+pub const WARNING: u32 = 0x0002;
+/// `TypesSupported` flag for information events
+pub const INFORMATION: u32 = 0x0004;
+/// `TypesSupported` flag for success audit events
+pub const AUDIT_SUCCESS: u32 = 0x0008;
+/// `TypesSupported` flag for failure audit events
+pub const AUDIT_FAILURE: u32 = 0x0010;
+
+/// Register an event source in `log` (e.g. `Application`).
+/// `message_file` may contain `%VAR%` references, it's stored as `REG_EXPAND_SZ`.
+pub fn register_source(
+    log: &str,
+    source: &str,
+    message_file: &str,
+    types_supported: u32,
+) -> io::Result<()> {
+    let (key, _disp) = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .create_subkey(format!("{}\\{}\\{}", EVENTLOG_PATH, log, source))?;
+    key.set_raw_value(
+        "EventMessageFile",
+        &RegValue {
+            bytes: v16_to_v8(&to_utf16(message_file)),
+            vtype: REG_EXPAND_SZ,
+        },
+    )?;
+    key.set_value("TypesSupported", &types_supported)
+}
+
+/// Remove an event source, a missing source is not an error
+pub fn unregister_source(log: &str, source: &str) -> io::Result<()> {
+    RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey_with_flags(format!("{}\\{}", EVENTLOG_PATH, log), KEY_ALL_ACCESS)
+        .and_then(|key| key.delete_subkey_all(source))
+        .or_else(ignore_not_found)
+}
+
+/// Check whether `source` is registered in `log`
+pub fn source_exists(log: &str, source: &str) -> io::Result<bool> {
+    match RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(format!("{}\\{}\\{}", EVENTLOG_PATH, log, source))
+    {
+        Ok(_) => Ok(true),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
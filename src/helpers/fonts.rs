@@ -0,0 +1,95 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Font registration in the `Fonts` key.
+//!
+//! Per-user fonts (`Scope::CurrentUser`) are supported since Windows 10 1809,
+//! per-machine fonts require administrative rights. The font file itself is not copied,
+//! it should already be in its final location, e.g. `%LOCALAPPDATA%\Microsoft\Windows\Fonts`
+//! for per-user fonts.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::fonts;
+//!use winreg2::helpers::Scope;
+//!
+//!fn main() -> io::Result<()> {
+//!    let path = "C:\\Users\\me\\AppData\\Local\\Microsoft\\Windows\\Fonts\\MyFont.ttf";
+//!    fonts::register("My Font (TrueType)", path, Scope::CurrentUser)?;
+//!    fonts::unregister("My Font (TrueType)", Scope::CurrentUser)?;
+//!    Ok(())
+//!}
+//!```
+use super::{windows_version, Scope};
+use crate::common::*;
+use crate::enums::*;
+use std::io;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::Graphics::Gdi;
+use windows_sys::Win32::UI::WindowsAndMessaging;
+
+/// Path of the `Fonts` key relative to `HKCU` or `HKLM`
+pub const FONTS_PATH: &str = "Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts";
+
+/// First build with per-user font installation (Windows 10 1809)
+const PER_USER_FONTS_BUILD: u32 = 17763;
+const BROADCAST_TIMEOUT_MS: u32 = 5000;
+
+/// Register a font file and load it into the current session.
+/// `face_name` is the value name, conventionally suffixed with the font type,
+/// e.g. `"Consolas Bold (TrueType)"`.
+pub fn register(face_name: &str, file_path: &str, scope: Scope) -> io::Result<()> {
+    if scope == Scope::CurrentUser {
+        let v = windows_version::current()?;
+        if v.version.major < 10 || v.version.build < PER_USER_FONTS_BUILD {
+            return werr!(Foundation::ERROR_NOT_SUPPORTED);
+        }
+    }
+    let (key, _disp) = scope.root().create_subkey(FONTS_PATH)?;
+    key.set_value(face_name, &file_path)?;
+    let path = to_utf16(file_path);
+    if unsafe { Gdi::AddFontResourceW(path.as_ptr()) } == 0 {
+        return werr!(Foundation::ERROR_INVALID_DATA);
+    }
+    broadcast_change()
+}
+
+/// Remove a font registration and unload the font from the current session
+pub fn unregister(face_name: &str, scope: Scope) -> io::Result<()> {
+    let key = scope
+        .root()
+        .open_subkey_with_flags(FONTS_PATH, KEY_READ | KEY_SET_VALUE)?;
+    let file_path: String = key.get_value(face_name)?;
+    key.delete_value(face_name)?;
+    // the font may not be loaded, e.g. after a failed registration
+    let path = to_utf16(&file_path);
+    if unsafe { Gdi::RemoveFontResourceW(path.as_ptr()) } != 0 {
+        broadcast_change()?;
+    }
+    Ok(())
+}
+
+/// Notify top-level windows that the set of available fonts has changed
+pub fn broadcast_change() -> io::Result<()> {
+    let mut result: usize = 0;
+    match unsafe {
+        WindowsAndMessaging::SendMessageTimeoutW(
+            WindowsAndMessaging::HWND_BROADCAST,
+            WindowsAndMessaging::WM_FONTCHANGE,
+            0,
+            0,
+            WindowsAndMessaging::SMTO_ABORTIFHUNG,
+            BROADCAST_TIMEOUT_MS,
+            &mut result,
+        )
+    } {
+        0 => match io::Error::last_os_error() {
+            ref e if e.raw_os_error() == Some(Foundation::ERROR_TIMEOUT as i32) => Ok(()),
+            e => Err(e),
+        },
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Enumeration of installed programs as shown in Apps & Features.
+//!
+//!```no_run
+//!use winreg2::helpers::installed_programs;
+//!
+//!for p in installed_programs::list() {
+//!    println!("{} {}", p.display_name, p.display_version.unwrap_or_default());
+//!}
+//!```
+use super::uninstall_entry::UNINSTALL_PATH;
+use super::{wow64_view, Scope};
+use crate::enums::*;
+use crate::RegKey;
+use std::collections::HashSet;
+use std::io;
+
+/// `InstallDate` value, stored by installers as a `YYYYMMDD` string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InstallDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl InstallDate {
+    /// Parse a `YYYYMMDD` string, returning `None` if it's malformed
+    pub fn parse(s: &str) -> Option<InstallDate> {
+        let s = s.trim();
+        if s.len() != 8 || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let year = s[0..4].parse().ok()?;
+        let month = s[4..6].parse().ok()?;
+        let day = s[6..8].parse().ok()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(InstallDate { year, month, day })
+    }
+}
+
+/// A single program registered under one of the `Uninstall` keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledProgram {
+    /// Name of the key under `Uninstall`
+    pub id: String,
+    pub display_name: String,
+    pub display_version: Option<String>,
+    pub publisher: Option<String>,
+    pub install_location: Option<String>,
+    pub install_date: Option<InstallDate>,
+    pub uninstall_string: Option<String>,
+    /// Installed size in KiB
+    pub estimated_size: Option<u32>,
+    /// `SystemComponent` is set, Apps & Features hides such entries
+    pub system_component: bool,
+    /// Hive the entry was found in
+    pub scope: Scope,
+    /// Whether the entry was found in the 32-bit registry view
+    pub wow64_32: bool,
+}
+
+/// List programs from the 64-bit and 32-bit views of `HKLM` and from `HKCU`.
+///
+/// Entries without a `DisplayName` are skipped, as are locations and entries
+/// that can't be read. Programs registered in several locations are reported once,
+/// the first occurrence wins in the order `HKLM` 64-bit, `HKLM` 32-bit, `HKCU`.
+pub fn list() -> Vec<InstalledProgram> {
+    let locations = [
+        (Scope::LocalMachine, false),
+        (Scope::LocalMachine, true),
+        (Scope::CurrentUser, false),
+    ];
+    let mut seen = HashSet::new();
+    let mut programs = Vec::new();
+    for &(scope, wow64_32) in &locations {
+        let found = match list_in(scope, wow64_32) {
+            Ok(found) => found,
+            Err(_) => continue,
+        };
+        for p in found {
+            let id = (
+                p.display_name.to_lowercase(),
+                p.display_version.clone(),
+                p.publisher.clone(),
+            );
+            if seen.insert(id) {
+                programs.push(p);
+            }
+        }
+    }
+    programs
+}
+
+/// List programs from a single `Uninstall` key.
+/// Entries without a `DisplayName` or that can't be read are skipped.
+pub fn list_in(scope: Scope, wow64_32: bool) -> io::Result<Vec<InstalledProgram>> {
+    let view = wow64_view(wow64_32);
+    let uninstall = scope
+        .root()
+        .open_subkey_with_flags(UNINSTALL_PATH, KEY_READ | view)?;
+    let mut programs = Vec::new();
+    for id in uninstall.enum_keys().filter_map(|x| x.ok()) {
+        let key = match uninstall.open_subkey_with_flags(&id, KEY_READ | view) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        if let Some(p) = read_program(&key, id, scope, wow64_32) {
+            programs.push(p);
+        }
+    }
+    Ok(programs)
+}
+
+fn read_program(
+    key: &RegKey,
+    id: String,
+    scope: Scope,
+    wow64_32: bool,
+) -> Option<InstalledProgram> {
+    let display_name: String = key.get_value("DisplayName").ok()?;
+    if display_name.is_empty() {
+        return None;
+    }
+    let string = |name: &str| {
+        key.get_value::<String, _>(name)
+            .ok()
+            .filter(|s| !s.is_empty())
+    };
+    Some(InstalledProgram {
+        id,
+        display_name,
+        display_version: string("DisplayVersion"),
+        publisher: string("Publisher"),
+        install_location: string("InstallLocation"),
+        install_date: string("InstallDate").and_then(|s| InstallDate::parse(&s)),
+        uninstall_string: string("UninstallString"),
+        estimated_size: key.get_value("EstimatedSize").ok(),
+        system_component: key
+            .get_value::<u32, _>("SystemComponent")
+            .map(|v| v != 0)
+            .unwrap_or(false),
+        scope,
+        wow64_32,
+    })
+}
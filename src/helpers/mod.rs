@@ -0,0 +1,73 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! High-level helpers for well-known registry locations
+use crate::enums::*;
+use crate::RegKey;
+use std::io;
+use windows_sys::Win32::System::Registry;
+
+pub mod app_paths;
+pub mod com;
+pub mod context_menu;
+pub mod env;
+pub mod eventlog;
+pub mod fonts;
+pub mod installed_programs;
+pub mod network;
+pub mod office_addin;
+pub mod pending_reboot;
+pub mod proxy;
+pub mod security_audit;
+pub mod services;
+pub mod shell_folders;
+pub mod uninstall_entry;
+pub mod windows_version;
+
+/// Registry hive a helper operates on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// `HKEY_CURRENT_USER`, no elevation required
+    CurrentUser,
+    /// `HKEY_LOCAL_MACHINE`, usually requires administrative rights to write
+    LocalMachine,
+}
+
+impl Scope {
+    /// Return the predefined root key of this scope
+    pub const fn root(self) -> RegKey {
+        match self {
+            Scope::CurrentUser => RegKey::predef(HKEY_CURRENT_USER),
+            Scope::LocalMachine => RegKey::predef(HKEY_LOCAL_MACHINE),
+        }
+    }
+}
+
+/// Delete a value, treating a missing value as success
+pub(crate) fn delete_value_if_exists(key: &RegKey, name: &str) -> io::Result<()> {
+    match key.delete_value(name) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    }
+}
+
+/// Map `NotFound` errors to success, for use with `Result::or_else`
+pub(crate) fn ignore_not_found(e: io::Error) -> io::Result<()> {
+    if e.kind() == io::ErrorKind::NotFound {
+        Ok(())
+    } else {
+        Err(e)
+    }
+}
+
+/// Registry view flag for `KEY_WOW64_32KEY` or `KEY_WOW64_64KEY`
+pub(crate) const fn wow64_view(wow64_32: bool) -> Registry::REG_SAM_FLAGS {
+    if wow64_32 {
+        RegistryView::V32.flags()
+    } else {
+        RegistryView::V64.flags()
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Persistent mapped network drives of the current user, Explorer's
+//! `MountPoints2` metadata and the "Map Network Drive" MRU list.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::network;
+//!
+//!fn main() -> io::Result<()> {
+//!    for drive in network::mapped_drives()? {
+//!        println!("{}: -> {}", drive.letter, drive.remote_path);
+//!    }
+//!    for share in network::map_network_drive_mru()? {
+//!        println!("recently mapped: {}", share);
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::RegKey;
+use std::io;
+
+/// Path of the persistent network connections key relative to `HKCU`
+pub const NETWORK_PATH: &str = "Network";
+/// Path of the `MountPoints2` key relative to `HKCU`
+pub const MOUNT_POINTS_PATH: &str =
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\MountPoints2";
+/// Path of the "Map Network Drive" MRU key relative to `HKCU`
+pub const MAP_NETWORK_DRIVE_MRU_PATH: &str =
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Map Network Drive MRU";
+
+/// A persistent drive mapping from `HKCU\Network\<letter>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedDrive {
+    /// Drive letter, uppercase
+    pub letter: char,
+    /// `RemotePath`, e.g. `\\server\share`
+    pub remote_path: String,
+    /// `UserName` the connection is made with, `None` for the logged on user
+    pub user_name: Option<String>,
+    /// `ProviderName`, e.g. `Microsoft Windows Network`
+    pub provider_name: Option<String>,
+    pub provider_type: Option<u32>,
+    pub connection_type: Option<u32>,
+}
+
+/// What a `MountPoints2` subkey refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MountPointKind {
+    /// A network share, the key name is the UNC path with `#` instead of `\`
+    Network(String),
+    /// A volume identified by its GUID, e.g. `{0b5c5c1e-...}`
+    Volume(String),
+    /// A drive letter or anything else
+    Other(String),
+}
+
+/// A `MountPoints2` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountPoint {
+    pub kind: MountPointKind,
+    /// `_LabelFromReg`, the label shown by Explorer
+    pub label: Option<String>,
+    /// `_CommentFromDesktopINI`
+    pub comment: Option<String>,
+}
+
+impl MountPoint {
+    /// Build an entry from a `MountPoints2` key name without reading any values
+    pub fn from_key_name(name: &str) -> MountPoint {
+        let kind = if name.starts_with("##") {
+            MountPointKind::Network(name.replace('#', "\\"))
+        } else if name.starts_with('{') && name.ends_with('}') {
+            MountPointKind::Volume(name.to_owned())
+        } else {
+            MountPointKind::Other(name.to_owned())
+        };
+        MountPoint {
+            kind,
+            label: None,
+            comment: None,
+        }
+    }
+}
+
+/// List persistent mapped drives, sorted by letter
+pub fn mapped_drives() -> io::Result<Vec<MappedDrive>> {
+    let network = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(NETWORK_PATH) {
+        Ok(key) => key,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut drives = Vec::new();
+    for name in network.enum_keys() {
+        let name = name?;
+        let mut chars = name.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
+            _ => continue,
+        };
+        let key = network.open_subkey(&name)?;
+        let string = |name: &str| {
+            key.get_value::<String, _>(name)
+                .ok()
+                .filter(|s| !s.is_empty())
+        };
+        let remote_path = match string("RemotePath") {
+            Some(p) => p,
+            None => continue,
+        };
+        drives.push(MappedDrive {
+            letter,
+            remote_path,
+            user_name: string("UserName"),
+            provider_name: string("ProviderName"),
+            provider_type: key.get_value("ProviderType").ok(),
+            connection_type: key.get_value("ConnectionType").ok(),
+        });
+    }
+    drives.sort_by_key(|d| d.letter);
+    Ok(drives)
+}
+
+/// List Explorer's `MountPoints2` entries
+pub fn mount_points() -> io::Result<Vec<MountPoint>> {
+    let root = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(MOUNT_POINTS_PATH) {
+        Ok(key) => key,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut points = Vec::new();
+    for name in root.enum_keys() {
+        let name = name?;
+        let mut point = MountPoint::from_key_name(&name);
+        if let Ok(key) = root.open_subkey(&name) {
+            let string = |name: &str| {
+                key.get_value::<String, _>(name)
+                    .ok()
+                    .filter(|s| !s.is_empty())
+            };
+            point.label = string("_LabelFromReg");
+            point.comment = string("_CommentFromDesktopINI");
+        }
+        points.push(point);
+    }
+    Ok(points)
+}
+
+/// Shares from the "Map Network Drive" dialog history, most recent first
+pub fn map_network_drive_mru() -> io::Result<Vec<String>> {
+    let key = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(MAP_NETWORK_DRIVE_MRU_PATH) {
+        Ok(key) => key,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    // MRUList holds the value names ("a", "b", ...) ordered by recency
+    let order: String = key.get_value("MRUList").unwrap_or_default();
+    Ok(order
+        .chars()
+        .filter_map(|c| key.get_value::<String, _>(c.to_string()).ok())
+        .filter(|s| !s.is_empty())
+        .collect())
+}
@@ -0,0 +1,197 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Office add-in registration under `Software\Microsoft\Office\<App>\Addins\<Id>`.
+//!
+//! The add-in id is its ProgID. COM add-ins also need their class registered,
+//! see [`com::register_inproc_server`](super::com::register_inproc_server) with the same ProgID;
+//! VSTO add-ins are located through their deployment manifest instead.
+//! `wow64_32` selects the view 32-bit Office reads `HKLM` registrations from.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::office_addin::{self, LoadBehavior};
+//!use winreg2::helpers::Scope;
+//!
+//!fn main() -> io::Result<()> {
+//!    office_addin::register(
+//!        Scope::CurrentUser,
+//!        false,
+//!        "Outlook",
+//!        "MyCompany.MyAddin",
+//!        "My Add-in",
+//!        LoadBehavior::Startup,
+//!        Some("C:\\Program Files\\MyAddin\\MyAddin.vsto"),
+//!    )?;
+//!    office_addin::unregister(Scope::CurrentUser, false, "Outlook", "MyCompany.MyAddin")?;
+//!    Ok(())
+//!}
+//!```
+use super::{ignore_not_found, wow64_view, Scope};
+use crate::enums::*;
+use crate::RegKey;
+use std::io;
+
+/// Path of the Office key relative to `HKCU` or `HKLM`
+pub const OFFICE_PATH: &str = "Software\\Microsoft\\Office";
+
+/// `LoadBehavior` of an add-in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadBehavior {
+    /// Not loaded automatically (0)
+    DoNotLoad,
+    /// Loaded when the application starts (3)
+    Startup,
+    /// Loaded when the user first uses it (9)
+    OnDemand,
+    /// Loaded at the next start, then on demand (16)
+    FirstTimeThenOnDemand,
+    /// Any other value, including the "unloaded" states Office writes back
+    Other(u32),
+}
+
+impl LoadBehavior {
+    pub fn from_u32(v: u32) -> LoadBehavior {
+        match v {
+            0 => LoadBehavior::DoNotLoad,
+            3 => LoadBehavior::Startup,
+            9 => LoadBehavior::OnDemand,
+            16 => LoadBehavior::FirstTimeThenOnDemand,
+            v => LoadBehavior::Other(v),
+        }
+    }
+
+    pub const fn to_u32(self) -> u32 {
+        match self {
+            LoadBehavior::DoNotLoad => 0,
+            LoadBehavior::Startup => 3,
+            LoadBehavior::OnDemand => 9,
+            LoadBehavior::FirstTimeThenOnDemand => 16,
+            LoadBehavior::Other(v) => v,
+        }
+    }
+}
+
+/// A registered add-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Addin {
+    pub id: String,
+    pub friendly_name: Option<String>,
+    pub description: Option<String>,
+    pub load_behavior: Option<LoadBehavior>,
+    /// `Manifest` of VSTO add-ins
+    pub manifest: Option<String>,
+}
+
+/// Register an add-in for an Office application (`Outlook`, `Excel`, `Word`, ...).
+/// `manifest` is the path of a VSTO deployment manifest, `None` for COM add-ins;
+/// `|vstolocal` is appended to it as Office expects.
+pub fn register(
+    scope: Scope,
+    wow64_32: bool,
+    app: &str,
+    addin_id: &str,
+    friendly_name: &str,
+    load_behavior: LoadBehavior,
+    manifest: Option<&str>,
+) -> io::Result<()> {
+    let (key, _disp) = scope.root().create_subkey_with_flags(
+        addin_path(app, addin_id),
+        KEY_ALL_ACCESS | wow64_view(wow64_32),
+    )?;
+    key.set_value("FriendlyName", &friendly_name)?;
+    key.set_value("Description", &friendly_name)?;
+    // Office ignores the add-in if LoadBehavior is not a REG_DWORD
+    key.set_value("LoadBehavior", &load_behavior.to_u32())?;
+    if let Some(manifest) = manifest {
+        let manifest = if manifest.ends_with("|vstolocal") {
+            manifest.to_owned()
+        } else {
+            format!("{}|vstolocal", manifest)
+        };
+        key.set_value("Manifest", &manifest)?;
+    }
+    Ok(())
+}
+
+/// Change the `LoadBehavior` of a registered add-in
+pub fn set_load_behavior(
+    scope: Scope,
+    wow64_32: bool,
+    app: &str,
+    addin_id: &str,
+    load_behavior: LoadBehavior,
+) -> io::Result<()> {
+    scope
+        .root()
+        .open_subkey_with_flags(
+            addin_path(app, addin_id),
+            KEY_SET_VALUE | wow64_view(wow64_32),
+        )?
+        .set_value("LoadBehavior", &load_behavior.to_u32())
+}
+
+/// Remove an add-in registration
+pub fn unregister(scope: Scope, wow64_32: bool, app: &str, addin_id: &str) -> io::Result<()> {
+    scope
+        .root()
+        .open_subkey_with_flags(
+            format!("{}\\{}\\Addins", OFFICE_PATH, app),
+            KEY_ALL_ACCESS | wow64_view(wow64_32),
+        )
+        .and_then(|addins| addins.delete_subkey_all(addin_id))
+        .or_else(ignore_not_found)
+}
+
+/// Read a single add-in registration
+pub fn read(scope: Scope, wow64_32: bool, app: &str, addin_id: &str) -> io::Result<Addin> {
+    let key = scope
+        .root()
+        .open_subkey_with_flags(addin_path(app, addin_id), KEY_READ | wow64_view(wow64_32))?;
+    Ok(read_addin(&key, addin_id))
+}
+
+/// List add-ins registered for an Office application
+pub fn list(scope: Scope, wow64_32: bool, app: &str) -> io::Result<Vec<Addin>> {
+    let addins = match scope.root().open_subkey_with_flags(
+        format!("{}\\{}\\Addins", OFFICE_PATH, app),
+        KEY_READ | wow64_view(wow64_32),
+    ) {
+        Ok(key) => key,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut result = Vec::new();
+    for id in addins.enum_keys() {
+        let id = id?;
+        if let Ok(key) = addins.open_subkey(&id) {
+            result.push(read_addin(&key, &id));
+        }
+    }
+    Ok(result)
+}
+
+fn addin_path(app: &str, addin_id: &str) -> String {
+    format!("{}\\{}\\Addins\\{}", OFFICE_PATH, app, addin_id)
+}
+
+fn read_addin(key: &RegKey, id: &str) -> Addin {
+    let string = |name: &str| {
+        key.get_value::<String, _>(name)
+            .ok()
+            .filter(|s| !s.is_empty())
+    };
+    Addin {
+        id: id.to_owned(),
+        friendly_name: string("FriendlyName"),
+        description: string("Description"),
+        load_behavior: key
+            .get_value::<u32, _>("LoadBehavior")
+            .ok()
+            .map(LoadBehavior::from_u32),
+        manifest: string("Manifest"),
+    }
+}
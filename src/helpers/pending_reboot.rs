@@ -0,0 +1,127 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Detection of a pending system restart.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::pending_reboot;
+//!
+//!fn main() -> io::Result<()> {
+//!    let reasons = pending_reboot::check()?;
+//!    if reasons.is_pending() {
+//!        println!("restart required: {:?}", reasons);
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::RegKey;
+use std::io;
+
+const CBS_REBOOT_PENDING_PATH: &str =
+    "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Component Based Servicing\\RebootPending";
+const WINDOWS_UPDATE_REBOOT_REQUIRED_PATH: &str =
+    "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\WindowsUpdate\\Auto Update\\RebootRequired";
+const SESSION_MANAGER_PATH: &str = "SYSTEM\\CurrentControlSet\\Control\\Session Manager";
+const UPDATES_PATH: &str = "SOFTWARE\\Microsoft\\Updates";
+const ACTIVE_COMPUTER_NAME_PATH: &str =
+    "SYSTEM\\CurrentControlSet\\Control\\ComputerName\\ActiveComputerName";
+const COMPUTER_NAME_PATH: &str = "SYSTEM\\CurrentControlSet\\Control\\ComputerName\\ComputerName";
+
+/// A single entry of `PendingFileRenameOperations`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRenameOperation {
+    /// Source path in NT form, e.g. `\??\C:\Temp\file.dll`
+    pub source: String,
+    /// Destination path, `None` if the file is to be deleted
+    pub destination: Option<String>,
+}
+
+/// Reasons a restart is pending, as recorded in the registry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingRebootReasons {
+    /// Component Based Servicing has `RebootPending`
+    pub component_based_servicing: bool,
+    /// Windows Update has `RebootRequired`
+    pub windows_update: bool,
+    /// `Updates\UpdateExeVolatile` is non-zero
+    pub update_exe_volatile: bool,
+    /// Session Manager `PendingFileRenameOperations` and `PendingFileRenameOperations2`
+    pub file_rename_operations: Vec<FileRenameOperation>,
+    /// The computer has been renamed but not restarted yet
+    pub computer_rename: bool,
+}
+
+impl PendingRebootReasons {
+    /// Whether any of the reasons applies
+    pub fn is_pending(&self) -> bool {
+        self.component_based_servicing
+            || self.windows_update
+            || self.update_exe_volatile
+            || !self.file_rename_operations.is_empty()
+            || self.computer_rename
+    }
+}
+
+/// Inspect the well-known locations that signal a pending restart
+pub fn check() -> io::Result<PendingRebootReasons> {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let mut file_rename_operations = Vec::new();
+    if let Ok(sm) = hklm.open_subkey(SESSION_MANAGER_PATH) {
+        for name in &[
+            "PendingFileRenameOperations",
+            "PendingFileRenameOperations2",
+        ] {
+            if let Ok(v) = sm.get_value::<Vec<String>, _>(name) {
+                file_rename_operations.extend(parse_file_rename_operations(&v));
+            }
+        }
+    }
+    Ok(PendingRebootReasons {
+        component_based_servicing: key_exists(&hklm, CBS_REBOOT_PENDING_PATH)?,
+        windows_update: key_exists(&hklm, WINDOWS_UPDATE_REBOOT_REQUIRED_PATH)?,
+        update_exe_volatile: hklm
+            .open_subkey(UPDATES_PATH)
+            .and_then(|k| k.get_value::<u32, _>("UpdateExeVolatile"))
+            .map(|v| v != 0)
+            .unwrap_or(false),
+        file_rename_operations,
+        computer_rename: computer_rename_pending(&hklm),
+    })
+}
+
+/// Parse `PendingFileRenameOperations`, which is a list of source/destination pairs
+/// with an empty destination meaning deletion
+pub fn parse_file_rename_operations(v: &[String]) -> Vec<FileRenameOperation> {
+    v.chunks(2)
+        .filter(|pair| !pair[0].is_empty())
+        .map(|pair| FileRenameOperation {
+            source: pair[0].clone(),
+            destination: pair.get(1).filter(|d| !d.is_empty()).cloned(),
+        })
+        .collect()
+}
+
+fn key_exists(root: &RegKey, path: &str) -> io::Result<bool> {
+    match root.open_subkey(path) {
+        Ok(_) => Ok(true),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn computer_rename_pending(hklm: &RegKey) -> bool {
+    let name = |path: &str| {
+        hklm.open_subkey(path)
+            .and_then(|k| k.get_value::<String, _>("ComputerName"))
+            .ok()
+    };
+    match (name(ACTIVE_COMPUTER_NAME_PATH), name(COMPUTER_NAME_PATH)) {
+        (Some(active), Some(pending)) => !active.eq_ignore_ascii_case(&pending),
+        _ => false,
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Per-user WinINET proxy settings from
+//! `HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings`.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::proxy::{self, ProxyServer};
+//!
+//!fn main() -> io::Result<()> {
+//!    let mut settings = proxy::read()?;
+//!    println!("https proxy: {:?}", settings.server.for_protocol("https"));
+//!    settings.enabled = true;
+//!    settings.server = ProxyServer::parse("http=proxy:8080;https=proxy:8443");
+//!    settings.bypass = vec!["<local>".to_owned(), "*.example.com".to_owned()];
+//!    proxy::write(&settings)?;
+//!    Ok(())
+//!}
+//!```
+use super::delete_value_if_exists;
+use crate::enums::*;
+use crate::RegKey;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::ptr;
+use windows_sys::Win32::Networking::WinInet;
+
+/// Path of the `Internet Settings` key relative to `HKCU`
+pub const INTERNET_SETTINGS_PATH: &str =
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings";
+
+/// Parsed `ProxyServer` value.
+///
+/// The value is either a single `host:port` used for all protocols,
+/// or a `;`-separated list of `protocol=host:port` pairs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyServer {
+    /// Server used for protocols without a specific entry
+    pub default: Option<String>,
+    /// Protocol-specific servers, keyed by lowercase protocol name
+    pub protocols: BTreeMap<String, String>,
+}
+
+impl ProxyServer {
+    pub fn parse(s: &str) -> ProxyServer {
+        let mut server = ProxyServer::default();
+        for part in s.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.find('=') {
+                Some(i) => {
+                    server.protocols.insert(
+                        part[..i].trim().to_lowercase(),
+                        part[i + 1..].trim().to_owned(),
+                    );
+                }
+                None => server.default = Some(part.to_owned()),
+            }
+        }
+        server
+    }
+
+    /// Server for `protocol` (e.g. `https`), falling back to the default one
+    pub fn for_protocol(&self, protocol: &str) -> Option<&str> {
+        self.protocols
+            .get(&protocol.to_lowercase())
+            .or(self.default.as_ref())
+            .map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.protocols.is_empty()
+    }
+}
+
+impl fmt::Display for ProxyServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts: Vec<String> = Vec::with_capacity(self.protocols.len() + 1);
+        if let Some(ref default) = self.default {
+            parts.push(default.clone());
+        }
+        parts.extend(self.protocols.iter().map(|(p, s)| format!("{}={}", p, s)));
+        write!(f, "{}", parts.join(";"))
+    }
+}
+
+/// WinINET proxy configuration of the current user
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxySettings {
+    /// `ProxyEnable`
+    pub enabled: bool,
+    /// `ProxyServer`
+    pub server: ProxyServer,
+    /// `ProxyOverride`, hosts that bypass the proxy; `<local>` stands for intranet names
+    pub bypass: Vec<String>,
+    /// `AutoConfigURL`, the PAC script location
+    pub auto_config_url: Option<String>,
+}
+
+/// Read the proxy settings of the current user
+pub fn read() -> io::Result<ProxySettings> {
+    let key = RegKey::predef(HKEY_CURRENT_USER).open_subkey(INTERNET_SETTINGS_PATH)?;
+    let string = |name: &str| {
+        key.get_value::<String, _>(name)
+            .ok()
+            .filter(|s| !s.is_empty())
+    };
+    Ok(ProxySettings {
+        enabled: key
+            .get_value::<u32, _>("ProxyEnable")
+            .map(|v| v != 0)
+            .unwrap_or(false),
+        server: string("ProxyServer")
+            .map(|s| ProxyServer::parse(&s))
+            .unwrap_or_default(),
+        bypass: string("ProxyOverride")
+            .map(|s| {
+                s.split(';')
+                    .map(str::trim)
+                    .filter(|h| !h.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        auto_config_url: string("AutoConfigURL"),
+    })
+}
+
+/// Write the proxy settings of the current user and notify WinINET about the change
+pub fn write(settings: &ProxySettings) -> io::Result<()> {
+    let key = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags(INTERNET_SETTINGS_PATH, KEY_READ | KEY_SET_VALUE)?;
+    key.set_value("ProxyEnable", &(settings.enabled as u32))?;
+    if settings.server.is_empty() {
+        delete_value_if_exists(&key, "ProxyServer")?;
+    } else {
+        key.set_value("ProxyServer", &settings.server.to_string())?;
+    }
+    if settings.bypass.is_empty() {
+        delete_value_if_exists(&key, "ProxyOverride")?;
+    } else {
+        key.set_value("ProxyOverride", &settings.bypass.join(";"))?;
+    }
+    match settings.auto_config_url {
+        Some(ref url) => key.set_value("AutoConfigURL", url)?,
+        None => delete_value_if_exists(&key, "AutoConfigURL")?,
+    }
+    refresh()
+}
+
+/// Tell WinINET to reload the settings from the registry,
+/// so running applications pick up the changes
+pub fn refresh() -> io::Result<()> {
+    for &option in &[
+        WinInet::INTERNET_OPTION_SETTINGS_CHANGED,
+        WinInet::INTERNET_OPTION_REFRESH,
+    ] {
+        if unsafe { WinInet::InternetSetOptionW(ptr::null(), option, ptr::null(), 0) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
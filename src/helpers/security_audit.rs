@@ -0,0 +1,243 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Read-only audit of common security-relevant registry settings:
+//! Winlogon autologon, LSA authentication settings and UAC.
+//!
+//! Nothing here modifies the registry. Secrets are never returned,
+//! only whether they are present.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::security_audit::{self, Severity};
+//!
+//!fn main() -> io::Result<()> {
+//!    for finding in security_audit::audit()? {
+//!        if finding.severity >= Severity::Medium {
+//!            println!("[{:?}] {}: {}", finding.severity, finding.location, finding.description);
+//!        }
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::RegKey;
+use std::cmp::Reverse;
+use std::io;
+
+/// Path of the `Winlogon` key relative to `HKLM`
+pub const WINLOGON_PATH: &str = "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Winlogon";
+/// Path of the `Lsa` key relative to `HKLM`
+pub const LSA_PATH: &str = "SYSTEM\\CurrentControlSet\\Control\\Lsa";
+/// Path of the system policies key (UAC settings) relative to `HKLM`
+pub const SYSTEM_POLICIES_PATH: &str =
+    "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Policies\\System";
+
+/// Severity of a finding, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+/// A single audit result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    /// `HKLM\<key path>\<value name>` the finding is about
+    pub location: String,
+    pub description: String,
+}
+
+/// Autologon configuration from `Winlogon`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WinlogonSettings {
+    pub auto_admin_logon: bool,
+    pub default_user_name: Option<String>,
+    pub default_domain_name: Option<String>,
+    /// A clear-text `DefaultPassword` is stored
+    pub default_password_present: bool,
+}
+
+/// Authentication settings from `Lsa`, `None` when the value is absent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsaSettings {
+    pub lm_compatibility_level: Option<u32>,
+    pub no_lm_hash: Option<bool>,
+    pub restrict_anonymous: Option<u32>,
+    pub restrict_anonymous_sam: Option<bool>,
+    /// `RunAsPPL`, LSA protection
+    pub run_as_ppl: Option<u32>,
+}
+
+/// UAC settings from the system policies key, `None` when the value is absent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UacSettings {
+    pub enable_lua: Option<bool>,
+    pub consent_prompt_behavior_admin: Option<u32>,
+    pub prompt_on_secure_desktop: Option<bool>,
+    pub filter_administrator_token: Option<bool>,
+}
+
+/// Read the autologon configuration
+pub fn winlogon() -> io::Result<WinlogonSettings> {
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(WINLOGON_PATH)?;
+    let string = |name: &str| {
+        key.get_value::<String, _>(name)
+            .ok()
+            .filter(|s| !s.is_empty())
+    };
+    Ok(WinlogonSettings {
+        // stored as REG_SZ "1", but REG_DWORD is seen in the wild too
+        auto_admin_logon: string("AutoAdminLogon")
+            .map(|s| s.trim() == "1")
+            .or_else(|| {
+                key.get_value::<u32, _>("AutoAdminLogon")
+                    .ok()
+                    .map(|v| v != 0)
+            })
+            .unwrap_or(false),
+        default_user_name: string("DefaultUserName"),
+        default_domain_name: string("DefaultDomainName"),
+        default_password_present: key
+            .get_raw_value("DefaultPassword")
+            .map(|v| v.bytes.iter().any(|&b| b != 0))
+            .unwrap_or(false),
+    })
+}
+
+/// Read LSA authentication settings
+pub fn lsa() -> io::Result<LsaSettings> {
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(LSA_PATH)?;
+    Ok(LsaSettings {
+        lm_compatibility_level: dword(&key, "LmCompatibilityLevel"),
+        no_lm_hash: dword(&key, "NoLMHash").map(|v| v != 0),
+        restrict_anonymous: dword(&key, "RestrictAnonymous"),
+        restrict_anonymous_sam: dword(&key, "RestrictAnonymousSAM").map(|v| v != 0),
+        run_as_ppl: dword(&key, "RunAsPPL"),
+    })
+}
+
+/// Read UAC settings
+pub fn uac() -> io::Result<UacSettings> {
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(SYSTEM_POLICIES_PATH)?;
+    Ok(UacSettings {
+        enable_lua: dword(&key, "EnableLUA").map(|v| v != 0),
+        consent_prompt_behavior_admin: dword(&key, "ConsentPromptBehaviorAdmin"),
+        prompt_on_secure_desktop: dword(&key, "PromptOnSecureDesktop").map(|v| v != 0),
+        filter_administrator_token: dword(&key, "FilterAdministratorToken").map(|v| v != 0),
+    })
+}
+
+/// Run all checks and return the findings, most severe first
+pub fn audit() -> io::Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    audit_winlogon(&winlogon()?, &mut findings);
+    audit_lsa(&lsa()?, &mut findings);
+    audit_uac(&uac()?, &mut findings);
+    findings.sort_by_key(|f| Reverse(f.severity));
+    Ok(findings)
+}
+
+/// Evaluate autologon settings
+pub fn audit_winlogon(s: &WinlogonSettings, findings: &mut Vec<Finding>) {
+    if s.default_password_present {
+        findings.push(finding(
+            Severity::High,
+            WINLOGON_PATH,
+            "DefaultPassword",
+            "a clear-text logon password is stored in the registry",
+        ));
+    }
+    if s.auto_admin_logon {
+        findings.push(finding(
+            Severity::Medium,
+            WINLOGON_PATH,
+            "AutoAdminLogon",
+            "automatic logon is enabled",
+        ));
+    }
+}
+
+/// Evaluate LSA authentication settings
+pub fn audit_lsa(s: &LsaSettings, findings: &mut Vec<Finding>) {
+    // the default since Vista is 3: send NTLMv2 only
+    match s.lm_compatibility_level {
+        Some(level) if level < 3 => findings.push(finding(
+            Severity::High,
+            LSA_PATH,
+            "LmCompatibilityLevel",
+            "LM or NTLMv1 responses are sent",
+        )),
+        _ => {}
+    }
+    if s.no_lm_hash == Some(false) {
+        findings.push(finding(
+            Severity::High,
+            LSA_PATH,
+            "NoLMHash",
+            "LM password hashes are stored",
+        ));
+    }
+    if s.restrict_anonymous_sam == Some(false) {
+        findings.push(finding(
+            Severity::Medium,
+            LSA_PATH,
+            "RestrictAnonymousSAM",
+            "anonymous enumeration of SAM accounts is allowed",
+        ));
+    }
+    if s.run_as_ppl.map_or(true, |v| v == 0) {
+        findings.push(finding(
+            Severity::Low,
+            LSA_PATH,
+            "RunAsPPL",
+            "LSA protection is not enabled",
+        ));
+    }
+}
+
+/// Evaluate UAC settings
+pub fn audit_uac(s: &UacSettings, findings: &mut Vec<Finding>) {
+    if s.enable_lua == Some(false) {
+        findings.push(finding(
+            Severity::High,
+            SYSTEM_POLICIES_PATH,
+            "EnableLUA",
+            "UAC is disabled",
+        ));
+    }
+    if s.consent_prompt_behavior_admin == Some(0) {
+        findings.push(finding(
+            Severity::Medium,
+            SYSTEM_POLICIES_PATH,
+            "ConsentPromptBehaviorAdmin",
+            "administrators are elevated without prompting",
+        ));
+    }
+    if s.prompt_on_secure_desktop == Some(false) {
+        findings.push(finding(
+            Severity::Low,
+            SYSTEM_POLICIES_PATH,
+            "PromptOnSecureDesktop",
+            "elevation prompts are not shown on the secure desktop",
+        ));
+    }
+}
+
+fn dword(key: &RegKey, name: &str) -> Option<u32> {
+    key.get_value(name).ok()
+}
+
+fn finding(severity: Severity, path: &str, value: &str, description: &str) -> Finding {
+    Finding {
+        severity,
+        location: format!("HKLM\\{}\\{}", path, value),
+        description: description.to_owned(),
+    }
+}
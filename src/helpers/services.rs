@@ -0,0 +1,245 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Windows service configuration as stored under
+//! `HKLM\SYSTEM\CurrentControlSet\Services`.
+//!
+//! Changes made here are picked up by the Service Control Manager on the next boot.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::services::{self, StartType};
+//!
+//!fn main() -> io::Result<()> {
+//!    let spooler = services::read("Spooler")?;
+//!    println!("{:?} {:?}", spooler.start_type, spooler.image_path);
+//!    services::set_start_type("Spooler", StartType::Manual)?;
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::{RegKey, RegValue};
+use std::convert::TryInto;
+use std::io;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::System::Registry;
+
+/// Path of the services key relative to `HKLM`
+pub const SERVICES_PATH: &str = "SYSTEM\\CurrentControlSet\\Services";
+
+/// `Start` value of a service
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartType {
+    Boot,
+    System,
+    Automatic,
+    Manual,
+    Disabled,
+}
+
+impl StartType {
+    pub fn from_u32(v: u32) -> Option<StartType> {
+        match v {
+            0 => Some(StartType::Boot),
+            1 => Some(StartType::System),
+            2 => Some(StartType::Automatic),
+            3 => Some(StartType::Manual),
+            4 => Some(StartType::Disabled),
+            _ => None,
+        }
+    }
+
+    pub const fn to_u32(self) -> u32 {
+        match self {
+            StartType::Boot => 0,
+            StartType::System => 1,
+            StartType::Automatic => 2,
+            StartType::Manual => 3,
+            StartType::Disabled => 4,
+        }
+    }
+}
+
+/// Action taken by the Service Control Manager when a service fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureActionType {
+    None,
+    Restart,
+    Reboot,
+    RunCommand,
+    Unknown(u32),
+}
+
+impl FailureActionType {
+    fn from_u32(v: u32) -> FailureActionType {
+        match v {
+            0 => FailureActionType::None,
+            1 => FailureActionType::Restart,
+            2 => FailureActionType::Reboot,
+            3 => FailureActionType::RunCommand,
+            v => FailureActionType::Unknown(v),
+        }
+    }
+
+    const fn to_u32(self) -> u32 {
+        match self {
+            FailureActionType::None => 0,
+            FailureActionType::Restart => 1,
+            FailureActionType::Reboot => 2,
+            FailureActionType::RunCommand => 3,
+            FailureActionType::Unknown(v) => v,
+        }
+    }
+}
+
+/// A single entry of `FailureActions`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailureAction {
+    pub action: FailureActionType,
+    pub delay_ms: u32,
+}
+
+/// Parsed `FailureActions` value, the registry form of `SERVICE_FAILURE_ACTIONS`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailureActions {
+    /// Seconds without failures after which the failure count is reset
+    pub reset_period: u32,
+    pub actions: Vec<FailureAction>,
+}
+
+// dwResetPeriod, lpRebootMsg, lpCommand, cActions and lpsaActions, 32 bits each
+const FAILURE_ACTIONS_HEADER_LEN: usize = 20;
+const FAILURE_ACTION_LEN: usize = 8;
+
+impl FailureActions {
+    /// Parse the binary `FailureActions` value
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<FailureActions> {
+        let dword = |offset: usize| -> io::Result<u32> {
+            bytes
+                .get(offset..offset + 4)
+                .and_then(|b| b.try_into().ok())
+                .map(u32::from_le_bytes)
+                .ok_or_else(|| io::Error::from_raw_os_error(Foundation::ERROR_INVALID_DATA as i32))
+        };
+        let reset_period = dword(0)?;
+        let count = dword(12)? as usize;
+        let mut actions = Vec::with_capacity(count.min(16));
+        for i in 0..count {
+            let offset = FAILURE_ACTIONS_HEADER_LEN + i * FAILURE_ACTION_LEN;
+            actions.push(FailureAction {
+                action: FailureActionType::from_u32(dword(offset)?),
+                delay_ms: dword(offset + 4)?,
+            });
+        }
+        Ok(FailureActions {
+            reset_period,
+            actions,
+        })
+    }
+
+    /// Serialize to the binary `FailureActions` value
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            FAILURE_ACTIONS_HEADER_LEN + self.actions.len() * FAILURE_ACTION_LEN,
+        );
+        let has_actions = !self.actions.is_empty() as u32;
+        for v in &[
+            self.reset_period,
+            0,
+            0,
+            self.actions.len() as u32,
+            has_actions,
+        ] {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        for a in &self.actions {
+            bytes.extend_from_slice(&a.action.to_u32().to_le_bytes());
+            bytes.extend_from_slice(&a.delay_ms.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// Registry configuration of a service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceConfig {
+    /// Name of the service key
+    pub name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    /// `Type`, a combination of `SERVICE_*` type flags
+    pub service_type: Option<u32>,
+    pub start_type: Option<StartType>,
+    /// Unexpanded `ImagePath`
+    pub image_path: Option<String>,
+    /// Account the service runs as
+    pub object_name: Option<String>,
+    pub delayed_autostart: bool,
+    pub failure_actions: Option<FailureActions>,
+}
+
+/// Return names of all registered services and drivers
+pub fn list() -> io::Result<Vec<String>> {
+    services_key(KEY_READ)?.enum_keys().collect()
+}
+
+/// Read the configuration of a service
+pub fn read(name: &str) -> io::Result<ServiceConfig> {
+    let key = services_key(KEY_READ)?.open_subkey(name)?;
+    let string = |n: &str| key.get_value::<String, _>(n).ok();
+    Ok(ServiceConfig {
+        name: name.to_owned(),
+        display_name: string("DisplayName"),
+        description: string("Description"),
+        service_type: key.get_value("Type").ok(),
+        start_type: key.get_value("Start").ok().and_then(StartType::from_u32),
+        image_path: string("ImagePath"),
+        object_name: string("ObjectName"),
+        delayed_autostart: key
+            .get_value::<u32, _>("DelayedAutostart")
+            .map(|v| v != 0)
+            .unwrap_or(false),
+        failure_actions: match key.get_raw_value("FailureActions") {
+            Ok(v) => Some(FailureActions::from_bytes(&v.bytes)?),
+            Err(_) => None,
+        },
+    })
+}
+
+/// Change the `Start` value of an existing service
+pub fn set_start_type(name: &str, start_type: StartType) -> io::Result<()> {
+    open_for_write(name)?.set_value("Start", &start_type.to_u32())
+}
+
+/// Set or clear `DelayedAutostart`, only effective for `StartType::Automatic` services
+pub fn set_delayed_autostart(name: &str, delayed: bool) -> io::Result<()> {
+    open_for_write(name)?.set_value("DelayedAutostart", &(delayed as u32))
+}
+
+/// Change the `Description` of an existing service
+pub fn set_description(name: &str, description: &str) -> io::Result<()> {
+    open_for_write(name)?.set_value("Description", &description)
+}
+
+/// Replace the `FailureActions` of an existing service
+pub fn set_failure_actions(name: &str, actions: &FailureActions) -> io::Result<()> {
+    open_for_write(name)?.set_raw_value(
+        "FailureActions",
+        &RegValue {
+            bytes: actions.to_bytes(),
+            vtype: REG_BINARY,
+        },
+    )
+}
+
+fn services_key(perms: Registry::REG_SAM_FLAGS) -> io::Result<RegKey> {
+    RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey_with_flags(SERVICES_PATH, perms)
+}
+
+/// Open an existing service key, never creating one
+fn open_for_write(name: &str) -> io::Result<RegKey> {
+    services_key(KEY_READ)?.open_subkey_with_flags(name, KEY_READ | KEY_SET_VALUE)
+}
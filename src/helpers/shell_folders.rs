@@ -0,0 +1,125 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Per-user shell folder locations from
+//! `HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer`.
+//!
+//! `User Shell Folders` is the authoritative key and holds unexpanded
+//! `REG_EXPAND_SZ` paths, `Shell Folders` is a legacy cache of the expanded ones.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::shell_folders::{self, Folder};
+//!
+//!fn main() -> io::Result<()> {
+//!    println!("Documents: {}", shell_folders::get(&Folder::Documents)?.display());
+//!    shell_folders::set(&Folder::Downloads, "%USERPROFILE%\\Downloads")?;
+//!    Ok(())
+//!}
+//!```
+use crate::common::*;
+use crate::enums::*;
+use crate::{RegKey, RegValue};
+use std::io;
+use std::path::PathBuf;
+
+/// Path of the `User Shell Folders` key relative to `HKCU`
+pub const USER_SHELL_FOLDERS_PATH: &str =
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\User Shell Folders";
+/// Path of the legacy `Shell Folders` key relative to `HKCU`
+pub const SHELL_FOLDERS_PATH: &str =
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Shell Folders";
+
+/// A per-user shell folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Folder {
+    Desktop,
+    Documents,
+    Downloads,
+    Music,
+    Pictures,
+    Videos,
+    Favorites,
+    /// Roaming application data
+    AppData,
+    LocalAppData,
+    StartMenu,
+    Startup,
+    Programs,
+    Templates,
+    Recent,
+    SendTo,
+    /// Any other value name, e.g. a Known Folder GUID
+    Custom(String),
+}
+
+impl Folder {
+    /// Name of the value holding this folder's path
+    pub fn value_name(&self) -> &str {
+        match *self {
+            Folder::Desktop => "Desktop",
+            Folder::Documents => "Personal",
+            Folder::Downloads => "{374DE290-123F-4565-9164-39C4925E467B}",
+            Folder::Music => "My Music",
+            Folder::Pictures => "My Pictures",
+            Folder::Videos => "My Video",
+            Folder::Favorites => "Favorites",
+            Folder::AppData => "AppData",
+            Folder::LocalAppData => "Local AppData",
+            Folder::StartMenu => "Start Menu",
+            Folder::Startup => "Startup",
+            Folder::Programs => "Programs",
+            Folder::Templates => "Templates",
+            Folder::Recent => "Recent",
+            Folder::SendTo => "SendTo",
+            Folder::Custom(ref name) => name,
+        }
+    }
+}
+
+/// Resolve the path of a folder. `User Shell Folders` is read and expanded first,
+/// `Shell Folders` is used if the value is missing there.
+pub fn get(folder: &Folder) -> io::Result<PathBuf> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let name = folder.value_name();
+    let user = hkcu
+        .open_subkey(USER_SHELL_FOLDERS_PATH)
+        .and_then(|k| k.get_value::<String, _>(name));
+    match user {
+        Ok(path) => Ok(PathBuf::from(expand_env_strings(path)?)),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => hkcu
+            .open_subkey(SHELL_FOLDERS_PATH)?
+            .get_value::<String, _>(name)
+            .map(PathBuf::from),
+        Err(e) => Err(e),
+    }
+}
+
+/// Unexpanded path of a folder as stored in `User Shell Folders`
+pub fn get_unexpanded(folder: &Folder) -> io::Result<String> {
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(USER_SHELL_FOLDERS_PATH)?
+        .get_value(folder.value_name())
+}
+
+/// Change the location of a folder. `path` may contain `%VAR%` references;
+/// it is stored as `REG_EXPAND_SZ` in `User Shell Folders` and expanded in `Shell Folders`.
+/// Existing content is not moved.
+pub fn set(folder: &Folder, path: &str) -> io::Result<()> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let name = folder.value_name();
+    let expanded = expand_env_strings(path)?;
+    let (user, _disp) = hkcu.create_subkey(USER_SHELL_FOLDERS_PATH)?;
+    user.set_raw_value(
+        name,
+        &RegValue {
+            bytes: v16_to_v8(&to_utf16(path)),
+            vtype: REG_EXPAND_SZ,
+        },
+    )?;
+    let (legacy, _disp) = hkcu.create_subkey(SHELL_FOLDERS_PATH)?;
+    legacy.set_value(name, &expanded)
+}
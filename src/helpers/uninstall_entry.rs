@@ -0,0 +1,243 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Add/Remove Programs (Apps & Features) entries.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::Scope;
+//!use winreg2::helpers::uninstall_entry::UninstallEntry;
+//!
+//!fn main() -> io::Result<()> {
+//!    let entry = UninstallEntry::new("MyProduct", "My Product")
+//!        .scope(Scope::CurrentUser)
+//!        .display_version("1.2.3")
+//!        .publisher("ACME")
+//!        .install_location("C:\\Users\\me\\AppData\\Local\\MyProduct")
+//!        .uninstall_string("\"C:\\Users\\me\\AppData\\Local\\MyProduct\\uninstall.exe\"")
+//!        .no_modify(true)
+//!        .no_repair(true);
+//!    entry.register()?;
+//!    // ...
+//!    entry.remove()?;
+//!    Ok(())
+//!}
+//!```
+use super::{delete_value_if_exists, wow64_view, Scope};
+use crate::enums::*;
+use crate::RegKey;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use windows_sys::Win32::System::Registry;
+
+/// Path of the `Uninstall` key relative to `HKCU` or `HKLM`
+pub const UNINSTALL_PATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall";
+
+/// Builder for a single `Uninstall\<id>` entry
+#[derive(Debug, Clone)]
+pub struct UninstallEntry {
+    id: String,
+    display_name: String,
+    display_version: Option<String>,
+    publisher: Option<String>,
+    install_location: Option<PathBuf>,
+    uninstall_string: Option<String>,
+    estimated_size: Option<u32>,
+    no_modify: bool,
+    no_repair: bool,
+    scope: Scope,
+    wow64_32: bool,
+}
+
+impl UninstallEntry {
+    /// Create an entry with the key name `id` and the `DisplayName` shown in Apps & Features.
+    /// Defaults to the per-machine 64-bit registry view.
+    pub fn new<I: Into<String>, N: Into<String>>(id: I, display_name: N) -> UninstallEntry {
+        UninstallEntry {
+            id: id.into(),
+            display_name: display_name.into(),
+            display_version: None,
+            publisher: None,
+            install_location: None,
+            uninstall_string: None,
+            estimated_size: None,
+            no_modify: false,
+            no_repair: false,
+            scope: Scope::LocalMachine,
+            wow64_32: false,
+        }
+    }
+
+    /// Register per-user or per-machine
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Use the 32-bit registry view (`WOW6432Node`), which is where
+    /// Apps & Features looks for entries of 32-bit applications
+    pub fn wow64_32(mut self, wow64_32: bool) -> Self {
+        self.wow64_32 = wow64_32;
+        self
+    }
+
+    pub fn display_version<S: Into<String>>(mut self, version: S) -> Self {
+        self.display_version = Some(version.into());
+        self
+    }
+
+    pub fn publisher<S: Into<String>>(mut self, publisher: S) -> Self {
+        self.publisher = Some(publisher.into());
+        self
+    }
+
+    /// Install directory. Unless `estimated_size` is set explicitly,
+    /// `EstimatedSize` is computed from the size of this directory.
+    pub fn install_location<P: Into<PathBuf>>(mut self, location: P) -> Self {
+        self.install_location = Some(location.into());
+        self
+    }
+
+    pub fn uninstall_string<S: Into<String>>(mut self, command: S) -> Self {
+        self.uninstall_string = Some(command.into());
+        self
+    }
+
+    /// Override the installed size, in KiB
+    pub fn estimated_size(mut self, kib: u32) -> Self {
+        self.estimated_size = Some(kib);
+        self
+    }
+
+    pub fn no_modify(mut self, no_modify: bool) -> Self {
+        self.no_modify = no_modify;
+        self
+    }
+
+    pub fn no_repair(mut self, no_repair: bool) -> Self {
+        self.no_repair = no_repair;
+        self
+    }
+
+    /// Name of the entry's key under `Uninstall`
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Create the entry (or overwrite an existing one) and write all its values
+    pub fn register(&self) -> io::Result<()> {
+        let (key, _disp) = self
+            .scope
+            .root()
+            .create_subkey_with_flags(self.path(), KEY_ALL_ACCESS | self.view())?;
+        self.write_values(&key)
+    }
+
+    /// Rewrite the values of an existing entry.
+    /// Fails with `io::ErrorKind::NotFound` if the entry is not registered.
+    pub fn update(&self) -> io::Result<()> {
+        let key = self
+            .scope
+            .root()
+            .open_subkey_with_flags(self.path(), KEY_READ | KEY_WRITE | self.view())?;
+        self.write_values(&key)
+    }
+
+    /// Delete the entry with all its values and subkeys
+    pub fn remove(&self) -> io::Result<()> {
+        self.scope
+            .root()
+            .open_subkey_with_flags(UNINSTALL_PATH, KEY_ALL_ACCESS | self.view())?
+            .delete_subkey_all(&self.id)
+    }
+
+    /// Check whether the entry is registered
+    pub fn exists(&self) -> io::Result<bool> {
+        match self
+            .scope
+            .root()
+            .open_subkey_with_flags(self.path(), KEY_READ | self.view())
+        {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn path(&self) -> String {
+        format!("{}\\{}", UNINSTALL_PATH, self.id)
+    }
+
+    fn view(&self) -> Registry::REG_SAM_FLAGS {
+        wow64_view(self.wow64_32)
+    }
+
+    fn write_values(&self, key: &RegKey) -> io::Result<()> {
+        key.set_value("DisplayName", &self.display_name)?;
+        set_or_delete(key, "DisplayVersion", self.display_version.as_deref())?;
+        set_or_delete(key, "Publisher", self.publisher.as_deref())?;
+        set_or_delete(
+            key,
+            "InstallLocation",
+            self.install_location.as_ref().map(|p| p.as_os_str()),
+        )?;
+        set_or_delete(key, "UninstallString", self.uninstall_string.as_deref())?;
+        let size = match (self.estimated_size, &self.install_location) {
+            (Some(size), _) => Some(size),
+            (None, Some(location)) => Some(estimate_size(location)?),
+            (None, None) => None,
+        };
+        set_or_delete(key, "EstimatedSize", size)?;
+        set_flag(key, "NoModify", self.no_modify)?;
+        set_flag(key, "NoRepair", self.no_repair)
+    }
+}
+
+fn set_or_delete<T: crate::types::ToRegValue>(
+    key: &RegKey,
+    name: &str,
+    value: Option<T>,
+) -> io::Result<()> {
+    match value {
+        Some(v) => key.set_value(name, &v),
+        None => delete_value_if_exists(key, name),
+    }
+}
+
+fn set_flag(key: &RegKey, name: &str, flag: bool) -> io::Result<()> {
+    if flag {
+        key.set_value(name, &1u32)
+    } else {
+        delete_value_if_exists(key, name)
+    }
+}
+
+/// Size of a directory tree in KiB, saturating at `u32::MAX`
+fn estimate_size(dir: &Path) -> io::Result<u32> {
+    fn walk(dir: &Path) -> io::Result<u64> {
+        let mut total = 0u64;
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                total += walk(&entry.path())?;
+            } else if file_type.is_file() {
+                total += entry.metadata()?.len();
+            }
+        }
+        Ok(total)
+    }
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+    let kib = (walk(dir)? + 1023) / 1024;
+    Ok(if kib > u32::MAX as u64 {
+        u32::MAX
+    } else {
+        kib as u32
+    })
+}
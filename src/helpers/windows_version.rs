@@ -0,0 +1,156 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Windows version and edition as recorded under
+//! `HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion`.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::helpers::windows_version::{self, Version};
+//!
+//!fn main() -> io::Result<()> {
+//!    let v = windows_version::current()?;
+//!    println!("{} {} ({})", v.product_name, v.display_version.unwrap_or_default(), v.version);
+//!    if v.version >= Version::new(10, 0, 22000, 0) {
+//!        println!("Windows 11 or newer");
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::RegKey;
+use std::fmt;
+use std::io;
+
+/// Path of the `CurrentVersion` key relative to `HKLM`
+pub const CURRENT_VERSION_PATH: &str = "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion";
+
+/// First build number of Windows 11
+const WINDOWS_11_BUILD: u32 = 22000;
+
+/// Numeric OS version, ordered component by component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub build: u32,
+    /// Update build revision (`UBR`)
+    pub revision: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, build: u32, revision: u32) -> Version {
+        Version {
+            major,
+            minor,
+            build,
+            revision,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.build, self.revision
+        )
+    }
+}
+
+/// Installation type of the OS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallationType {
+    Client,
+    Server,
+    ServerCore,
+    Other(String),
+}
+
+/// Version and edition information of the running Windows installation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowsVersion {
+    pub version: Version,
+    /// `ProductName`, note that Windows 11 still reports "Windows 10" here
+    pub product_name: String,
+    /// `DisplayVersion` (e.g. `23H2`), falls back to `ReleaseId` (e.g. `1909`) on older systems
+    pub display_version: Option<String>,
+    /// `EditionID` (e.g. `Professional`, `ServerDatacenter`)
+    pub edition_id: Option<String>,
+    pub installation_type: Option<InstallationType>,
+}
+
+impl WindowsVersion {
+    /// Windows 11 (or a newer client release), which is not reflected in `product_name`
+    pub fn is_windows_11(&self) -> bool {
+        !self.is_server() && self.version.major == 10 && self.version.build >= WINDOWS_11_BUILD
+    }
+
+    pub fn is_server(&self) -> bool {
+        match self.installation_type {
+            Some(InstallationType::Server) | Some(InstallationType::ServerCore) => true,
+            Some(_) => false,
+            None => self
+                .edition_id
+                .as_ref()
+                .map_or(false, |e| e.starts_with("Server")),
+        }
+    }
+}
+
+/// Read the version of the running system
+pub fn current() -> io::Result<WindowsVersion> {
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(CURRENT_VERSION_PATH)?;
+    read(&key)
+}
+
+/// Read version information from a `CurrentVersion`-like key,
+/// e.g. from a mounted offline `SOFTWARE` hive
+pub fn read(key: &RegKey) -> io::Result<WindowsVersion> {
+    let string = |name: &str| {
+        key.get_value::<String, _>(name)
+            .ok()
+            .filter(|s| !s.is_empty())
+    };
+
+    // CurrentMajor/MinorVersionNumber exist since Windows 10,
+    // CurrentVersion is frozen at "6.3" there
+    let (major, minor) = match (
+        key.get_value::<u32, _>("CurrentMajorVersionNumber"),
+        key.get_value::<u32, _>("CurrentMinorVersionNumber"),
+    ) {
+        (Ok(major), Ok(minor)) => (major, minor),
+        _ => string("CurrentVersion")
+            .and_then(|v| parse_major_minor(&v))
+            .unwrap_or((0, 0)),
+    };
+    let build = string("CurrentBuildNumber")
+        .or_else(|| string("CurrentBuild"))
+        .and_then(|b| b.trim().parse().ok())
+        .unwrap_or(0);
+    let revision = key.get_value::<u32, _>("UBR").unwrap_or(0);
+
+    Ok(WindowsVersion {
+        version: Version::new(major, minor, build, revision),
+        product_name: string("ProductName").unwrap_or_default(),
+        display_version: string("DisplayVersion").or_else(|| string("ReleaseId")),
+        edition_id: string("EditionID"),
+        installation_type: string("InstallationType").map(|t| match t.as_str() {
+            "Client" => InstallationType::Client,
+            "Server" => InstallationType::Server,
+            "Server Core" => InstallationType::ServerCore,
+            _ => InstallationType::Other(t),
+        }),
+    })
+}
+
+fn parse_major_minor(s: &str) -> Option<(u32, u32)> {
+    let mut parts = s.trim().splitn(2, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().unwrap_or("0").parse().ok()?;
+    Some((major, minor))
+}
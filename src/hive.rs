@@ -0,0 +1,397 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reading hive files (`NTUSER.DAT`, `SYSTEM`, ...) directly, without loading them.
+//!
+//! The file is parsed in memory, so hives copied from other machines or disk
+//! images can be inspected without privileges. Transaction logs (`.LOG1`, `.LOG2`)
+//! are not replayed, so a hive copied from a running system may miss recent writes.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::hive::Hive;
+//!
+//!fn main() -> io::Result<()> {
+//!    let hive = Hive::open("C:\\Images\\Windows\\System32\\config\\SOFTWARE")?;
+//!    let cur_ver = hive.root()?.open_subkey("Microsoft\\Windows NT\\CurrentVersion")?;
+//!    let product: String = cur_ver.get_value("ProductName")?;
+//!    println!("{}", product);
+//!    for name in cur_ver.enum_keys() {
+//!        println!("{}", name?);
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::reg_key_metadata::FileTime;
+use crate::types::FromRegValue;
+use crate::util;
+use crate::RegValue;
+use std::convert::TryInto;
+use std::fs;
+use std::io;
+use std::path::Path;
+use windows_sys::Win32::Foundation;
+
+/// Cell offsets are relative to the first hive bin, which follows the base block
+const BASE_BLOCK_SIZE: usize = 0x1000;
+/// Name of a key or value is stored as Latin-1 rather than UTF-16
+const KEY_COMP_NAME: u16 = 0x20;
+const VALUE_COMP_NAME: u16 = 0x01;
+/// Set in the data size of a value when the data is stored in place of its offset
+const DATA_IN_OFFSET: u32 = 0x8000_0000;
+/// Values larger than this are split into segments of a `db` cell
+const MAX_CELL_DATA: usize = 16344;
+/// Invalid cell offset, e.g. of a missing list
+const NO_CELL: u32 = 0xffff_ffff;
+
+/// A hive file read into memory
+pub struct Hive {
+    data: Vec<u8>,
+    minor_version: u32,
+    root_offset: u32,
+}
+
+impl Hive {
+    /// Read the hive file at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Hive> {
+        Hive::from_bytes(fs::read(path)?)
+    }
+
+    /// Parse a hive from its contents. Fails with `ERROR_BADDB` if it's not a hive.
+    pub fn from_bytes(data: Vec<u8>) -> io::Result<Hive> {
+        if data.len() < BASE_BLOCK_SIZE || &data[..4] != b"regf" {
+            return werr!(Foundation::ERROR_BADDB);
+        }
+        let minor_version = read_u32(&data, 0x18)?;
+        let root_offset = read_u32(&data, 0x24)?;
+        Ok(Hive {
+            data,
+            minor_version,
+            root_offset,
+        })
+    }
+
+    /// Root key of the hive
+    pub fn root(&self) -> io::Result<HiveKey<'_>> {
+        HiveKey::new(self, self.root_offset)
+    }
+
+    /// Data of the cell at `offset`, without its size
+    fn cell(&self, offset: u32) -> io::Result<&[u8]> {
+        // offsets near `u32::MAX` overflow `usize` on 32-bit targets
+        let start = match BASE_BLOCK_SIZE.checked_add(offset as usize) {
+            Some(start) => start,
+            None => return werr!(Foundation::ERROR_BADDB),
+        };
+        let size = read_u32(&self.data, start)? as i32;
+        // allocated cells have a negative size
+        let len = (size as i64).abs() as usize;
+        let cell = start
+            .checked_add(len.max(4))
+            .and_then(|end| self.data.get(start + 4..end));
+        match cell {
+            Some(cell) => Ok(cell),
+            None => werr!(Foundation::ERROR_BADDB),
+        }
+    }
+
+    /// Offsets of the cells listed in a subkey list, following `ri` indexes
+    fn subkey_offsets(&self, list: u32, offsets: &mut Vec<u32>, nested: bool) -> io::Result<()> {
+        let cell = self.cell(list)?;
+        let count = read_u16(cell, 2)? as usize;
+        let (stride, index) = match cell.get(..2) {
+            Some(b"lf") | Some(b"lh") => (8, false),
+            Some(b"li") => (4, false),
+            Some(b"ri") if !nested => (4, true),
+            _ => return werr!(Foundation::ERROR_BADDB),
+        };
+        for i in 0..count {
+            let offset = read_u32(cell, 4 + i * stride)?;
+            if index {
+                self.subkey_offsets(offset, offsets, true)?;
+            } else {
+                offsets.push(offset);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A key of a `Hive`, with an API following `RegKey`
+pub struct HiveKey<'hive> {
+    hive: &'hive Hive,
+    /// Data of the `nk` cell
+    cell: &'hive [u8],
+    name: String,
+}
+
+impl<'hive> HiveKey<'hive> {
+    fn new(hive: &'hive Hive, offset: u32) -> io::Result<HiveKey<'hive>> {
+        let cell = hive.cell(offset)?;
+        if cell.get(..2) != Some(b"nk") {
+            return werr!(Foundation::ERROR_BADDB);
+        }
+        let flags = read_u16(cell, 0x02)?;
+        let name_len = read_u16(cell, 0x48)? as usize;
+        let name = match cell.get(0x4c..0x4c + name_len) {
+            Some(name) => decode_name(name, flags & KEY_COMP_NAME != 0),
+            None => return werr!(Foundation::ERROR_BADDB),
+        };
+        Ok(HiveKey { hive, cell, name })
+    }
+
+    /// Name of the key, the root key of a hive has an implementation-defined name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Time of the last change of the key
+    pub fn last_write_time(&self) -> io::Result<FileTime> {
+        let time = read_u64(self.cell, 0x04)?;
+        Ok(FileTime(Foundation::FILETIME {
+            dwLowDateTime: time as u32,
+            dwHighDateTime: (time >> 32) as u32,
+        }))
+    }
+
+    /// Class of the key, empty if it has none
+    pub fn class(&self) -> io::Result<String> {
+        let offset = read_u32(self.cell, 0x30)?;
+        let len = read_u16(self.cell, 0x4a)? as usize;
+        if offset == NO_CELL || len == 0 {
+            return Ok(String::new());
+        }
+        match self.hive.cell(offset)?.get(..len) {
+            Some(class) => Ok(decode_name(class, false)),
+            None => werr!(Foundation::ERROR_BADDB),
+        }
+    }
+
+    /// Open a subkey by a relative path, names are compared case-insensitively.
+    /// Fails with `ERROR_FILE_NOT_FOUND` if it doesn't exist.
+    pub fn open_subkey<P: AsRef<str>>(&self, path: P) -> io::Result<HiveKey<'hive>> {
+        let mut key = HiveKey {
+            hive: self.hive,
+            cell: self.cell,
+            name: self.name.clone(),
+        };
+        for name in path.as_ref().split('\\').filter(|n| !n.is_empty()) {
+            let mut found = None;
+            for offset in key.subkey_offsets()? {
+                let subkey = HiveKey::new(self.hive, offset)?;
+                if util::name_eq(&subkey.name, name) {
+                    found = Some(subkey);
+                    break;
+                }
+            }
+            key = match found {
+                Some(subkey) => subkey,
+                None => return werr!(Foundation::ERROR_FILE_NOT_FOUND),
+            };
+        }
+        Ok(key)
+    }
+
+    /// Iterate over the names of the subkeys
+    pub fn enum_keys(&self) -> EnumKeys<'hive> {
+        let (offsets, error) = match self.subkey_offsets() {
+            Ok(offsets) => (offsets, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        EnumKeys {
+            hive: self.hive,
+            offsets,
+            error,
+            index: 0,
+        }
+    }
+
+    /// Iterate over the names and data of the values
+    pub fn enum_values(&self) -> EnumValues<'hive> {
+        let (offsets, error) = match self.value_offsets() {
+            Ok(offsets) => (offsets, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        EnumValues {
+            hive: self.hive,
+            offsets,
+            error,
+            index: 0,
+        }
+    }
+
+    /// Read the value `name`, the default value has an empty name.
+    /// Fails with `ERROR_FILE_NOT_FOUND` if it doesn't exist.
+    pub fn get_raw_value<N: AsRef<str>>(&self, name: N) -> io::Result<RegValue> {
+        for offset in self.value_offsets()? {
+            let (value_name, value) = read_value(self.hive, offset)?;
+            if util::name_eq(&value_name, name.as_ref()) {
+                return value;
+            }
+        }
+        werr!(Foundation::ERROR_FILE_NOT_FOUND)
+    }
+
+    /// Read the value `name` converted to a rust type
+    pub fn get_value<T: FromRegValue, N: AsRef<str>>(&self, name: N) -> io::Result<T> {
+        T::from_reg_value(&self.get_raw_value(name)?)
+    }
+
+    fn subkey_offsets(&self) -> io::Result<Vec<u32>> {
+        let count = read_u32(self.cell, 0x14)?;
+        let list = read_u32(self.cell, 0x1c)?;
+        // the count comes from the file, the lists bound the actual number
+        let mut offsets = Vec::new();
+        if count > 0 && list != NO_CELL {
+            self.hive.subkey_offsets(list, &mut offsets, false)?;
+        }
+        Ok(offsets)
+    }
+
+    fn value_offsets(&self) -> io::Result<Vec<u32>> {
+        let count = read_u32(self.cell, 0x24)? as usize;
+        let list = read_u32(self.cell, 0x28)?;
+        if count == 0 || list == NO_CELL {
+            return Ok(Vec::new());
+        }
+        let cell = self.hive.cell(list)?;
+        if count > cell.len() / 4 {
+            return werr!(Foundation::ERROR_BADDB);
+        }
+        (0..count).map(|i| read_u32(cell, i * 4)).collect()
+    }
+}
+
+/// Iterator over subkey names of a `HiveKey`
+pub struct EnumKeys<'hive> {
+    hive: &'hive Hive,
+    offsets: Vec<u32>,
+    /// Failure to read the list, returned by the first call of `next`
+    error: Option<io::Error>,
+    index: usize,
+}
+
+impl Iterator for EnumKeys<'_> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        let offset = *self.offsets.get(self.index)?;
+        self.index += 1;
+        Some(HiveKey::new(self.hive, offset).map(|key| key.name))
+    }
+}
+
+/// Iterator over values of a `HiveKey`
+pub struct EnumValues<'hive> {
+    hive: &'hive Hive,
+    offsets: Vec<u32>,
+    /// Failure to read the list, returned by the first call of `next`
+    error: Option<io::Error>,
+    index: usize,
+}
+
+impl Iterator for EnumValues<'_> {
+    type Item = io::Result<(String, RegValue)>;
+
+    fn next(&mut self) -> Option<io::Result<(String, RegValue)>> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        let offset = *self.offsets.get(self.index)?;
+        self.index += 1;
+        Some(read_value(self.hive, offset).and_then(|(name, value)| Ok((name, value?))))
+    }
+}
+
+/// Name of the `vk` cell at `offset` and its data. The data is a separate result,
+/// so values of unsupported types can be skipped by name.
+fn read_value(hive: &Hive, offset: u32) -> io::Result<(String, io::Result<RegValue>)> {
+    let cell = hive.cell(offset)?;
+    if cell.get(..2) != Some(b"vk") {
+        return werr!(Foundation::ERROR_BADDB);
+    }
+    let name_len = read_u16(cell, 0x02)? as usize;
+    let flags = read_u16(cell, 0x10)?;
+    let name = match cell.get(0x14..0x14 + name_len) {
+        Some(name) => decode_name(name, flags & VALUE_COMP_NAME != 0),
+        None => return werr!(Foundation::ERROR_BADDB),
+    };
+    let vtype = RegType::from(read_u32(cell, 0x0c)?);
+    let bytes = read_data(hive, cell);
+    Ok((name, bytes.map(|bytes| RegValue { bytes, vtype })))
+}
+
+fn read_data(hive: &Hive, vk: &[u8]) -> io::Result<Vec<u8>> {
+    let size = read_u32(vk, 0x04)?;
+    let offset = read_u32(vk, 0x08)?;
+    if size & DATA_IN_OFFSET != 0 {
+        let len = (size & !DATA_IN_OFFSET) as usize;
+        return match vk.get(0x08..0x08 + len) {
+            Some(data) if len <= 4 => Ok(data.to_vec()),
+            _ => werr!(Foundation::ERROR_BADDB),
+        };
+    }
+    let len = size as usize;
+    let cell = hive.cell(offset)?;
+    // big data is split into segments since version 1.4
+    if len > MAX_CELL_DATA && hive.minor_version >= 4 && cell.get(..2) == Some(b"db") {
+        let count = read_u16(cell, 0x02)? as usize;
+        let segments = hive.cell(read_u32(cell, 0x04)?)?;
+        // the length comes from the file, it's checked after the segments are read
+        let mut data = Vec::new();
+        for i in 0..count {
+            let segment = hive.cell(read_u32(segments, i * 4)?)?;
+            let take = (len - data.len()).min(MAX_CELL_DATA).min(segment.len());
+            data.extend_from_slice(&segment[..take]);
+        }
+        if data.len() != len {
+            return werr!(Foundation::ERROR_BADDB);
+        }
+        return Ok(data);
+    }
+    match cell.get(..len) {
+        Some(data) => Ok(data.to_vec()),
+        None => werr!(Foundation::ERROR_BADDB),
+    }
+}
+
+fn decode_name(bytes: &[u8], compressed: bool) -> String {
+    if compressed {
+        // Latin-1, every byte is the code point
+        bytes.iter().map(|&b| b as char).collect()
+    } else {
+        let words: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&words)
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> io::Result<u16> {
+    match data.get(offset..).and_then(|d| d.get(..2)) {
+        Some(b) => Ok(u16::from_le_bytes(b.try_into().unwrap())),
+        None => werr!(Foundation::ERROR_BADDB),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> io::Result<u32> {
+    match data.get(offset..).and_then(|d| d.get(..4)) {
+        Some(b) => Ok(u32::from_le_bytes(b.try_into().unwrap())),
+        None => werr!(Foundation::ERROR_BADDB),
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> io::Result<u64> {
+    match data.get(offset..).and_then(|d| d.get(..8)) {
+        Some(b) => Ok(u64::from_le_bytes(b.try_into().unwrap())),
+        None => werr!(Foundation::ERROR_BADDB),
+    }
+}
@@ -114,21 +114,41 @@ cfg_if::cfg_if! {
     if #[cfg(not(windows))] {
         compile_error!("OS not supported. if your application is multi-platform, use `[target.'cfg(windows)'.dependencies] winreg2 = \"...\"`");
     } else {
-        pub use crate::reg_key::{EnumKeys, EnumValues, RegKey, HKEY};
+        pub use crate::reg_key::{
+            disable_predefined_cache, open_path, EnumKeyInfos, EnumKeys, EnumKeysOs, EnumKeysWithInfo, EnumValues, EnumValuesOs, KeyFlags,
+            KeyInfo, LoadedHive, PhysicalPath, RegKey, HKEY,
+        };
         pub use crate::reg_key_metadata::RegKeyMetadata;
         pub use crate::reg_value::RegValue;
 
         mod common;
+        pub mod copy;
         #[cfg(feature = "serialization-serde")]
         pub mod decoder;
+        pub mod diff;
+        pub mod dual_view;
         #[cfg(feature = "serialization-serde")]
         pub mod encoder;
         pub mod enums;
+        pub mod helpers;
+        pub mod hive;
+        pub mod notify;
+        pub mod path;
+        pub mod perf;
         pub mod reg_key;
         pub mod reg_key_metadata;
         pub mod reg_value;
+        pub mod search;
+        pub mod security;
+        pub mod serialization;
+        pub mod snapshot;
+        pub mod system;
         #[cfg(feature = "transactions")]
         pub mod transaction;
         pub mod types;
+        pub mod util;
+        pub mod virtualization;
+        pub mod walker;
+        pub mod watcher;
     }
 }
@@ -0,0 +1,256 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Change notifications for registry keys.
+//!
+//!```no_run
+//!use std::io;
+//!use std::time::Duration;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!
+//!fn main() -> io::Result<()> {
+//!    let env = RegKey::predef(HKEY_CURRENT_USER).open_subkey_with_flags("Environment", KEY_NOTIFY)?;
+//!    let event = env.watch_event(NotifyFilter::REG_NOTIFY_CHANGE_LAST_SET, false)?;
+//!    loop {
+//!        if event.wait(Some(Duration::from_secs(1)))? {
+//!            println!("environment changed");
+//!            event.rearm()?;
+//!        }
+//!    }
+//!}
+//!```
+//!
+//! With the `async` feature, `RegKey::watch_async` returns a `ChangeStream`
+//! that can be awaited without blocking a thread per key.
+#[cfg(feature = "async")]
+use crate::enums::*;
+use crate::RegKey;
+#[cfg(feature = "async")]
+use futures_core::Stream;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::ptr;
+#[cfg(feature = "async")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "async")]
+use std::sync::Mutex;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use windows_sys::Win32::Foundation;
+#[cfg(feature = "async")]
+use windows_sys::Win32::System::Registry;
+use windows_sys::Win32::System::Threading;
+
+/// Event signaled on a change of a key, returned by `RegKey::watch_event`.
+///
+/// A notification fires once, call `rearm` to get the next one.
+/// Unless `REG_NOTIFY_THREAD_AGNOSTIC` is used, the notification is canceled
+/// when the thread that registered it exits.
+pub struct ChangeEvent<'key> {
+    key: &'key RegKey,
+    handle: Foundation::HANDLE,
+    recursive: bool,
+    filter: u32,
+}
+
+impl<'key> ChangeEvent<'key> {
+    pub(crate) fn new(key: &'key RegKey, recursive: bool, filter: u32) -> io::Result<Self> {
+        // auto-reset, so a successful wait consumes the notification
+        let handle = unsafe { Threading::CreateEventW(ptr::null(), 0, 0, ptr::null()) };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let event = ChangeEvent {
+            key,
+            handle,
+            recursive,
+            filter,
+        };
+        event.rearm()?;
+        Ok(event)
+    }
+
+    /// Wait until a change happens, at most `timeout` if given.
+    /// Returns `false` on timeout.
+    pub fn wait(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        let millis = timeout.map_or(Threading::INFINITE, |t| {
+            t.as_millis().min(u128::from(Threading::INFINITE - 1)) as u32
+        });
+        match unsafe { Threading::WaitForSingleObject(self.handle, millis) } {
+            Foundation::WAIT_OBJECT_0 => Ok(true),
+            Foundation::WAIT_TIMEOUT => Ok(false),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// Register for the next change after the previous one was received
+    pub fn rearm(&self) -> io::Result<()> {
+        self.key
+            .notify_raw(self.recursive, self.filter, self.handle)
+    }
+
+    /// Event handle, e.g. for `WaitForMultipleObjects`
+    pub fn raw_handle(&self) -> Foundation::HANDLE {
+        self.handle
+    }
+}
+
+impl Drop for ChangeEvent<'_> {
+    fn drop(&mut self) {
+        unsafe { Foundation::CloseHandle(self.handle) };
+    }
+}
+
+#[cfg(feature = "async")]
+struct Shared {
+    fired: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+#[cfg(feature = "async")]
+unsafe extern "system" fn on_change(context: *mut std::ffi::c_void, _timed_out: u8) {
+    let shared = &*(context as *const Shared);
+    shared.fired.store(true, Ordering::SeqCst);
+    if let Some(waker) = shared.waker.lock().unwrap().take() {
+        waker.wake();
+    }
+}
+
+/// Stream of changes of a key, returned by `RegKey::watch_async`.
+///
+/// It is a `futures_core::Stream`, so it works with the combinators of
+/// `futures::StreamExt` or `tokio_stream::StreamExt`. Every item is a single
+/// notification, several changes made in quick succession may be reported as one.
+/// The stream never ends.
+///
+/// Part of `async` feature.
+#[cfg(feature = "async")]
+pub struct ChangeStream {
+    key: RegKey,
+    event: Foundation::HANDLE,
+    wait: Foundation::HANDLE,
+    shared: Box<Shared>,
+    recursive: bool,
+    filter: u32,
+}
+
+#[cfg(feature = "async")]
+unsafe impl Send for ChangeStream {}
+
+#[cfg(feature = "async")]
+impl ChangeStream {
+    pub(crate) fn new(key: &RegKey, recursive: bool, filter: u32) -> io::Result<Self> {
+        let key = key.reopen_with_flags(KEY_NOTIFY)?;
+        let event = unsafe { Threading::CreateEventW(ptr::null(), 0, 0, ptr::null()) };
+        if event.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let mut stream = ChangeStream {
+            key,
+            event,
+            wait: ptr::null_mut(),
+            shared: Box::new(Shared {
+                fired: AtomicBool::new(false),
+                waker: Mutex::new(None),
+            }),
+            recursive,
+            // polls may happen on any thread of an executor
+            filter: filter | Registry::REG_NOTIFY_THREAD_AGNOSTIC,
+        };
+        stream.rearm()?;
+        let registered = unsafe {
+            Threading::RegisterWaitForSingleObject(
+                &mut stream.wait,
+                stream.event,
+                Some(on_change),
+                &*stream.shared as *const Shared as *const _,
+                Threading::INFINITE,
+                Threading::WT_EXECUTEDEFAULT,
+            )
+        };
+        if registered == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(stream)
+    }
+
+    fn rearm(&self) -> io::Result<()> {
+        self.key.notify_raw(self.recursive, self.filter, self.event)
+    }
+
+    /// Wait for the next change
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # async fn watch() -> Result<(), Box<dyn Error>> {
+    /// let env = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Environment")?;
+    /// let mut changes = env.watch_async(NotifyFilter::REG_NOTIFY_CHANGE_LAST_SET, false)?;
+    /// loop {
+    ///     changes.changed().await?;
+    ///     println!("environment changed");
+    /// }
+    /// # }
+    /// ```
+    pub fn changed(&mut self) -> NextChange<'_> {
+        NextChange { stream: self }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Stream for ChangeStream {
+    type Item = io::Result<()>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<io::Result<()>>> {
+        *self.shared.waker.lock().unwrap() = Some(cx.waker().clone());
+        if !self.shared.fired.swap(false, Ordering::SeqCst) {
+            return Poll::Pending;
+        }
+        Poll::Ready(Some(self.rearm()))
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for ChangeStream {
+    fn drop(&mut self) {
+        unsafe {
+            // waits for a running callback, which borrows `shared`
+            if !self.wait.is_null() {
+                Threading::UnregisterWaitEx(self.wait, Foundation::INVALID_HANDLE_VALUE);
+            }
+            Foundation::CloseHandle(self.event);
+        }
+    }
+}
+
+/// Future returned by `ChangeStream::changed`
+///
+/// Part of `async` feature.
+#[cfg(feature = "async")]
+pub struct NextChange<'stream> {
+    stream: &'stream mut ChangeStream,
+}
+
+#[cfg(feature = "async")]
+impl Future for NextChange<'_> {
+    type Output = io::Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match Pin::new(&mut *self.stream).poll_next(cx) {
+            Poll::Ready(Some(r)) => Poll::Ready(r),
+            Poll::Ready(None) => Poll::Ready(Ok(())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
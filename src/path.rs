@@ -0,0 +1,420 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Registry path manipulation.
+//!
+//! Key names can't contain backslashes but may contain forward slashes,
+//! so only `\` is treated as a separator.
+//!
+//!```no_run
+//!use winreg2::enums::*;
+//!use winreg2::path::RegPath;
+//!
+//!let path = RegPath::parse("HKLM\\SOFTWARE\\\\Vendor\\");
+//!assert_eq!(path.root(), Some(HKEY_LOCAL_MACHINE));
+//!assert_eq!(path.to_string(), "HKEY_LOCAL_MACHINE\\SOFTWARE\\Vendor");
+//!let app = path.join("App\\Settings");
+//!assert_eq!(app.relative(), "SOFTWARE\\Vendor\\App\\Settings");
+//!assert_eq!(app.parent().unwrap().name(), Some("App"));
+//!```
+use crate::enums;
+use crate::reg_key::HKEY;
+use crate::util;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Maximum length of a key name in UTF-16 code units
+pub const MAX_KEY_NAME_LEN: usize = 255;
+/// Maximum number of nested keys below a predefined key
+pub const MAX_KEY_DEPTH: usize = 512;
+/// Maximum length of a value name in UTF-16 code units
+pub const MAX_VALUE_NAME_LEN: usize = 16383;
+
+/// A registry path, optionally starting with a predefined key.
+/// Paths are compared and hashed case-insensitively like in the registry,
+/// so `HKLM\Software` and `HKLM\SOFTWARE` are equal.
+#[derive(Debug, Clone, Default)]
+pub struct RegPath {
+    root: Option<HKEY>,
+    components: Vec<String>,
+}
+
+// `root` is always one of the predefined keys, which are constants
+unsafe impl Send for RegPath {}
+unsafe impl Sync for RegPath {}
+
+impl RegPath {
+    /// Parse a path. A leading root name (e.g. `HKEY_LOCAL_MACHINE` or `HKLM`,
+    /// case-insensitive, optionally preceded by regedit's `Computer\`) makes the path absolute.
+    /// Empty components from repeated, leading or trailing backslashes are dropped.
+    pub fn parse(s: &str) -> RegPath {
+        let mut components: Vec<&str> = s.split('\\').filter(|c| !c.is_empty()).collect();
+        if components.len() > 1
+            && components[0].eq_ignore_ascii_case("Computer")
+            && enums::root_from_name(components[1]).is_some()
+        {
+            components.remove(0);
+        }
+        let root = components.first().and_then(|c| enums::root_from_name(c));
+        if root.is_some() {
+            components.remove(0);
+        }
+        RegPath {
+            root,
+            components: components.into_iter().map(str::to_owned).collect(),
+        }
+    }
+
+    /// Build an absolute path from a predefined key and a path relative to it,
+    /// `None` if `root` is not a predefined key
+    pub fn from_parts(root: HKEY, relative: &str) -> Option<RegPath> {
+        enums::root_name(root)?;
+        let mut path = RegPath::parse(relative);
+        path.root = Some(root);
+        Some(path)
+    }
+
+    /// Split into the predefined key and the path relative to it,
+    /// `None` if the path is relative
+    pub fn to_parts(&self) -> Option<(HKEY, String)> {
+        self.root.map(|root| (root, self.relative()))
+    }
+
+    /// Predefined key the path starts with
+    pub fn root(&self) -> Option<HKEY> {
+        self.root
+    }
+
+    /// Long name of the predefined key the path starts with, e.g. `HKEY_LOCAL_MACHINE`
+    pub fn root_name(&self) -> Option<&'static str> {
+        self.root.and_then(enums::root_name)
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.root.is_some()
+    }
+
+    /// Path without the root, suitable for `RegKey::open_subkey`
+    pub fn relative(&self) -> String {
+        self.components.join("\\")
+    }
+
+    /// Key names the path consists of, without the root
+    pub fn components(&self) -> &[String] {
+        &self.components
+    }
+
+    /// Last key name, `None` for a root or an empty path
+    pub fn name(&self) -> Option<&str> {
+        self.components.last().map(String::as_str)
+    }
+
+    /// Append a relative path, which may consist of several components
+    pub fn join(&self, child: &str) -> RegPath {
+        let mut path = self.clone();
+        path.components.extend(
+            child
+                .split('\\')
+                .filter(|c| !c.is_empty())
+                .map(str::to_owned),
+        );
+        path
+    }
+
+    /// Path without the last component, `None` for a root or an empty path
+    pub fn parent(&self) -> Option<RegPath> {
+        if self.components.is_empty() {
+            return None;
+        }
+        let mut path = self.clone();
+        path.components.pop();
+        Some(path)
+    }
+
+    /// Path of `self` relative to `base`, `None` if `self` is not `base` or one of its descendants
+    pub fn strip_prefix(&self, base: &RegPath) -> Option<RegPath> {
+        if !self.starts_with(base) {
+            return None;
+        }
+        Some(RegPath {
+            root: None,
+            components: self.components[base.components.len()..].to_vec(),
+        })
+    }
+
+    /// Move `self` from under `from` to under `to`, e.g. from `HKCU\Software\App`
+    /// to `HKLM\SOFTWARE\App`. `None` if `self` is not below `from`.
+    pub fn reroot(&self, from: &RegPath, to: &RegPath) -> Option<RegPath> {
+        self.strip_prefix(from).map(|rel| to.join(&rel.relative()))
+    }
+
+    /// Check whether `self` is `other` or one of its descendants, comparing names case-insensitively
+    pub fn starts_with(&self, other: &RegPath) -> bool {
+        self.root == other.root
+            && self.components.len() >= other.components.len()
+            && self
+                .components
+                .iter()
+                .zip(&other.components)
+                .all(|(a, b)| util::name_eq(a, b))
+    }
+}
+
+impl PartialEq for RegPath {
+    fn eq(&self, other: &RegPath) -> bool {
+        self.root == other.root
+            && self.components.len() == other.components.len()
+            && self.starts_with(other)
+    }
+}
+
+impl Eq for RegPath {}
+
+impl Hash for RegPath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.root.hash(state);
+        for c in &self.components {
+            util::hash_name(c, state);
+            // key names can't contain the separator
+            state.write_u8(b'\\');
+        }
+    }
+}
+
+impl fmt::Display for RegPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.root_name() {
+            Some(root) if self.components.is_empty() => write!(f, "{}", root),
+            Some(root) => write!(f, "{}\\{}", root, self.relative()),
+            None => write!(f, "{}", self.relative()),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for RegPath {
+    fn from(s: &'a str) -> RegPath {
+        RegPath::parse(s)
+    }
+}
+
+/// A glob pattern over registry paths, compared case-insensitively.
+///
+/// Each `\`-separated segment may contain `*` (any run of characters) and `?`
+/// (any single character); a segment consisting of `**` matches any number of keys,
+/// including none. A pattern starting with a root name only matches paths with that root.
+///
+/// ```no_run
+/// use winreg2::path::Pattern;
+///
+/// let pattern = Pattern::new("HKLM\\SOFTWARE\\**\\Vendor?\\*Settings");
+/// assert!(pattern.matches("HKEY_LOCAL_MACHINE\\SOFTWARE\\VendorA\\AppSettings"));
+/// assert!(pattern.matches("HKLM\\SOFTWARE\\WOW6432Node\\vendorb\\Settings"));
+/// assert!(!pattern.matches("HKLM\\SOFTWARE\\Vendor\\Settings"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    source: String,
+    root: Option<HKEY>,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// `**`
+    AnyKeys,
+    /// Lowercased characters of a segment, possibly with `*` and `?`
+    Glob(Vec<char>),
+}
+
+// `root` is always one of the predefined keys, which are constants
+unsafe impl Send for Pattern {}
+unsafe impl Sync for Pattern {}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Pattern {
+        let path = RegPath::parse(pattern);
+        let segments = path
+            .components()
+            .iter()
+            .map(|c| match c.as_str() {
+                "**" => Segment::AnyKeys,
+                c => Segment::Glob(c.chars().flat_map(char::to_lowercase).collect()),
+            })
+            .collect();
+        Pattern {
+            source: pattern.to_owned(),
+            root: path.root(),
+            segments,
+        }
+    }
+
+    /// Check whether a path string matches the pattern
+    pub fn matches(&self, path: &str) -> bool {
+        self.matches_path(&RegPath::parse(path))
+    }
+
+    /// Check whether a path matches the pattern
+    pub fn matches_path(&self, path: &RegPath) -> bool {
+        if self.root != path.root() {
+            return false;
+        }
+        let names: Vec<Vec<char>> = path
+            .components()
+            .iter()
+            .map(|c| c.chars().flat_map(char::to_lowercase).collect())
+            .collect();
+        match_segments(&self.segments, &names)
+    }
+
+    /// Check whether a path made of `names`, relative to the key a search started
+    /// from, matches the pattern. Patterns with a root never match relative paths.
+    pub(crate) fn matches_names<'a, I: IntoIterator<Item = &'a str>>(&self, names: I) -> bool {
+        if self.root.is_some() {
+            return false;
+        }
+        let names: Vec<Vec<char>> = names
+            .into_iter()
+            .map(|c| c.chars().flat_map(char::to_lowercase).collect())
+            .collect();
+        match_segments(&self.segments, &names)
+    }
+
+    /// Number of keys a matching path has, `None` if it contains `**`
+    pub(crate) fn depth(&self) -> Option<usize> {
+        if self.segments.contains(&Segment::AnyKeys) {
+            None
+        } else {
+            Some(self.segments.len())
+        }
+    }
+
+    /// The pattern as it was written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl<'a> From<&'a str> for Pattern {
+    fn from(s: &'a str) -> Pattern {
+        Pattern::new(s)
+    }
+}
+
+fn match_segments(segments: &[Segment], names: &[Vec<char>]) -> bool {
+    match segments.split_first() {
+        None => names.is_empty(),
+        Some((Segment::AnyKeys, rest)) => {
+            (0..=names.len()).any(|skip| match_segments(rest, &names[skip..]))
+        }
+        Some((Segment::Glob(glob), rest)) => match names.split_first() {
+            Some((name, names)) => match_glob(glob, name) && match_segments(rest, names),
+            None => false,
+        },
+    }
+}
+
+fn match_glob(glob: &[char], name: &[char]) -> bool {
+    // greedy matching, backtracking to the last `*`
+    let (mut g, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if g < glob.len() && (glob[g] == '?' || glob[g] == name[n]) {
+            g += 1;
+            n += 1;
+        } else if g < glob.len() && glob[g] == '*' {
+            star = Some((g, n));
+            g += 1;
+        } else if let Some((star_g, star_n)) = star {
+            g = star_g + 1;
+            n = star_n + 1;
+            star = Some((star_g, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+/// A limit broken by a key path or a value name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// Key name at `index` (0 is the first component after the root) is `len` units long
+    KeyNameTooLong { index: usize, len: usize },
+    /// Path is nested `depth` levels deep
+    TooDeep { depth: usize },
+    /// Value name is `len` units long
+    ValueNameTooLong { len: usize },
+    /// Key name at `index`, or the value name if `None`, contains a NUL
+    EmbeddedNul { index: Option<usize> },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::KeyNameTooLong { index, len } => write!(
+                f,
+                "key name {} is {} characters long, the limit is {}",
+                index, len, MAX_KEY_NAME_LEN
+            ),
+            Violation::TooDeep { depth } => write!(
+                f,
+                "path is {} levels deep, the limit is {}",
+                depth, MAX_KEY_DEPTH
+            ),
+            Violation::ValueNameTooLong { len } => write!(
+                f,
+                "value name is {} characters long, the limit is {}",
+                len, MAX_VALUE_NAME_LEN
+            ),
+            Violation::EmbeddedNul { index: Some(index) } => {
+                write!(f, "key name {} contains a NUL character", index)
+            }
+            Violation::EmbeddedNul { index: None } => {
+                write!(f, "value name contains a NUL character")
+            }
+        }
+    }
+}
+
+/// Check a key path against the registry limits, an empty result means the path is valid.
+/// The path is parsed like `RegPath::parse`, so a root name and repeated backslashes are allowed.
+pub fn validate(path: &str) -> Vec<Violation> {
+    let path = RegPath::parse(path);
+    let mut violations = Vec::new();
+    for (index, name) in path.components().iter().enumerate() {
+        let len = name.encode_utf16().count();
+        if len > MAX_KEY_NAME_LEN {
+            violations.push(Violation::KeyNameTooLong { index, len });
+        }
+        if name.contains('\0') {
+            violations.push(Violation::EmbeddedNul { index: Some(index) });
+        }
+    }
+    let depth = path.components().len();
+    if depth > MAX_KEY_DEPTH {
+        violations.push(Violation::TooDeep { depth });
+    }
+    violations
+}
+
+/// Check a value name against the registry limits, an empty result means the name is valid
+pub fn validate_value_name(name: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let len = name.encode_utf16().count();
+    if len > MAX_VALUE_NAME_LEN {
+        violations.push(Violation::ValueNameTooLong { len });
+    }
+    if name.contains('\0') {
+        violations.push(Violation::EmbeddedNul { index: None });
+    }
+    violations
+}
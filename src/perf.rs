@@ -0,0 +1,429 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Performance counters read through `HKEY_PERFORMANCE_DATA`.
+//!
+//! The data is a `PERF_DATA_BLOCK` with nested object, counter and instance
+//! structures. Objects and counters are identified by title indices, which
+//! `TitleDatabase` maps to names.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::perf::{PerfSnapshot, TitleDatabase};
+//!
+//!fn main() -> io::Result<()> {
+//!    let titles = TitleDatabase::load()?;
+//!    let processor = titles.index_of("Processor").unwrap();
+//!    let snapshot = PerfSnapshot::query(&processor.to_string(), &titles)?;
+//!    let object = snapshot.object("Processor").unwrap();
+//!    let time = object.counter_position("% Processor Time").unwrap();
+//!    for instance in &object.instances {
+//!        println!("{:?}: {:?}", instance.name, instance.values[time]);
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::common::*;
+use crate::enums::*;
+use crate::reg_key::HKEY;
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::ptr;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::System::Performance;
+use windows_sys::Win32::System::Registry;
+
+/// Names and help texts of performance objects and counters by title index
+#[derive(Debug, Clone, Default)]
+pub struct TitleDatabase {
+    names: HashMap<u32, String>,
+    help: HashMap<u32, String>,
+}
+
+impl TitleDatabase {
+    /// Load the English titles from `HKEY_PERFORMANCE_TEXT`
+    pub fn load() -> io::Result<TitleDatabase> {
+        TitleDatabase::load_from(HKEY_PERFORMANCE_TEXT)
+    }
+
+    /// Load the titles in the user interface language from `HKEY_PERFORMANCE_NLSTEXT`
+    pub fn load_localized() -> io::Result<TitleDatabase> {
+        TitleDatabase::load_from(HKEY_PERFORMANCE_NLSTEXT)
+    }
+
+    fn load_from(hkey: HKEY) -> io::Result<TitleDatabase> {
+        Ok(TitleDatabase {
+            names: parse_titles(&query_value(hkey, "Counter")?),
+            help: parse_titles(&query_value(hkey, "Help")?),
+        })
+    }
+
+    /// Name of an object or a counter
+    pub fn name(&self, index: u32) -> Option<&str> {
+        self.names.get(&index).map(String::as_str)
+    }
+
+    /// Help text of an object or a counter
+    pub fn help(&self, index: u32) -> Option<&str> {
+        self.help.get(&index).map(String::as_str)
+    }
+
+    /// Title index of an object or a counter, compared case-insensitively.
+    /// Some names are used by several counters, any of them may be returned.
+    pub fn index_of(&self, name: &str) -> Option<u32> {
+        self.names
+            .iter()
+            .find(|&(_, n)| n.eq_ignore_ascii_case(name))
+            .map(|(&index, _)| index)
+    }
+}
+
+/// Parse `index\0title\0index\0title\0...` pairs of a `REG_MULTI_SZ` value
+fn parse_titles(data: &[u8]) -> HashMap<u32, String> {
+    let words: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    let strings: Vec<String> = words
+        .split(|&w| w == 0)
+        .map(String::from_utf16_lossy)
+        .collect();
+    strings
+        .chunks_exact(2)
+        .filter_map(|pair| pair[0].trim().parse().ok().map(|i| (i, pair[1].clone())))
+        .collect()
+}
+
+/// Value of a counter in one instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CounterValue {
+    /// `PERF_SIZE_DWORD` counters
+    Dword(u32),
+    /// `PERF_SIZE_LARGE` counters
+    Large(u64),
+    /// Counters of other sizes, e.g. `PERF_SIZE_VARIABLE_LEN` text
+    Raw(Vec<u8>),
+}
+
+impl CounterValue {
+    /// Numeric value, `None` for raw data
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            CounterValue::Dword(v) => Some(v.into()),
+            CounterValue::Large(v) => Some(v),
+            CounterValue::Raw(_) => None,
+        }
+    }
+}
+
+/// A counter definition of an object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerfCounter {
+    pub name_index: u32,
+    pub help_index: u32,
+    /// Resolved from the title database, `None` if it has no such index
+    pub name: Option<String>,
+    /// `PERF_COUNTER_*` type, defining how values are calculated and displayed
+    pub counter_type: u32,
+    /// Power of 10 to scale values by for display
+    pub default_scale: i32,
+    /// `PERF_DETAIL_*` level of the intended audience
+    pub detail_level: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// An instance of an object, e.g. a processor or a process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerfInstance {
+    /// `None` for objects without instances, which have a single unnamed one
+    pub name: Option<String>,
+    pub unique_id: i32,
+    pub parent_object_index: u32,
+    pub parent_instance: u32,
+    /// Values in the order of `PerfObject::counters`
+    pub values: Vec<CounterValue>,
+}
+
+/// A performance object, e.g. `Processor` or `Memory`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerfObject {
+    pub name_index: u32,
+    pub help_index: u32,
+    /// Resolved from the title database, `None` if it has no such index
+    pub name: Option<String>,
+    pub detail_level: u32,
+    /// Object-specific time and frequency, used by some counter types
+    pub perf_time: i64,
+    pub perf_freq: i64,
+    pub counters: Vec<PerfCounter>,
+    pub instances: Vec<PerfInstance>,
+}
+
+impl PerfObject {
+    /// Position of a counter in `counters` and `PerfInstance::values`
+    pub fn counter_position(&self, name: &str) -> Option<usize> {
+        self.counters.iter().position(|c| {
+            c.name
+                .as_ref()
+                .map_or(false, |n| n.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Instance with the given name, compared case-insensitively
+    pub fn instance(&self, name: &str) -> Option<&PerfInstance> {
+        self.instances.iter().find(|i| {
+            i.name
+                .as_ref()
+                .map_or(false, |n| n.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// Parsed content of `HKEY_PERFORMANCE_DATA`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerfSnapshot {
+    pub system_name: String,
+    /// Performance counter value and frequency at the time of the query
+    pub perf_time: i64,
+    pub perf_freq: i64,
+    /// Time of the query in 100ns units
+    pub perf_time_100ns: i64,
+    pub objects: Vec<PerfObject>,
+}
+
+impl PerfSnapshot {
+    /// Query and parse performance data. `objects` is `Global` for all
+    /// commonly used objects, `Costly` for expensive ones, or space-separated
+    /// decimal title indices of the wanted objects.
+    pub fn query(objects: &str, titles: &TitleDatabase) -> io::Result<PerfSnapshot> {
+        PerfSnapshot::parse(&query_raw(objects)?, Some(titles))
+    }
+
+    /// Parse a `PERF_DATA_BLOCK`, resolving object and counter names if `titles` is given
+    pub fn parse(data: &[u8], titles: Option<&TitleDatabase>) -> io::Result<PerfSnapshot> {
+        let block: Performance::PERF_DATA_BLOCK = read(data, 0)?;
+        let signature: Vec<u16> = "PERF".encode_utf16().collect();
+        if block.Signature[..] != signature[..] {
+            return werr!(Foundation::ERROR_INVALID_DATA);
+        }
+        let name_of = |index: u32| titles.and_then(|t| t.name(index)).map(str::to_owned);
+        let system_name = read_string(
+            data,
+            block.SystemNameOffset as usize,
+            block.SystemNameLength as usize,
+        )?;
+
+        // counts and lengths come from the data, so they are checked against
+        // its size and every structure has to advance the offset
+        check_count::<Performance::PERF_OBJECT_TYPE>(data, block.NumObjectTypes)?;
+        let mut objects = Vec::new();
+        let mut object_offset = block.HeaderLength as usize;
+        for _ in 0..block.NumObjectTypes {
+            let object: Performance::PERF_OBJECT_TYPE = read(data, object_offset)?;
+            check_length::<Performance::PERF_OBJECT_TYPE>(object.TotalByteLength)?;
+
+            check_count::<Performance::PERF_COUNTER_DEFINITION>(data, object.NumCounters)?;
+            let mut counters = Vec::new();
+            let mut counter_offset = add_offset(object_offset, object.HeaderLength)?;
+            for _ in 0..object.NumCounters {
+                let def: Performance::PERF_COUNTER_DEFINITION = read(data, counter_offset)?;
+                check_length::<Performance::PERF_COUNTER_DEFINITION>(def.ByteLength)?;
+                counters.push(PerfCounter {
+                    name_index: def.CounterNameTitleIndex,
+                    help_index: def.CounterHelpTitleIndex,
+                    name: name_of(def.CounterNameTitleIndex),
+                    counter_type: def.CounterType,
+                    default_scale: def.DefaultScale,
+                    detail_level: def.DetailLevel,
+                    size: def.CounterSize,
+                    offset: def.CounterOffset,
+                });
+                counter_offset = add_offset(counter_offset, def.ByteLength)?;
+            }
+
+            let mut instances = Vec::new();
+            let instance_start = add_offset(object_offset, object.DefinitionLength)?;
+            if object.NumInstances == Performance::PERF_NO_INSTANCES {
+                instances.push(PerfInstance {
+                    name: None,
+                    unique_id: Performance::PERF_NO_UNIQUE_ID,
+                    parent_object_index: 0,
+                    parent_instance: 0,
+                    values: read_values(data, instance_start, &counters)?,
+                });
+            } else {
+                let count = object.NumInstances.max(0) as u32;
+                check_count::<Performance::PERF_INSTANCE_DEFINITION>(data, count)?;
+                let mut instance_offset = instance_start;
+                for _ in 0..count {
+                    let def: Performance::PERF_INSTANCE_DEFINITION = read(data, instance_offset)?;
+                    check_length::<Performance::PERF_INSTANCE_DEFINITION>(def.ByteLength)?;
+                    let name = read_string(
+                        data,
+                        add_offset(instance_offset, def.NameOffset)?,
+                        def.NameLength as usize,
+                    )?;
+                    let block_offset = add_offset(instance_offset, def.ByteLength)?;
+                    let counter_block: Performance::PERF_COUNTER_BLOCK = read(data, block_offset)?;
+                    check_length::<Performance::PERF_COUNTER_BLOCK>(counter_block.ByteLength)?;
+                    instances.push(PerfInstance {
+                        name: Some(name),
+                        unique_id: def.UniqueID,
+                        parent_object_index: def.ParentObjectTitleIndex,
+                        parent_instance: def.ParentObjectInstance,
+                        values: read_values(data, block_offset, &counters)?,
+                    });
+                    instance_offset = add_offset(block_offset, counter_block.ByteLength)?;
+                }
+            }
+
+            objects.push(PerfObject {
+                name_index: object.ObjectNameTitleIndex,
+                help_index: object.ObjectHelpTitleIndex,
+                name: name_of(object.ObjectNameTitleIndex),
+                detail_level: object.DetailLevel,
+                perf_time: object.PerfTime,
+                perf_freq: object.PerfFreq,
+                counters,
+                instances,
+            });
+            object_offset = add_offset(object_offset, object.TotalByteLength)?;
+        }
+
+        Ok(PerfSnapshot {
+            system_name,
+            perf_time: block.PerfTime,
+            perf_freq: block.PerfFreq,
+            perf_time_100ns: block.PerfTime100nSec,
+            objects,
+        })
+    }
+
+    /// Object with the given name, compared case-insensitively
+    pub fn object(&self, name: &str) -> Option<&PerfObject> {
+        self.objects.iter().find(|o| {
+            o.name
+                .as_ref()
+                .map_or(false, |n| n.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// Read the raw `PERF_DATA_BLOCK` for `objects`, see `PerfSnapshot::query`
+pub fn query_raw(objects: &str) -> io::Result<Vec<u8>> {
+    let data = query_value(HKEY_PERFORMANCE_DATA, objects);
+    // releases the performance libraries loaded by the query
+    unsafe { Registry::RegCloseKey(HKEY_PERFORMANCE_DATA) };
+    data
+}
+
+/// Query a value of a performance key. The required size is not reported
+/// for these keys, so the buffer is grown until the data fits.
+fn query_value(hkey: HKEY, name: &str) -> io::Result<Vec<u8>> {
+    let c_name = to_utf16(name);
+    let mut buf: Vec<u8> = vec![0; 64 * 1024];
+    loop {
+        let mut len = buf.len() as u32;
+        match unsafe {
+            Registry::RegQueryValueExW(
+                hkey,
+                c_name.as_ptr(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                buf.as_mut_ptr(),
+                &mut len,
+            )
+        } {
+            0 => {
+                buf.truncate(len as usize);
+                return Ok(buf);
+            }
+            Foundation::ERROR_MORE_DATA => {
+                let new_len = buf.len() * 2;
+                buf.resize(new_len, 0);
+            }
+            err => return werr!(err),
+        }
+    }
+}
+
+/// Read a structure at `offset`, which may be unaligned
+fn read<T: Copy>(data: &[u8], offset: usize) -> io::Result<T> {
+    match offset.checked_add(mem::size_of::<T>()) {
+        Some(end) if end <= data.len() => {
+            Ok(unsafe { ptr::read_unaligned(data[offset..].as_ptr() as *const T) })
+        }
+        _ => werr!(Foundation::ERROR_INVALID_DATA),
+    }
+}
+
+/// `offset` advanced by `len`, which may overflow `usize` on 32-bit targets
+fn add_offset(offset: usize, len: u32) -> io::Result<usize> {
+    match offset.checked_add(len as usize) {
+        Some(offset) => Ok(offset),
+        None => werr!(Foundation::ERROR_INVALID_DATA),
+    }
+}
+
+/// Reject more structures of type `T` than would fit in `data`
+fn check_count<T>(data: &[u8], count: u32) -> io::Result<()> {
+    if count as usize > data.len() / mem::size_of::<T>() {
+        return werr!(Foundation::ERROR_INVALID_DATA);
+    }
+    Ok(())
+}
+
+/// Reject a length shorter than the structure of type `T` it includes,
+/// which would not advance to the next structure
+fn check_length<T>(len: u32) -> io::Result<()> {
+    if (len as usize) < mem::size_of::<T>() {
+        return werr!(Foundation::ERROR_INVALID_DATA);
+    }
+    Ok(())
+}
+
+/// Read a NUL-terminated UTF-16 string of at most `len` bytes
+fn read_string(data: &[u8], offset: usize, len: usize) -> io::Result<String> {
+    let bytes = offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| io::Error::from_raw_os_error(Foundation::ERROR_INVALID_DATA as i32))?;
+    let words: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&w| w != 0)
+        .collect();
+    Ok(String::from_utf16_lossy(&words))
+}
+
+/// Read the values of `counters` from a counter block at `offset`
+fn read_values(
+    data: &[u8],
+    offset: usize,
+    counters: &[PerfCounter],
+) -> io::Result<Vec<CounterValue>> {
+    counters
+        .iter()
+        .map(|counter| {
+            let start = add_offset(offset, counter.offset)?;
+            Ok(match counter.size {
+                4 => CounterValue::Dword(read(data, start)?),
+                8 => CounterValue::Large(read(data, start)?),
+                size => {
+                    let bytes = add_offset(start, size)
+                        .ok()
+                        .and_then(|end| data.get(start..end))
+                        .ok_or_else(|| {
+                            io::Error::from_raw_os_error(Foundation::ERROR_INVALID_DATA as i32)
+                        })?;
+                    CounterValue::Raw(bytes.to_vec())
+                }
+            })
+        })
+        .collect()
+}
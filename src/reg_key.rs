@@ -4,25 +4,91 @@
 // may not be copied, modified, or distributed
 // except according to those terms.
 use crate::common::*;
+use crate::copy::{self, CopyOptions};
 use crate::enums::{self, *};
-use crate::reg_key_metadata::RegKeyMetadata;
+use crate::notify::ChangeEvent;
+#[cfg(feature = "async")]
+use crate::notify::ChangeStream;
+use crate::path::{Pattern, RegPath, MAX_VALUE_NAME_LEN};
+use crate::reg_key_metadata::{FileTime, RegKeyMetadata};
 use crate::reg_value::RegValue;
+use crate::search::{FindKeys, FindValues};
+use crate::security::{SecurityDescriptor, SecurityInformation};
+use crate::snapshot::RegSnapshot;
 #[cfg(feature = "transactions")]
-use crate::transaction::Transaction;
+use crate::transaction::{TransactedKey, Transaction};
 use crate::types::{FromRegValue, ToRegValue};
+#[cfg(feature = "transactions")]
+use crate::util;
+#[cfg(feature = "rayon")]
+use crate::walker::{self, WalkEntry};
+use crate::walker::{Walk, WalkOptions};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::default::Default;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
 use std::io;
-use std::mem::transmute;
+use std::mem::{self, transmute};
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
 use std::ptr;
+use std::time::SystemTime;
+use windows_sys::Wdk::System::Registry as WdkRegistry;
 use windows_sys::Win32::Foundation;
+use windows_sys::Win32::Security;
+use windows_sys::Win32::Storage::FileSystem;
 use windows_sys::Win32::System::Registry;
 pub use windows_sys::Win32::System::Registry::HKEY;
+use windows_sys::Win32::System::Threading;
+
+/// Name of the value holding the target of a symbolic link
+const LINK_VALUE_NAME: &str = "SymbolicLinkValue";
+
+/// Access to the keys of a tree being deleted, the same that `RegDeleteTreeW` asks for
+const DELETE_TREE_SAM: Registry::REG_SAM_FLAGS = FileSystem::DELETE
+    | Registry::KEY_ENUMERATE_SUB_KEYS
+    | Registry::KEY_QUERY_VALUE
+    | Registry::KEY_SET_VALUE;
+
+/// Disable the per-process cache of the `HKEY_CURRENT_USER` predefined handle.
+///
+/// Once opened, `HKEY_CURRENT_USER` keeps referring to the hive of the user
+/// the process first accessed it as. Services that impersonate clients must call this
+/// before impersonating, otherwise `RegKey::predef(HKEY_CURRENT_USER)` may access
+/// the wrong user's hive. `RegKey::open_current_user` is not affected by the cache
+/// and is the preferred way to access the impersonated user's hive.
+pub fn disable_predefined_cache() -> io::Result<()> {
+    match unsafe { Registry::RegDisablePredefinedCacheEx() } {
+        0 => Ok(()),
+        err => werr!(err),
+    }
+}
+
+/// Open a key from a fully-qualified path such as `HKLM\SOFTWARE\Vendor`,
+/// see `RegKey::open_path`.
+pub fn open_path<P: AsRef<str>>(path: P, perms: Registry::REG_SAM_FLAGS) -> io::Result<RegKey> {
+    RegKey::open_path(path, perms)
+}
+
+/// `KeyFlags` bit of a volatile key
+const REG_FLAG_VOLATILE: u32 = 0x0001;
+/// `KeyFlags` bit of a symbolic link key
+const REG_FLAG_LINK: u32 = 0x0002;
+
+/// `KEY_FLAGS_INFORMATION`, not declared by `windows-sys`
+#[repr(C)]
+#[derive(Default)]
+struct KeyFlagsInformation {
+    wow64_flags: u32,
+    key_flags: u32,
+    control_flags: u32,
+}
 
 /// Handle of opened registry key
-#[derive(Debug)]
 pub struct RegKey {
     hkey: HKEY,
+    /// Cached result of `full_path`
+    path: RefCell<Option<String>>,
 }
 
 unsafe impl Send for RegKey {}
@@ -49,7 +115,110 @@ impl RegKey {
     /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
     /// ```
     pub const fn predef(hkey: HKEY) -> RegKey {
-        RegKey { hkey }
+        RegKey::new(hkey)
+    }
+
+    const fn new(hkey: HKEY) -> RegKey {
+        RegKey {
+            hkey,
+            path: RefCell::new(None),
+        }
+    }
+
+    /// Open `HKEY_CURRENT_USER` of the user the calling thread is impersonating,
+    /// or of the process user if the thread is not impersonating.
+    /// Unlike `predef(HKEY_CURRENT_USER)`, which is cached per process,
+    /// this always resolves the hive from the current thread token.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// // while impersonating a client
+    /// let hkcu = RegKey::open_current_user(KEY_READ)?;
+    /// let env = hkcu.open_subkey("Environment")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_current_user(perms: Registry::REG_SAM_FLAGS) -> io::Result<RegKey> {
+        let mut new_hkey: HKEY = std::ptr::null_mut();
+        match unsafe { Registry::RegOpenCurrentUser(perms, &mut new_hkey) } {
+            0 => Ok(RegKey::new(new_hkey)),
+            err => werr!(err),
+        }
+    }
+
+    /// Connect to a predefined key on another computer through the Remote Registry
+    /// service. Only `HKEY_LOCAL_MACHINE`, `HKEY_USERS` and `HKEY_PERFORMANCE_DATA`
+    /// (plus `HKEY_CURRENT_CONFIG` on newer systems) can be connected to.
+    /// `machine_name` may be given with or without leading backslashes,
+    /// an empty name connects to the local computer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::connect("\\\\fileserver", HKEY_LOCAL_MACHINE)?;
+    /// let cur_ver = hklm.open_subkey("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion")?;
+    /// let product: String = cur_ver.get_value("ProductName")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect<N: AsRef<OsStr>>(machine_name: N, hkey: HKEY) -> io::Result<RegKey> {
+        let machine_name = machine_name.as_ref();
+        let c_machine_name = to_utf16(machine_name);
+        let name_ptr = if machine_name.is_empty() {
+            ptr::null()
+        } else {
+            c_machine_name.as_ptr()
+        };
+        let root = RegKey::predef(hkey);
+        let mut new_hkey: HKEY = ptr::null_mut();
+        match unsafe { Registry::RegConnectRegistryW(name_ptr, root.hkey, &mut new_hkey) } {
+            0 => {
+                let key = RegKey::new(new_hkey);
+                // remote handles can't be queried for their name
+                if let Some(root) = enums::root_name(hkey) {
+                    let machine = machine_name.to_string_lossy();
+                    let path = if machine.is_empty() {
+                        root.to_owned()
+                    } else {
+                        format!("\\\\{}\\{}", machine.trim_start_matches('\\'), root)
+                    };
+                    *key.path.borrow_mut() = Some(path);
+                }
+                Ok(key)
+            }
+            err => werr!(err),
+        }
+    }
+
+    /// Open a key from a fully-qualified path starting with the long or short name
+    /// of a predefined key (e.g. `HKEY_LOCAL_MACHINE` or `HKLM`).
+    /// Fails with `ERROR_BAD_PATHNAME` if the path has no root.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let app = RegKey::open_path("HKLM\\SOFTWARE\\Vendor\\App", KEY_READ)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_path<P: AsRef<str>>(path: P, perms: Registry::REG_SAM_FLAGS) -> io::Result<RegKey> {
+        match RegPath::parse(path.as_ref()).to_parts() {
+            Some((root, relative)) => RegKey::predef(root).open_subkey_with_flags(relative, perms),
+            None => werr!(Foundation::ERROR_BAD_PATHNAME),
+        }
     }
 
     /// Load a registry hive from a file as an application hive.
@@ -101,11 +270,145 @@ impl RegKey {
         match unsafe {
             Registry::RegLoadAppKeyW(c_filename.as_ptr(), &mut new_hkey, perms, options, 0)
         } {
-            0 => Ok(RegKey { hkey: new_hkey }),
+            0 => Ok(RegKey::new(new_hkey)),
+            err => werr!(err),
+        }
+    }
+
+    /// Write all changes of the key to disk with `RegFlushKey`, returning only
+    /// once they are stored. The registry flushes changes lazily on its own,
+    /// so this is only needed for data that must survive a crash.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// let state = hklm.open_subkey_with_flags("SOFTWARE\\Vendor\\Service", KEY_SET_VALUE)?;
+    /// state.set_value("LastCheckpoint", &42u64)?;
+    /// state.flush()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flush(&self) -> io::Result<()> {
+        match unsafe { Registry::RegFlushKey(self.hkey) } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Save the key and all its subkeys to a new hive file, which can be restored
+    /// with `restore_from_file` or read with `load_app_key` and `hive::Hive`.
+    /// `SeBackupPrivilege` is enabled for the call, so the process must run as
+    /// an administrator or backup operator. Fails with `ERROR_ALREADY_EXISTS`
+    /// if the file exists.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// let app = hklm.open_subkey("SOFTWARE\\Vendor\\App")?;
+    /// app.save_to_file("C:\\Backup\\App.hiv", HiveFormat::Latest)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_to_file<P: AsRef<OsStr>>(&self, path: P, format: HiveFormat) -> io::Result<()> {
+        let _privilege = enable_privilege(Security::SE_BACKUP_NAME)?;
+        let c_path = to_utf16(path);
+        match unsafe {
+            Registry::RegSaveKeyExW(self.hkey, c_path.as_ptr(), ptr::null(), format.flags())
+        } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Replace the key and all its subkeys with the content of a hive file
+    /// written by `save_to_file`. `flags` may combine `REG_FORCE_RESTORE`,
+    /// to restore even if the key has open subkey handles, and
+    /// `REG_WHOLE_HIVE_VOLATILE`. `SeRestorePrivilege` is enabled for the call.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// let (app, _disp) = hklm.create_subkey("SOFTWARE\\Vendor\\App")?;
+    /// app.restore_from_file("C:\\Backup\\App.hiv", REG_FORCE_RESTORE)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_from_file<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        flags: Registry::REG_RESTORE_KEY_FLAGS,
+    ) -> io::Result<()> {
+        let _privilege = enable_privilege(Security::SE_RESTORE_NAME)?;
+        let c_path = to_utf16(path);
+        match unsafe { Registry::RegRestoreKeyW(self.hkey, c_path.as_ptr(), flags as u32) } {
+            0 => Ok(()),
             err => werr!(err),
         }
     }
 
+    /// Mount a hive file as the subkey `name` of `self`, which must be
+    /// `HKEY_LOCAL_MACHINE` or `HKEY_USERS`, e.g. to edit the `NTUSER.DAT` of a user
+    /// who is not logged on. The hive is unloaded when the returned guard is dropped.
+    /// `SeBackupPrivilege` and `SeRestorePrivilege` are enabled for loading and unloading.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hku = RegKey::predef(HKEY_USERS);
+    /// let hive = hku.load_key("OfflineUser", "C:\\Users\\Someone\\NTUSER.DAT")?;
+    /// let (env, _disp) = hive.key().create_subkey("Environment")?;
+    /// env.set_value("EDITOR", &"notepad.exe")?;
+    /// drop(env);
+    /// hive.unload()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_key<N: AsRef<OsStr>, P: AsRef<OsStr>>(
+        &self,
+        name: N,
+        hive_file: P,
+    ) -> io::Result<LoadedHive<'_>> {
+        let _backup = enable_privilege(Security::SE_BACKUP_NAME)?;
+        let _restore = enable_privilege(Security::SE_RESTORE_NAME)?;
+        let c_name = to_utf16(name.as_ref());
+        let c_file = to_utf16(hive_file);
+        match unsafe { Registry::RegLoadKeyW(self.hkey, c_name.as_ptr(), c_file.as_ptr()) } {
+            0 => {}
+            err => return werr!(err),
+        }
+        let key = match self.open_subkey_with_flags(name, KEY_ALL_ACCESS) {
+            Ok(key) => key,
+            Err(e) => {
+                unsafe { Registry::RegUnLoadKeyW(self.hkey, c_name.as_ptr()) };
+                return Err(e);
+            }
+        };
+        Ok(LoadedHive {
+            root: self,
+            name: c_name,
+            key: Some(key),
+        })
+    }
+
     /// Return inner winapi HKEY of a key:
     ///
     /// # Examples
@@ -149,6 +452,191 @@ impl RegKey {
         }
     }
 
+    /// Read the parts of the key's security descriptor selected by `info`.
+    /// The key must be opened with `READ_CONTROL` (part of `KEY_READ`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// let software = hklm.open_subkey("SOFTWARE")?;
+    /// let sd = software.get_security(OWNER_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION)?;
+    /// println!("protected: {}", sd.is_dacl_protected()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_security(&self, info: SecurityInformation) -> io::Result<SecurityDescriptor> {
+        let mut buf: Vec<u64> = vec![0; 64];
+        loop {
+            let mut len = (buf.len() * 8) as u32;
+            match unsafe {
+                Registry::RegGetKeySecurity(self.hkey, info, buf.as_mut_ptr() as *mut _, &mut len)
+            } {
+                0 => return SecurityDescriptor::from_buf(buf, len as usize),
+                Foundation::ERROR_INSUFFICIENT_BUFFER => buf.resize((len as usize + 7) / 8, 0),
+                err => return werr!(err),
+            }
+        }
+    }
+
+    /// Replace the parts of the key's security descriptor selected by `info`.
+    /// The key must be opened with `WRITE_DAC` to set the DACL
+    /// and with `WRITE_OWNER` to set the owner.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let template = hkcu.open_subkey("Software\\Template")?;
+    /// let app = hkcu.open_subkey_with_flags("Software\\App", WRITE_DAC)?;
+    /// app.set_security(DACL_SECURITY_INFORMATION, &template.get_security(DACL_SECURITY_INFORMATION)?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_security(
+        &self,
+        info: SecurityInformation,
+        sd: &SecurityDescriptor,
+    ) -> io::Result<()> {
+        match unsafe { Registry::RegSetKeySecurity(self.hkey, info, sd.as_ptr()) } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Owner, group and DACL of the key in the SDDL notation, e.g.
+    /// `O:BAG:SYD:(A;CI;KA;;;SY)(A;CI;KA;;;BA)`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// println!("{}", hklm.open_subkey("SOFTWARE")?.get_sddl()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_sddl(&self) -> io::Result<String> {
+        let info =
+            OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION;
+        self.get_security(info)?.to_sddl(info)
+    }
+
+    /// Set the parts of the security descriptor present in `sddl`,
+    /// e.g. only the DACL for `D:P(A;OICI;KA;;;BA)`. A `P` flag on the DACL
+    /// stops inheritance of the parent key's entries.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// let (app, _disp) = hklm.create_subkey("SOFTWARE\\Vendor\\App")?;
+    /// // administrators and SYSTEM may change the settings, users may only read them
+    /// app.set_sddl("D:P(A;OICI;KA;;;BA)(A;OICI;KA;;;SY)(A;OICI;KR;;;BU)")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_sddl(&self, sddl: &str) -> io::Result<()> {
+        let sd = SecurityDescriptor::from_sddl(sddl)?;
+        self.set_security(sd.information()?, &sd)
+    }
+
+    /// Block until the key (or, if `recursive`, any of its subkeys) changes as
+    /// described by `filter`. The key must be opened with `KEY_NOTIFY`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let env = hkcu.open_subkey_with_flags("Environment", KEY_NOTIFY)?;
+    /// env.watch(NotifyFilter::REG_NOTIFY_CHANGE_LAST_SET, false)?;
+    /// println!("environment changed");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch(&self, filter: enums::NotifyFilter, recursive: bool) -> io::Result<()> {
+        self.notify_raw(recursive, filter as u32, ptr::null_mut())
+    }
+
+    /// Same as `watch`, but returns immediately with an event that is signaled on the change
+    pub fn watch_event(
+        &self,
+        filter: enums::NotifyFilter,
+        recursive: bool,
+    ) -> io::Result<ChangeEvent<'_>> {
+        ChangeEvent::new(self, recursive, filter as u32)
+    }
+
+    /// Same as `watch`, but returns a stream of changes that can be awaited.
+    /// The key is re-opened with `KEY_NOTIFY`, so the stream doesn't borrow it.
+    ///
+    /// Part of `async` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # async fn watch() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let mut changes = hkcu.watch_async(NotifyFilter::REG_NOTIFY_CHANGE_NAME, true)?;
+    /// while changes.changed().await.is_ok() {
+    ///     println!("a key was created or deleted");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn watch_async(
+        &self,
+        filter: enums::NotifyFilter,
+        recursive: bool,
+    ) -> io::Result<ChangeStream> {
+        ChangeStream::new(self, recursive, filter as u32)
+    }
+
+    /// Register for a change notification with a combination of `REG_NOTIFY_*` flags,
+    /// signaling `event`, or waiting for the change if it's null
+    pub(crate) fn notify_raw(
+        &self,
+        recursive: bool,
+        filter: u32,
+        event: Foundation::HANDLE,
+    ) -> io::Result<()> {
+        match unsafe {
+            Registry::RegNotifyChangeKeyValue(
+                self.hkey,
+                recursive as i32,
+                filter,
+                event,
+                !event.is_null() as i32,
+            )
+        } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
     /// Open subkey with `KEY_READ` permissions.
     /// Will open another handle to itself if `path` is an empty string.
     /// To open with different permissions use `open_subkey_with_flags`.
@@ -195,13 +683,13 @@ impl RegKey {
         match unsafe {
             Registry::RegOpenKeyExW(self.hkey, c_path.as_ptr(), 0, perms, &mut new_hkey)
         } {
-            0 => Ok(RegKey { hkey: new_hkey }),
+            0 => Ok(RegKey::new(new_hkey)),
             err => werr!(err),
         }
     }
 
-    /// Open subkey with desired permissions and options.
-    /// Will open another handle to itself if `path` is an empty string.
+    /// Open another handle to the same key with different permissions, e.g. to hold a
+    /// long-lived `KEY_READ` handle and only briefly get `KEY_SET_VALUE` when needed.
     ///
     /// # Examples
     ///
@@ -210,7 +698,76 @@ impl RegKey {
     /// # use winreg2::RegKey;
     /// # use winreg2::enums::*;
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// # use windows_sys::Win32::System::Registry::REG_OPTION_CREATE_LINK;
+    /// let settings = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\MyProduct")?;
+    /// let version: u32 = settings.get_value("Version")?;
+    /// settings
+    ///     .reopen_with_flags(KEY_SET_VALUE)?
+    ///     .set_value("Version", &(version + 1))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reopen_with_flags(&self, perms: Registry::REG_SAM_FLAGS) -> io::Result<RegKey> {
+        // an empty path opens a new handle to the key itself
+        self.open_subkey_with_flags("", perms)
+    }
+
+    /// Duplicate the handle of the key with `DuplicateHandle`, keeping its access rights.
+    /// The new `RegKey` is closed independently, so it can be moved to another thread
+    /// while this one is still in use. Predefined keys are returned as they are.
+    /// Handles of remote keys can't be duplicated, use `reopen_with_flags` for them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use std::thread;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let settings = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\MyProduct")?;
+    /// let clone = settings.try_clone()?;
+    /// let worker = thread::spawn(move || clone.get_value::<String, _>("Name"));
+    /// let version: u32 = settings.get_value("Version")?;
+    /// println!("{} {}", worker.join().unwrap()?, version);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_clone(&self) -> io::Result<RegKey> {
+        if self.hkey as usize >= enums::HKEY_CLASSES_ROOT as usize {
+            return Ok(RegKey::predef(self.hkey));
+        }
+        let mut new_hkey: HKEY = ptr::null_mut();
+        let ok = unsafe {
+            let process = Threading::GetCurrentProcess();
+            Foundation::DuplicateHandle(
+                process,
+                self.hkey,
+                process,
+                &mut new_hkey,
+                0,
+                Foundation::FALSE,
+                Foundation::DUPLICATE_SAME_ACCESS,
+            )
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let key = RegKey::new(new_hkey);
+        *key.path.borrow_mut() = self.path.borrow().clone();
+        Ok(key)
+    }
+
+    /// Open subkey with desired permissions and options.
+    /// Will open another handle to itself if `path` is an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # use windows_sys::Win32::System::Registry::REG_OPTION_CREATE_LINK;
     /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
     /// hklm.open_subkey_with_options_flags("SOFTWARE\\Microsoft", REG_OPTION_CREATE_LINK, KEY_READ)?;
     /// # Ok(())
@@ -227,11 +784,88 @@ impl RegKey {
         match unsafe {
             Registry::RegOpenKeyExW(self.hkey, c_path.as_ptr(), options, perms, &mut new_hkey)
         } {
-            0 => Ok(RegKey { hkey: new_hkey }),
+            0 => Ok(RegKey::new(new_hkey)),
             err => werr!(err),
         }
     }
 
+    /// Open subkey with desired permissions in the given registry view.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// // HKLM\SOFTWARE\WOW6432Node\Microsoft for a 64-bit process
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// hklm.open_subkey_view("SOFTWARE\\Microsoft", KEY_READ, RegistryView::V32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_subkey_view<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        perms: Registry::REG_SAM_FLAGS,
+        view: RegistryView,
+    ) -> io::Result<RegKey> {
+        self.open_subkey_with_flags(path, perms | view.flags())
+    }
+
+    /// Open subkey with desired permissions without following it if it's a symbolic link,
+    /// the returned handle refers to the link key itself.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let link = RegKey::predef(HKEY_CURRENT_USER)
+    ///     .open_subkey_nofollow("Software\\MyProduct\\Link", KEY_READ)?;
+    /// println!("Target: {}", link.read_link()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_subkey_nofollow<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        perms: Registry::REG_SAM_FLAGS,
+    ) -> io::Result<RegKey> {
+        self.open_subkey_with_options_flags(path, Registry::REG_OPTION_OPEN_LINK, perms)
+    }
+
+    /// Open the key for reading and writing in the transaction `t`. Everything done
+    /// through the returned key, including its subkeys, is part of the transaction.
+    ///
+    /// Part of `transactions` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # use winreg2::transaction::Transaction;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let t = Transaction::new()?;
+    /// let app = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\MyProduct")?;
+    /// let app = app.transacted(&t)?;
+    /// let (settings, _disp) = app.create_subkey("Settings")?;
+    /// settings.set_value("Version", &2u32)?;
+    /// app.delete_subkey_all("LegacySettings")?;
+    /// t.commit()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "transactions")]
+    pub fn transacted<'t>(&self, t: &'t Transaction) -> io::Result<TransactedKey<'t>> {
+        self.open_subkey_transacted_with_flags("", t, enums::KEY_READ | enums::KEY_WRITE)
+            .map(|key| TransactedKey::new(key, t))
+    }
+
     /// Part of `transactions` feature.
     #[cfg(feature = "transactions")]
     pub fn open_subkey_transacted<P: AsRef<OsStr>>(
@@ -263,11 +897,26 @@ impl RegKey {
                 ptr::null_mut(),
             )
         } {
-            0 => Ok(RegKey { hkey: new_hkey }),
+            0 => Ok(RegKey::new(new_hkey)),
             err => werr!(err),
         }
     }
 
+    /// Open subkey with desired permissions in the given registry view
+    /// as part of the transaction.
+    ///
+    /// Part of `transactions` feature.
+    #[cfg(feature = "transactions")]
+    pub fn open_subkey_transacted_view<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        t: &Transaction,
+        perms: Registry::REG_SAM_FLAGS,
+        view: RegistryView,
+    ) -> io::Result<RegKey> {
+        self.open_subkey_transacted_with_flags(path, t, perms | view.flags())
+    }
+
     /// Part of `transactions` feature.
     #[cfg(feature = "transactions")]
     pub fn open_subkey_transacted_with_options_flags<P: AsRef<OsStr>>(
@@ -290,7 +939,7 @@ impl RegKey {
                 ptr::null_mut(),
             )
         } {
-            0 => Ok(RegKey { hkey: new_hkey }),
+            0 => Ok(RegKey::new(new_hkey)),
             err => werr!(err),
         }
     }
@@ -347,7 +996,7 @@ impl RegKey {
         } {
             0 => {
                 let disp: RegDisposition = unsafe { transmute(disp_buf as u8) };
-                Ok((RegKey { hkey: new_hkey }, disp))
+                Ok((RegKey::new(new_hkey), disp))
             }
             err => werr!(err),
         }
@@ -377,12 +1026,158 @@ impl RegKey {
         } {
             0 => {
                 let disp: RegDisposition = unsafe { transmute(disp_buf as u8) };
-                Ok((RegKey { hkey: new_hkey }, disp))
+                Ok((RegKey::new(new_hkey), disp))
+            }
+            err => werr!(err),
+        }
+    }
+
+    /// Same as `create_subkey_with_options_flags`, but also sets the class of the key
+    /// if it's created. The class of an existing key is not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let (key, _disp) = hkcu.create_subkey_with_class(
+    ///     "Software\\MyProduct\\Perf",
+    ///     "PerfClass",
+    ///     REG_OPTION_NON_VOLATILE,
+    ///     KEY_ALL_ACCESS,
+    /// )?;
+    /// assert_eq!(key.class()?, "PerfClass");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_subkey_with_class<P: AsRef<OsStr>, C: AsRef<OsStr>>(
+        &self,
+        path: P,
+        class: C,
+        options: Registry::REG_OPEN_CREATE_OPTIONS,
+        perms: Registry::REG_SAM_FLAGS,
+    ) -> io::Result<(RegKey, RegDisposition)> {
+        let c_path = to_utf16(path);
+        let c_class = to_utf16(class);
+        let mut new_hkey: HKEY = std::ptr::null_mut();
+        let mut disp_buf: u32 = 0;
+        match unsafe {
+            Registry::RegCreateKeyExW(
+                self.hkey,
+                c_path.as_ptr(),
+                0,
+                c_class.as_ptr(),
+                options,
+                perms,
+                ptr::null_mut(),
+                &mut new_hkey,
+                &mut disp_buf,
+            )
+        } {
+            0 => {
+                let disp: RegDisposition = unsafe { transmute(disp_buf as u8) };
+                Ok((RegKey::new(new_hkey), disp))
             }
             err => werr!(err),
         }
     }
 
+    /// Create subkey with `KEY_ALL_ACCESS` permissions in the given registry view.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let (key, disp) = RegKey::predef(HKEY_LOCAL_MACHINE)
+    ///     .create_subkey_view("SOFTWARE\\MyProduct", RegistryView::V64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_subkey_view<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        view: RegistryView,
+    ) -> io::Result<(RegKey, RegDisposition)> {
+        self.create_subkey_with_flags(path, enums::KEY_ALL_ACCESS | view.flags())
+    }
+
+    /// Create subkey with `KEY_ALL_ACCESS` permissions that is kept in memory only
+    /// and disappears when the hive is unloaded, e.g. on restart for `HKLM`
+    /// or on logoff for `HKCU`. Subkeys of a volatile key must be volatile as well.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let (session, disp) = RegKey::predef(HKEY_CURRENT_USER)
+    ///     .create_volatile_subkey("Software\\MyProduct\\Session")?;
+    /// session.set_value("started", &1u32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_volatile_subkey<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+    ) -> io::Result<(RegKey, RegDisposition)> {
+        self.create_subkey_with_options_flags(
+            path,
+            Registry::REG_OPTION_VOLATILE,
+            enums::KEY_ALL_ACCESS,
+        )
+    }
+
+    /// Create a registry symbolic link at `path` pointing to `target`.
+    /// `target` must be an absolute kernel-mode path,
+    /// e.g. `\REGISTRY\MACHINE\SOFTWARE\MyProduct` or `\REGISTRY\USER\<SID>\Software\MyProduct`.
+    /// Returns a handle to the link key itself.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// RegKey::predef(HKEY_LOCAL_MACHINE).create_link(
+    ///     "SOFTWARE\\MyProduct\\Current",
+    ///     "\\REGISTRY\\MACHINE\\SOFTWARE\\MyProduct\\2.0",
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_link<P: AsRef<OsStr>, T: AsRef<OsStr>>(
+        &self,
+        path: P,
+        target: T,
+    ) -> io::Result<RegKey> {
+        let (link, _disp) = self.create_subkey_with_options_flags(
+            path,
+            Registry::REG_OPTION_CREATE_LINK,
+            enums::KEY_ALL_ACCESS | enums::KEY_CREATE_LINK,
+        )?;
+        // the link target is stored without a terminating NULL
+        let target: Vec<u16> = target.as_ref().encode_wide().collect();
+        let value = RegValue {
+            bytes: v16_to_v8(&target),
+            vtype: REG_LINK,
+        };
+        if let Err(e) = link.set_raw_value(LINK_VALUE_NAME, &value) {
+            // don't leave a dangling link behind
+            let _ = link.delete_link();
+            return Err(e);
+        }
+        Ok(link)
+    }
+
     /// Part of `transactions` feature.
     #[cfg(feature = "transactions")]
     pub fn create_subkey_transacted<P: AsRef<OsStr>>(
@@ -421,12 +1216,26 @@ impl RegKey {
         } {
             0 => {
                 let disp: RegDisposition = unsafe { transmute(disp_buf as u8) };
-                Ok((RegKey { hkey: new_hkey }, disp))
+                Ok((RegKey::new(new_hkey), disp))
             }
             err => werr!(err),
         }
     }
 
+    /// Create subkey with `KEY_ALL_ACCESS` permissions in the given registry view
+    /// as part of the transaction.
+    ///
+    /// Part of `transactions` feature.
+    #[cfg(feature = "transactions")]
+    pub fn create_subkey_transacted_view<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        t: &Transaction,
+        view: RegistryView,
+    ) -> io::Result<(RegKey, RegDisposition)> {
+        self.create_subkey_transacted_with_flags(path, t, enums::KEY_ALL_ACCESS | view.flags())
+    }
+
     /// Part of `transactions` feature.
     #[cfg(feature = "transactions")]
     pub fn create_subkey_transacted_with_options_flags<P: AsRef<OsStr>>(
@@ -456,7 +1265,7 @@ impl RegKey {
         } {
             0 => {
                 let disp: RegDisposition = unsafe { transmute(disp_buf as u8) };
-                Ok((RegKey { hkey: new_hkey }, disp))
+                Ok((RegKey::new(new_hkey), disp))
             }
             err => werr!(err),
         }
@@ -491,6 +1300,7 @@ impl RegKey {
 
     /// Copy all the values and subkeys from `path` to `dest` key.
     /// Will copy the content of `self` if `path` is an empty string.
+    /// Symbolic links inside the tree are copied as links, they are not followed.
     ///
     /// # Examples
     ///
@@ -507,6 +1317,17 @@ impl RegKey {
     /// # }
     /// ```
     pub fn copy_tree<P: AsRef<OsStr>>(&self, path: P, dest: &RegKey) -> io::Result<()> {
+        self.copy_tree_with_options(path, dest, CopyOptions::new())
+    }
+
+    /// Same as `copy_tree`, but symbolic links inside the tree are followed
+    /// and their targets' content is copied (`RegCopyTreeW` behavior).
+    /// Links can point outside of the tree or form cycles, only use this with trusted trees.
+    pub fn copy_tree_follow_links<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        dest: &RegKey,
+    ) -> io::Result<()> {
         let c_path = to_utf16(path);
         match unsafe { Registry::RegCopyTreeW(self.hkey, c_path.as_ptr(), dest.hkey) } {
             0 => Ok(()),
@@ -514,52 +1335,37 @@ impl RegKey {
         }
     }
 
-    pub fn query_info(&self) -> io::Result<RegKeyMetadata> {
-        let mut info: RegKeyMetadata = RegKeyMetadata::default();
-        match unsafe {
-            Registry::RegQueryInfoKeyW(
-                self.hkey,
-                ptr::null_mut(), // Class: winapi::LPWSTR,
-                ptr::null_mut(), // ClassLen: u32,
-                ptr::null_mut(), // Reserved
-                &mut info.sub_keys,
-                &mut info.max_sub_key_len,
-                &mut info.max_class_len,
-                &mut info.values,
-                &mut info.max_value_name_len,
-                &mut info.max_value_len,
-                ptr::null_mut(), // lpcbSecurityDescriptor: winapi::LPDWORD,
-                &mut info.last_write_time.0,
-            )
-        } {
-            0 => Ok(info),
-            err => werr!(err),
-        }
-    }
-
-    /// Return an iterator over subkeys names.
+    /// Same as `copy_tree`, configured with `options`
     ///
     /// # Examples
     ///
     /// ```no_run
+    /// # use std::error::Error;
     /// # use winreg2::RegKey;
+    /// # use winreg2::copy::{CopyOptions, OnExisting};
     /// # use winreg2::enums::*;
-    /// println!("File extensions, registered in this system:");
-    /// for i in RegKey::predef(HKEY_CLASSES_ROOT)
-    ///     .enum_keys().map(|x| x.unwrap())
-    ///     .filter(|x| x.starts_with("."))
-    /// {
-    ///     println!("{}", i);
-    /// }
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let (dst, _disp) = hkcu.create_subkey("Software\\MyProduct\\Current")?;
+    /// // fill in missing settings from the defaults, keeping the user's ones
+    /// let options = CopyOptions::new().on_existing(OnExisting::Skip);
+    /// hkcu.copy_tree_with_options("Software\\MyProduct\\Defaults", &dst, options)?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub const fn enum_keys(&self) -> EnumKeys {
-        EnumKeys {
-            key: self,
-            index: 0,
-        }
+    pub fn copy_tree_with_options<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        dest: &RegKey,
+        mut options: CopyOptions,
+    ) -> io::Result<()> {
+        copy::copy_tree(&self.open_subkey(path)?, dest, "", &mut options)
     }
 
-    /// Return an iterator over values.
+    /// Same as `copy_tree`, but all the keys are read and written as part of
+    /// the transaction `t`, so the copy is rolled back if it isn't committed.
+    ///
+    /// Part of `transactions` feature.
     ///
     /// # Examples
     ///
@@ -567,25 +1373,1375 @@ impl RegKey {
     /// # use std::error::Error;
     /// # use winreg2::RegKey;
     /// # use winreg2::enums::*;
+    /// # use winreg2::transaction::Transaction;
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// let system = RegKey::predef(HKEY_LOCAL_MACHINE)
-    ///     .open_subkey_with_flags("HARDWARE\\DESCRIPTION\\System", KEY_READ)?;
-    /// for (name, value) in system.enum_values().map(|x| x.unwrap()) {
-    ///     println!("{} = {:?}", name, value);
-    /// }
+    /// let t = Transaction::new()?;
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let (dst, _disp) = hkcu.create_subkey_transacted("Software\\MyProduct\\Backup", &t)?;
+    /// hkcu.copy_tree_transacted("Software\\MyProduct\\Settings", &dst, &t)?;
+    /// t.commit()?;
     /// # Ok(())
     /// # }
     /// ```
-    pub const fn enum_values(&self) -> EnumValues {
-        EnumValues {
-            key: self,
-            index: 0,
+    #[cfg(feature = "transactions")]
+    pub fn copy_tree_transacted<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        dest: &RegKey,
+        t: &Transaction,
+    ) -> io::Result<()> {
+        let dest = dest.open_subkey_transacted_with_flags("", t, enums::KEY_ALL_ACCESS)?;
+        self.open_subkey_transacted_with_flags(path, t, enums::KEY_READ)?
+            .copy_contents_transacted(&dest, t)
+    }
+
+    /// Move all the values and subkeys from `path` to `dest` key and delete `path`.
+    /// The copy and the delete are part of a transaction of their own, so on failure
+    /// the source is left intact and nothing is written to `dest`.
+    /// Symbolic links inside the tree are moved as links, they are not followed.
+    /// Fails with `ERROR_INVALID_PARAMETER` if `path` is empty or `dest` is `path`
+    /// itself or one of its subkeys.
+    ///
+    /// Part of `transactions` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let (dst, _disp) = hkcu.create_subkey("Software\\NewVendor\\MyProduct")?;
+    /// hkcu.move_tree("Software\\OldVendor\\MyProduct", &dst)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "transactions")]
+    pub fn move_tree<P: AsRef<OsStr>>(&self, path: P, dest: &RegKey) -> io::Result<()> {
+        let path = path.as_ref();
+        // an empty path would move the contents of this key and delete them
+        if path.to_string_lossy().split('\\').all(str::is_empty) {
+            return werr!(Foundation::ERROR_INVALID_PARAMETER);
+        }
+        let t = Transaction::new()?;
+        let dest = dest.open_subkey_transacted_with_flags("", &t, enums::KEY_ALL_ACCESS)?;
+        let src = self.open_subkey_transacted_with_flags(path, &t, enums::KEY_READ)?;
+        // copying into its own subtree would never end
+        if is_within(&dest.nt_name()?, &src.nt_name()?) {
+            return werr!(Foundation::ERROR_INVALID_PARAMETER);
+        }
+        src.copy_contents_transacted(&dest, &t)?;
+        drop(src);
+        self.delete_tree_transacted(path, &t)?;
+        t.commit()
+    }
+
+    /// Same as `copy::copy_tree`, but every key is opened and created in the transaction.
+    #[cfg(feature = "transactions")]
+    fn copy_contents_transacted(&self, dest: &RegKey, t: &Transaction) -> io::Result<()> {
+        for value in self.enum_values() {
+            let (name, value) = value?;
+            dest.set_raw_value(name, &value)?;
+        }
+        for name in self.enum_keys() {
+            let name = name?;
+            let child = self.open_subkey_transacted_with_options_flags(
+                &name,
+                t,
+                Registry::REG_OPTION_OPEN_LINK,
+                enums::KEY_READ,
+            )?;
+            let flags = child.key_flags()?;
+            if flags.link {
+                let (link, _disp) = dest.create_subkey_transacted_with_options_flags(
+                    &name,
+                    t,
+                    Registry::REG_OPTION_CREATE_LINK,
+                    enums::KEY_ALL_ACCESS | enums::KEY_CREATE_LINK,
+                )?;
+                link.set_raw_value(LINK_VALUE_NAME, &child.get_raw_value(LINK_VALUE_NAME)?)?;
+                continue;
+            }
+            // volatile keys can only have volatile subkeys
+            let options = if flags.volatile {
+                Registry::REG_OPTION_VOLATILE
+            } else {
+                Registry::REG_OPTION_NON_VOLATILE
+            };
+            let c_name = to_utf16(&name);
+            let c_class = to_utf16(child.class()?);
+            let mut new_hkey: HKEY = ptr::null_mut();
+            match unsafe {
+                Registry::RegCreateKeyTransactedW(
+                    dest.hkey,
+                    c_name.as_ptr(),
+                    0,
+                    c_class.as_ptr(),
+                    options,
+                    enums::KEY_ALL_ACCESS,
+                    ptr::null_mut(),
+                    &mut new_hkey,
+                    ptr::null_mut(),
+                    t.handle,
+                    ptr::null_mut(),
+                )
+            } {
+                0 => child.copy_contents_transacted(&RegKey::new(new_hkey), t)?,
+                err => return werr!(err),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn query_info(&self) -> io::Result<RegKeyMetadata> {
+        let mut info: RegKeyMetadata = RegKeyMetadata::default();
+        match unsafe {
+            Registry::RegQueryInfoKeyW(
+                self.hkey,
+                ptr::null_mut(), // Class: winapi::LPWSTR,
+                ptr::null_mut(), // ClassLen: u32,
+                ptr::null_mut(), // Reserved
+                &mut info.sub_keys,
+                &mut info.max_sub_key_len,
+                &mut info.max_class_len,
+                &mut info.values,
+                &mut info.max_value_name_len,
+                &mut info.max_value_len,
+                ptr::null_mut(), // lpcbSecurityDescriptor: winapi::LPDWORD,
+                &mut info.last_write_time.0,
+            )
+        } {
+            0 => Ok(info),
+            err => werr!(err),
+        }
+    }
+
+    /// Get the class of the key, an empty string if it has none.
+    /// Requires `KEY_QUERY_VALUE` access.
+    pub fn class(&self) -> io::Result<String> {
+        let mut buf: Vec<u16> = vec![0; 256];
+        loop {
+            // in characters, including the terminating NUL
+            let mut len = buf.len() as u32;
+            match unsafe {
+                Registry::RegQueryInfoKeyW(
+                    self.hkey,
+                    buf.as_mut_ptr(),
+                    &mut len,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                )
+            } {
+                0 => return Ok(String::from_utf16_lossy(&buf[..len as usize])),
+                Foundation::ERROR_MORE_DATA => {
+                    let new_len = (len as usize + 1).max(buf.len() * 2);
+                    buf.resize(new_len, 0);
+                }
+                err => return werr!(err),
+            }
+        }
+    }
+
+    /// Return an iterator over subkeys names.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// println!("File extensions, registered in this system:");
+    /// for i in RegKey::predef(HKEY_CLASSES_ROOT)
+    ///     .enum_keys().map(|x| x.unwrap())
+    ///     .filter(|x| x.starts_with("."))
+    /// {
+    ///     println!("{}", i);
+    /// }
+    /// ```
+    pub const fn enum_keys(&self) -> EnumKeys<'_> {
+        EnumKeys {
+            key: self,
+            index: 0,
+            count: Cell::new(None),
+        }
+    }
+
+    /// Same as `enum_keys`, but the names are returned as `OsString`, so names
+    /// that are not valid UTF-16, which `enum_keys` fails on, can be read as well.
+    pub const fn enum_keys_os(&self) -> EnumKeysOs<'_> {
+        EnumKeysOs(self.enum_keys())
+    }
+
+    /// Return an iterator over subkeys names, optionally with their flags.
+    /// With `with_flags` each subkey is opened without following symbolic links
+    /// to query its flags, so link keys can be told apart from regular ones.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let system = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey("SYSTEM")?;
+    /// for info in system.enum_key_infos(true) {
+    ///     let info = info?;
+    ///     if info.flags.map_or(false, |f| f.link) {
+    ///         println!("{} is a link", info.name);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn enum_key_infos(&self, with_flags: bool) -> EnumKeyInfos<'_> {
+        EnumKeyInfos {
+            key: self,
+            index: 0,
+            with_flags,
+        }
+    }
+
+    /// Return an iterator over subkeys names with their last write times and classes,
+    /// which `RegEnumKeyExW` reports along with the names, so no subkey has to be opened.
+    /// The class is `None` for subkeys without one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use std::time::{Duration, SystemTime};
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let services = RegKey::predef(HKEY_LOCAL_MACHINE)
+    ///     .open_subkey("SYSTEM\\CurrentControlSet\\Services")?;
+    /// let day_ago = SystemTime::now() - Duration::from_secs(24 * 3600);
+    /// for info in services.enum_keys_with_info() {
+    ///     let (name, time, _class) = info?;
+    ///     if time > day_ago {
+    ///         println!("{} changed", name);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn enum_keys_with_info(&self) -> EnumKeysWithInfo<'_> {
+        EnumKeysWithInfo {
+            key: self,
+            index: 0,
+            class: Vec::new(),
+        }
+    }
+
+    /// Return a recursive iterator over all subkeys, depth-first.
+    /// Symbolic links are reported but not followed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let software = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software")?;
+    /// for entry in software.walk() {
+    ///     println!("{}", entry?.path);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn walk(&self) -> Walk<'_> {
+        Walk::new(self, WalkOptions::new())
+    }
+
+    /// Same as `walk`, configured with `options`
+    pub fn walk_with_options(&self, options: WalkOptions) -> Walk<'_> {
+        Walk::new(self, options)
+    }
+
+    /// Read the values of the key and all its subkeys into memory, e.g. to restore
+    /// them with `RegSnapshot::restore_to` if a later change goes wrong.
+    /// Keys that can't be read are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let app = hkcu.open_subkey_with_flags("Software\\MyProduct", KEY_ALL_ACCESS)?;
+    /// let before = app.snapshot()?;
+    /// app.delete_subkey_all("Cache")?;
+    /// before.restore_to(&app)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> io::Result<RegSnapshot> {
+        RegSnapshot::new(self)
+    }
+
+    /// Walk all subkeys on a rayon pool of `threads` threads, or of the default size
+    /// if it's 0, which speeds up scans of large trees such as `HKLM\\SOFTWARE`.
+    /// Unlike `walk`, all the entries are collected before returning and their order
+    /// is unspecified. Symbolic links are reported but not followed.
+    /// The first error stops the walk and is returned.
+    /// Part of `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let classes = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey("SOFTWARE\\Classes")?;
+    /// let mut entries = classes.par_walk(8)?;
+    /// entries.sort_by(|a, b| a.path.cmp(&b.path));
+    /// println!("{} keys", entries.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_walk(&self, threads: usize) -> io::Result<Vec<WalkEntry>> {
+        self.par_walk_with_options(threads, WalkOptions::new())
+            .map(|walk| walk.entries)
+    }
+
+    /// Walk all subkeys in parallel like `par_walk`, with the depth, view, flags,
+    /// link and error handling of `options`; the order and handle limit don't apply.
+    /// Keys left out by `ErrorPolicy::Skip` and `ErrorPolicy::Collect`
+    /// are reported in the returned `ParWalk`.
+    /// Part of `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # use winreg2::walker::{ErrorPolicy, WalkOptions};
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let software = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey("SOFTWARE")?;
+    /// let options = WalkOptions::new().error_policy(ErrorPolicy::Skip);
+    /// let walk = software.par_walk_with_options(0, options)?;
+    /// println!("{} keys, {} not readable", walk.entries.len(), walk.skipped.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_walk_with_options(
+        &self,
+        threads: usize,
+        options: WalkOptions,
+    ) -> io::Result<walker::ParWalk> {
+        walker::par_walk(self, threads, options)
+    }
+
+    /// Return an iterator over the subkeys whose paths, relative to this key,
+    /// match `pattern` (see `path::Pattern` for the syntax). The tree is walked lazily,
+    /// no deeper than the pattern reaches unless it contains `**`.
+    /// Keys that can't be read are left out and reported by `FindKeys::skipped`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// for entry in hklm.find_keys("SOFTWARE\\*\\Windows\\CurrentVersion\\Uninstall\\*") {
+    ///     println!("{}", entry?.path);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_keys<P: Into<Pattern>>(&self, pattern: P) -> FindKeys<'_> {
+        FindKeys::new(self, pattern.into())
+    }
+
+    /// Return an iterator over the values whose paths, made of the path of their key
+    /// relative to this one and their name, match `pattern`. Values of this key itself
+    /// only have their name as path.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let software = hkcu.open_subkey("Software")?;
+    /// for found in software.find_values("**\\*Path") {
+    ///     let found = found?;
+    ///     println!("{}\\{} = {}", found.key, found.name, found.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_values<P: Into<Pattern>>(&self, pattern: P) -> FindValues<'_> {
+        FindValues::new(self, pattern.into())
+    }
+
+    /// Find the subkeys, up to `max_depth` levels deep, written since `since`.
+    /// Returns their paths relative to `self` with the last write times, most recent first.
+    /// The times come from enumerating the parent, so every key is opened only once.
+    /// Subkeys that can't be opened due to access rights are skipped, symbolic links
+    /// are reported but not followed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use std::time::{Duration, SystemTime};
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let software = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software")?;
+    /// let hour_ago = SystemTime::now() - Duration::from_secs(3600);
+    /// for (path, time) in software.recently_modified(hour_ago, Some(3))? {
+    ///     println!("{:?} {}", time, path);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn recently_modified(
+        &self,
+        since: SystemTime,
+        max_depth: Option<usize>,
+    ) -> io::Result<Vec<(String, SystemTime)>> {
+        let mut found = Vec::new();
+        self.collect_modified("", 1, since, max_depth, &mut found)?;
+        found.sort_by_key(|&(_, time)| std::cmp::Reverse(time));
+        Ok(found)
+    }
+
+    fn collect_modified(
+        &self,
+        path: &str,
+        depth: usize,
+        since: SystemTime,
+        max_depth: Option<usize>,
+        found: &mut Vec<(String, SystemTime)>,
+    ) -> io::Result<()> {
+        let mut index = 0;
+        while let Some(item) = self.enum_key_with_time(index) {
+            index += 1;
+            let (name, time) = item?;
+            let child_path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}\\{}", path, name)
+            };
+            let time = time.to_system_time();
+            if time >= since {
+                found.push((child_path.clone(), time));
+            }
+            if max_depth.map_or(false, |max| depth >= max) {
+                continue;
+            }
+            let child = match self.open_subkey_nofollow(&name, enums::KEY_READ) {
+                Ok(child) => child,
+                Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => continue,
+                Err(e) => return Err(e),
+            };
+            if !child.key_flags()?.link {
+                child.collect_modified(&child_path, depth + 1, since, max_depth, found)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return an iterator over values.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let system = RegKey::predef(HKEY_LOCAL_MACHINE)
+    ///     .open_subkey_with_flags("HARDWARE\\DESCRIPTION\\System", KEY_READ)?;
+    /// for (name, value) in system.enum_values().map(|x| x.unwrap()) {
+    ///     println!("{} = {:?}", name, value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn enum_values(&self) -> EnumValues<'_> {
+        EnumValues {
+            key: self,
+            index: 0,
+            count: Cell::new(None),
+            name: Vec::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Same as `enum_values`, but the names are returned as `OsString`, so names
+    /// that are not valid UTF-16, which `enum_values` fails on, can be read as well.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let key = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\Hostile")?;
+    /// for value in key.enum_values_os() {
+    ///     let (name, value) = value?;
+    ///     // the exact name can be passed back, e.g. to delete the value
+    ///     println!("{:?} = {:?}", name, value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn enum_values_os(&self) -> EnumValuesOs<'_> {
+        EnumValuesOs(self.enum_values())
+    }
+
+    /// Delete key. Key names are not case sensitive.
+    /// Cannot delete if it has subkeys.
+    /// Use `delete_subkey_all` for that.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// RegKey::predef(HKEY_CURRENT_USER)
+    ///     .delete_subkey(r"Software\MyProduct\History")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delete_subkey<P: AsRef<OsStr>>(&self, path: P) -> io::Result<()> {
+        self.delete_subkey_with_flags(path, 0)
+    }
+
+    /// Delete key from the desired platform-specific view of the registry.
+    /// Key names are not case sensitive.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// // delete the key from the 32-bit registry view
+    /// RegKey::predef(HKEY_LOCAL_MACHINE)
+    ///     .delete_subkey_with_flags(r"Software\MyProduct\History", KEY_WOW64_32KEY)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delete_subkey_with_flags<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        perms: Registry::REG_SAM_FLAGS,
+    ) -> io::Result<()> {
+        let c_path = to_utf16(path);
+        match unsafe {
+            Registry::RegDeleteKeyExW(
+                self.hkey,
+                c_path.as_ptr(), // This parameter cannot be NULL.
+                perms,
+                0,
+            )
+        } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Delete key from the given registry view. Cannot delete if it has subkeys,
+    /// use `delete_subkey_all_view` for that.
+    pub fn delete_subkey_view<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        view: RegistryView,
+    ) -> io::Result<()> {
+        self.delete_subkey_with_flags(path, view.flags())
+    }
+
+    /// Part of `transactions` feature.
+    #[cfg(feature = "transactions")]
+    pub fn delete_subkey_transacted<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        t: &Transaction,
+    ) -> io::Result<()> {
+        self.delete_subkey_transacted_with_flags(path, t, 0)
+    }
+
+    /// Part of `transactions` feature.
+    #[cfg(feature = "transactions")]
+    pub fn delete_subkey_transacted_with_flags<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        t: &Transaction,
+        perms: Registry::REG_SAM_FLAGS,
+    ) -> io::Result<()> {
+        let c_path = to_utf16(path);
+        match unsafe {
+            Registry::RegDeleteKeyTransactedW(
+                self.hkey,
+                c_path.as_ptr(), // This parameter cannot be NULL.
+                perms,
+                0,
+                t.handle,
+                ptr::null_mut(),
+            )
+        } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Delete key from the given registry view as part of the transaction.
+    ///
+    /// Part of `transactions` feature.
+    #[cfg(feature = "transactions")]
+    pub fn delete_subkey_transacted_view<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        t: &Transaction,
+        view: RegistryView,
+    ) -> io::Result<()> {
+        self.delete_subkey_transacted_with_flags(path, t, view.flags())
+    }
+
+    /// Check whether registry reflection is disabled for the key.
+    /// Reflection copies keys between the 32-bit and 64-bit views on
+    /// 64-bit Windows Server 2003, XP and Vista, later versions don't reflect anything.
+    ///
+    /// Part of `legacy-reflection` feature.
+    #[cfg(feature = "legacy-reflection")]
+    pub fn is_reflection_disabled(&self) -> io::Result<bool> {
+        let mut disabled: Foundation::BOOL = 0;
+        match unsafe { Registry::RegQueryReflectionKey(self.hkey, &mut disabled) } {
+            0 => Ok(disabled != 0),
+            err => werr!(err),
+        }
+    }
+
+    /// Restore registry reflection for a key disabled with `disable_reflection`.
+    ///
+    /// Part of `legacy-reflection` feature.
+    #[cfg(feature = "legacy-reflection")]
+    pub fn enable_reflection(&self) -> io::Result<()> {
+        match unsafe { Registry::RegEnableReflectionKey(self.hkey) } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Disable registry reflection for the key, its subkeys are not affected.
+    ///
+    /// Part of `legacy-reflection` feature.
+    #[cfg(feature = "legacy-reflection")]
+    pub fn disable_reflection(&self) -> io::Result<()> {
+        match unsafe { Registry::RegDisableReflectionKey(self.hkey) } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Recursively delete subkey with all its subkeys and values.
+    /// If `path` is an empty string, the subkeys and values of this key are deleted.
+    /// Symbolic links are deleted without touching their targets,
+    /// so the deletion can't escape the subtree.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// RegKey::predef(HKEY_CURRENT_USER)
+    ///     .delete_subkey_all("Software\\MyProduct")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delete_subkey_all<P: AsRef<OsStr>>(&self, path: P) -> io::Result<()> {
+        self.delete_tree_nofollow(path.as_ref(), RegistryView::Default)
+    }
+
+    /// Same as `delete_subkey_all`, but symbolic links inside the tree are followed
+    /// and their targets' content is deleted (`RegDeleteTreeW` behavior).
+    /// Links can point outside of the tree, only use this with trusted trees.
+    pub fn delete_subkey_all_follow_links<P: AsRef<OsStr>>(&self, path: P) -> io::Result<()> {
+        let c_path;
+        let path_ptr = if path.as_ref().is_empty() {
+            ptr::null()
+        } else {
+            c_path = to_utf16(path);
+            c_path.as_ptr()
+        };
+        match unsafe {
+            Registry::RegDeleteTreeW(
+                self.hkey,
+                path_ptr, //If this parameter is NULL, the subkeys and values of this key are deleted.
+            )
+        } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+
+    /// Recursively delete subkey with all its subkeys and values from the given registry view.
+    /// Symbolic links are deleted without touching their targets.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// RegKey::predef(HKEY_LOCAL_MACHINE)
+    ///     .delete_subkey_all_view("SOFTWARE\\MyProduct", RegistryView::V32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delete_subkey_all_view<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        view: RegistryView,
+    ) -> io::Result<()> {
+        self.delete_tree_nofollow(path.as_ref(), view)
+    }
+
+    /// Same as `delete_subkey_all`, but all the keys are opened and deleted as part of
+    /// the transaction `t`, so the deletion is rolled back if it isn't committed.
+    ///
+    /// Part of `transactions` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # use winreg2::transaction::Transaction;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let t = Transaction::new()?;
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// hkcu.delete_subkey_all_transacted("Software\\MyProduct\\Cache", &t)?;
+    /// hkcu.delete_subkey_all_transacted("Software\\MyProduct\\History", &t)?;
+    /// t.commit()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "transactions")]
+    pub fn delete_subkey_all_transacted<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        t: &Transaction,
+    ) -> io::Result<()> {
+        self.delete_tree_transacted(path.as_ref(), t)
+    }
+
+    fn delete_tree_nofollow(&self, path: &OsStr, view: RegistryView) -> io::Result<()> {
+        if path.is_empty() {
+            let values: Vec<String> = self
+                .enum_values()
+                .map(|v| v.map(|(name, _)| name))
+                .collect::<io::Result<_>>()?;
+            for name in values {
+                self.delete_value(name)?;
+            }
+            return self.delete_children_nofollow(view);
+        }
+        let key = self.open_subkey_with_options_flags(
+            path,
+            Registry::REG_OPTION_OPEN_LINK,
+            DELETE_TREE_SAM | view.flags(),
+        )?;
+        if key.key_flags()?.link {
+            return key.delete_link();
+        }
+        key.delete_children_nofollow(view)?;
+        drop(key);
+        self.delete_subkey_view(path, view)
+    }
+
+    fn delete_children_nofollow(&self, view: RegistryView) -> io::Result<()> {
+        // collect the names first, deleting while enumerating shifts the indices
+        let names: Vec<String> = self.enum_keys().collect::<io::Result<_>>()?;
+        for name in names {
+            let child = self.open_subkey_with_options_flags(
+                &name,
+                Registry::REG_OPTION_OPEN_LINK,
+                DELETE_TREE_SAM | view.flags(),
+            )?;
+            if child.key_flags()?.link {
+                child.delete_link()?;
+                continue;
+            }
+            child.delete_children_nofollow(view)?;
+            drop(child);
+            self.delete_subkey_view(&name, view)?;
+        }
+        Ok(())
+    }
+
+    /// Same as `delete_tree_nofollow`, but every key is opened and deleted in the transaction.
+    #[cfg(feature = "transactions")]
+    fn delete_tree_transacted(&self, path: &OsStr, t: &Transaction) -> io::Result<()> {
+        let key = self.open_subkey_transacted_with_options_flags(
+            path,
+            t,
+            Registry::REG_OPTION_OPEN_LINK,
+            DELETE_TREE_SAM,
+        )?;
+        if path.is_empty() {
+            let values: Vec<String> = key
+                .enum_values()
+                .map(|v| v.map(|(name, _)| name))
+                .collect::<io::Result<_>>()?;
+            for name in values {
+                key.delete_value(name)?;
+            }
+            return key.delete_children_transacted(t);
+        }
+        if key.key_flags()?.link {
+            return key.delete_link();
+        }
+        key.delete_children_transacted(t)?;
+        drop(key);
+        self.delete_subkey_transacted(path, t)
+    }
+
+    #[cfg(feature = "transactions")]
+    fn delete_children_transacted(&self, t: &Transaction) -> io::Result<()> {
+        // collect the names first, deleting while enumerating shifts the indices
+        let names: Vec<String> = self.enum_keys().collect::<io::Result<_>>()?;
+        for name in names {
+            self.delete_tree_transacted(name.as_ref(), t)?;
+        }
+        Ok(())
+    }
+
+    /// Read the target of a symbolic link opened with `open_subkey_nofollow`
+    /// or returned by `create_link`.
+    pub fn read_link(&self) -> io::Result<String> {
+        let value = self.get_raw_value(LINK_VALUE_NAME)?;
+        if value.vtype != REG_LINK {
+            return werr!(Foundation::ERROR_BAD_FILE_TYPE);
+        }
+        let words: Vec<u16> = value
+            .bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&w| w != 0)
+            .collect();
+        String::from_utf16(&words)
+            .map_err(|_| io::Error::from_raw_os_error(Foundation::ERROR_INVALID_DATA as i32))
+    }
+
+    /// Check whether the key was created with `REG_OPTION_VOLATILE`.
+    /// Doesn't work with predefined keys, which are pseudo-handles.
+    pub fn is_volatile(&self) -> io::Result<bool> {
+        Ok(self.key_flags()?.volatile)
+    }
+
+    /// Check whether the key is a symbolic link. Only handles opened with
+    /// `open_subkey_nofollow` or returned by `create_link` refer to the link itself,
+    /// other opens follow it to the target.
+    pub fn is_symlink(&self) -> io::Result<bool> {
+        Ok(self.key_flags()?.link)
+    }
+
+    /// Query the volatile and symbolic link flags of the key via `NtQueryKey`.
+    /// The link flag is only set for handles opened with `open_subkey_nofollow`,
+    /// as other opens resolve the link to its target.
+    /// Doesn't work with predefined keys, which are pseudo-handles.
+    pub fn key_flags(&self) -> io::Result<KeyFlags> {
+        let mut info = KeyFlagsInformation::default();
+        let mut len: u32 = 0;
+        match unsafe {
+            WdkRegistry::NtQueryKey(
+                self.hkey,
+                WdkRegistry::KeyFlagsInformation,
+                &mut info as *mut KeyFlagsInformation as *mut _,
+                mem::size_of::<KeyFlagsInformation>() as u32,
+                &mut len,
+            )
+        } {
+            0 => Ok(KeyFlags {
+                volatile: info.key_flags & REG_FLAG_VOLATILE != 0,
+                link: info.key_flags & REG_FLAG_LINK != 0,
+            }),
+            status => werr!(unsafe { Foundation::RtlNtStatusToDosError(status) }),
+        }
+    }
+
+    /// Report where the key physically lives, after WOW64 redirection and
+    /// UAC virtualization were applied when it was opened.
+    /// Doesn't work with predefined keys, which are pseudo-handles.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let key = RegKey::predef(HKEY_LOCAL_MACHINE)
+    ///     .open_subkey_with_flags("SOFTWARE\\MyProduct", KEY_READ | KEY_WOW64_32KEY)?;
+    /// let location = key.physical_path()?;
+    /// // \REGISTRY\MACHINE\SOFTWARE\WOW6432Node\MyProduct
+    /// println!("{} (redirected: {})", location.path, location.wow64_redirected);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn physical_path(&self) -> io::Result<PhysicalPath> {
+        let path = self.nt_name()?;
+        let components: Vec<&str> = path.split('\\').collect();
+        let has = |name: &str| components.iter().any(|c| c.eq_ignore_ascii_case(name));
+        Ok(PhysicalPath {
+            wow64_redirected: has("WOW6432Node"),
+            virtualized: has("VirtualStore"),
+            path,
+        })
+    }
+
+    /// Get the path of the key in the form shown by regedit, e.g.
+    /// `HKEY_CURRENT_USER\Software\MyApp`. Keys outside of the predefined
+    /// hives, such as application hives, keep their kernel-mode name.
+    /// The result is cached on first use, so a later rename of the key is not reflected.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let key = hkcu.open_subkey("Software")?;
+    /// assert_eq!(key.full_path()?, "HKEY_CURRENT_USER\\Software");
+    /// println!("{:?}", key); // RegKey(HKEY_CURRENT_USER\Software)
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn full_path(&self) -> io::Result<String> {
+        if let Some(ref path) = *self.path.borrow() {
+            return Ok(path.clone());
+        }
+        let path = match enums::root_name(self.hkey) {
+            Some(root) => root.to_owned(),
+            None => win32_path(&self.nt_name()?),
+        };
+        *self.path.borrow_mut() = Some(path.clone());
+        Ok(path)
+    }
+
+    /// Query the kernel-mode name of the key, e.g. `\REGISTRY\MACHINE\SOFTWARE`
+    pub(crate) fn nt_name(&self) -> io::Result<String> {
+        // KEY_NAME_INFORMATION: NameLength in bytes followed by the name
+        let mut buf: Vec<u32> = vec![0; 128];
+        loop {
+            let mut len: u32 = 0;
+            match unsafe {
+                WdkRegistry::NtQueryKey(
+                    self.hkey,
+                    WdkRegistry::KeyNameInformation,
+                    buf.as_mut_ptr() as *mut _,
+                    (buf.len() * 4) as u32,
+                    &mut len,
+                )
+            } {
+                0 => {
+                    let name_len = (buf[0] as usize / 2).min((buf.len() - 1) * 2);
+                    let name = unsafe {
+                        std::slice::from_raw_parts(buf[1..].as_ptr() as *const u16, name_len)
+                    };
+                    return Ok(String::from_utf16_lossy(name));
+                }
+                Foundation::STATUS_BUFFER_TOO_SMALL | Foundation::STATUS_BUFFER_OVERFLOW => {
+                    buf.resize((len as usize + 3) / 4 + 1, 0);
+                }
+                status => return werr!(unsafe { Foundation::RtlNtStatusToDosError(status) }),
+            }
+        }
+    }
+
+    /// Delete the symbolic link this handle refers to, leaving the target untouched.
+    /// The handle must be opened with `open_subkey_nofollow` and `DELETE` access
+    /// (included in `KEY_ALL_ACCESS`), or returned by `create_link`.
+    pub fn delete_link(&self) -> io::Result<()> {
+        match unsafe { WdkRegistry::NtDeleteKey(self.hkey) } {
+            0 => Ok(()),
+            status => werr!(unsafe { Foundation::RtlNtStatusToDosError(status) }),
+        }
+    }
+
+    /// Get a value from registry and seamlessly convert it to the specified rust type
+    /// with `FromRegValue` implemented (currently `String`, `u32` and `u64`).
+    /// Will get the `Default` value if `name` is an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
+    /// let server: String = settings.get_value("server")?;
+    /// let port: u32 = settings.get_value("port")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_value<T: FromRegValue, N: AsRef<OsStr>>(&self, name: N) -> io::Result<T> {
+        match self.get_raw_value(name) {
+            Ok(ref val) => FromRegValue::from_reg_value(val),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get a value like `get_value`, but return `None` if the value doesn't exist.
+    /// Other errors, e.g. a denied access or a type mismatch, are returned as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
+    /// let port: u32 = settings.get_value_opt("port")?.unwrap_or(8080);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_value_opt<T: FromRegValue, N: AsRef<OsStr>>(
+        &self,
+        name: N,
+    ) -> io::Result<Option<T>> {
+        match self.get_raw_value(name) {
+            Ok(ref val) => FromRegValue::from_reg_value(val).map(Some),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get a value like `get_value`, but return `default` if the value doesn't exist.
+    /// Other errors are returned as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
+    /// let timeout = settings.get_value_or("Timeout", 30u32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_value_or<T: FromRegValue, N: AsRef<OsStr>>(
+        &self,
+        name: N,
+        default: T,
+    ) -> io::Result<T> {
+        self.get_value_opt(name).map(|v| v.unwrap_or(default))
+    }
+
+    /// Get a value like `get_value`, but return `T::default()` if the value doesn't exist.
+    /// Other errors are returned as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
+    /// let proxy: String = settings.get_value_or_default("Proxy")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_value_or_default<T: FromRegValue + Default, N: AsRef<OsStr>>(
+        &self,
+        name: N,
+    ) -> io::Result<T> {
+        self.get_value_opt(name).map(Option::unwrap_or_default)
+    }
+
+    /// Read all values of the key, converted to `T`, by name.
+    /// The `Default` value has an empty name.
+    /// Fails if any of the values can't be converted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let limits = hkcu.open_subkey("Software\\MyProduct\\Limits")?;
+    /// let limits = limits.get_values::<u32>()?;
+    /// println!("max connections: {:?}", limits.get("MaxConnections"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_values<T: FromRegValue>(&self) -> io::Result<HashMap<String, T>> {
+        self.enum_values()
+            .map(|v| v.and_then(|(name, value)| Ok((name, T::from_reg_value(&value)?))))
+            .collect()
+    }
+
+    /// Read all values of the key by name.
+    /// The `Default` value has an empty name.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let system = RegKey::predef(HKEY_LOCAL_MACHINE)
+    ///     .open_subkey_with_flags("HARDWARE\\DESCRIPTION\\System", KEY_READ)?;
+    /// for (name, value) in system.get_all_raw_values()? {
+    ///     println!("{} = {:?}", name, value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_all_raw_values(&self) -> io::Result<HashMap<String, RegValue>> {
+        self.enum_values().collect()
+    }
+
+    /// Get raw bytes from registry value.
+    /// Will get the `Default` value if `name` is an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
+    /// let data = settings.get_raw_value("data")?;
+    /// println!("Bytes: {:?}", data.bytes);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_raw_value<N: AsRef<OsStr>>(&self, name: N) -> io::Result<RegValue> {
+        let c_name = to_utf16(name);
+        let mut buf_len: u32 = 2048;
+        let mut buf_type: u32 = 0;
+        let mut buf: Vec<u8> = Vec::with_capacity(buf_len as usize);
+        loop {
+            match unsafe {
+                Registry::RegQueryValueExW(
+                    self.hkey,
+                    c_name.as_ptr(),
+                    ptr::null_mut(),
+                    &mut buf_type,
+                    buf.as_mut_ptr(),
+                    &mut buf_len,
+                )
+            } {
+                0 => {
+                    unsafe {
+                        buf.set_len(buf_len as usize);
+                    }
+                    let t = RegType::from(buf_type);
+                    return Ok(RegValue {
+                        bytes: buf,
+                        vtype: t,
+                    });
+                }
+                Foundation::ERROR_MORE_DATA => {
+                    buf.reserve(buf_len as usize);
+                }
+                err => return werr!(err),
+            }
+        }
+    }
+
+    /// Read the value `name` of the subkey `path` (or of the key itself if `path`
+    /// is empty) with `RegGetValueW`, without opening the subkey first.
+    /// `flags` must contain at least one of the `RRF_RT_*` constants restricting the
+    /// accepted types, values of other types fail with `ERROR_UNSUPPORTED_TYPE`.
+    /// `REG_EXPAND_SZ` values are expanded and reported as `REG_SZ` unless
+    /// `RRF_NOEXPAND` is given, which `RRF_RT_REG_EXPAND_SZ` requires.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let path = hkcu.get_raw_value_with_flags("Environment", "Path", RRF_RT_REG_SZ)?;
+    /// println!("expanded: {}", path);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_raw_value_with_flags<P: AsRef<OsStr>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        flags: Registry::REG_ROUTINE_FLAGS,
+    ) -> io::Result<RegValue> {
+        let c_path = to_utf16(path);
+        let c_name = to_utf16(name);
+        let mut buf: Vec<u8> = vec![0; 2048];
+        loop {
+            let mut buf_len = buf.len() as u32;
+            let mut buf_type: u32 = 0;
+            match unsafe {
+                Registry::RegGetValueW(
+                    self.hkey,
+                    c_path.as_ptr(),
+                    c_name.as_ptr(),
+                    flags,
+                    &mut buf_type,
+                    buf.as_mut_ptr() as *mut _,
+                    &mut buf_len,
+                )
+            } {
+                0 => {
+                    buf.truncate(buf_len as usize);
+                    let t = RegType::from(buf_type);
+                    return Ok(RegValue {
+                        bytes: buf,
+                        vtype: t,
+                    });
+                }
+                // grow past the reported size, an expanded string may have changed meanwhile
+                Foundation::ERROR_MORE_DATA => buf.resize(buf.len().max(buf_len as usize) * 2, 0),
+                err => return werr!(err),
+            }
+        }
+    }
+
+    /// Same as `get_raw_value_with_flags`, converting the value to a rust type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// let build: String = hklm.get_value_with_flags(
+    ///     "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion",
+    ///     "CurrentBuild",
+    ///     RRF_RT_REG_SZ,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_value_with_flags<T: FromRegValue, P: AsRef<OsStr>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        flags: Registry::REG_ROUTINE_FLAGS,
+    ) -> io::Result<T> {
+        T::from_reg_value(&self.get_raw_value_with_flags(path, name, flags)?)
+    }
+
+    /// Read several values of the key at once with `RegQueryMultipleValuesW`,
+    /// so they are consistent with each other. Fails if any of them doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let app = hkcu.open_subkey("Software\\MyProduct")?;
+    /// let values = app.query_multiple_values(&["Name", "Version", "Path"])?;
+    /// for value in &values {
+    ///     println!("{}", value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_multiple_values<N: AsRef<OsStr>>(&self, names: &[N]) -> io::Result<Vec<RegValue>> {
+        let mut c_names: Vec<Vec<u16>> = names.iter().map(to_utf16).collect();
+        let mut entries: Vec<Registry::VALENTW> = c_names
+            .iter_mut()
+            .map(|name| Registry::VALENTW {
+                ve_valuename: name.as_mut_ptr(),
+                ve_valuelen: 0,
+                ve_valueptr: 0,
+                ve_type: 0,
+            })
+            .collect();
+        // u16 units as the API expects a wide string buffer
+        let mut buf: Vec<u16> = vec![0; 1024];
+        loop {
+            let mut buf_len = (buf.len() * 2) as u32;
+            match unsafe {
+                Registry::RegQueryMultipleValuesW(
+                    self.hkey,
+                    entries.as_mut_ptr(),
+                    entries.len() as u32,
+                    buf.as_mut_ptr(),
+                    &mut buf_len,
+                )
+            } {
+                0 => break,
+                Foundation::ERROR_MORE_DATA => buf.resize((buf_len as usize + 1) / 2, 0),
+                err => return werr!(err),
+            }
         }
+        entries
+            .iter()
+            .map(|entry| {
+                let t = RegType::from(entry.ve_type);
+                // the data pointers point into `buf`
+                let bytes = unsafe {
+                    std::slice::from_raw_parts(
+                        entry.ve_valueptr as *const u8,
+                        entry.ve_valuelen as usize,
+                    )
+                };
+                Ok(RegValue {
+                    bytes: bytes.to_vec(),
+                    vtype: t,
+                })
+            })
+            .collect()
     }
 
-    /// Delete key. Key names are not case sensitive.
-    /// Cannot delete if it has subkeys.
-    /// Use `delete_subkey_all` for that.
+    /// Seamlessly convert a value from a rust type and write it to the registry value
+    /// with `ToRegValue` trait implemented (currently `String`, `&str`, `u32` and `u64`).
+    /// Will set the `Default` value if `name` is an empty string.
     ///
     /// # Examples
     ///
@@ -594,42 +2750,47 @@ impl RegKey {
     /// # use winreg2::RegKey;
     /// # use winreg2::enums::*;
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// RegKey::predef(HKEY_CURRENT_USER)
-    ///     .delete_subkey(r"Software\MyProduct\History")?;
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let (settings, disp) = hkcu.create_subkey("Software\\MyProduct\\Settings")?;
+    /// settings.set_value("server", &"www.example.com")?;
+    /// settings.set_value("port", &8080u32)?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn delete_subkey<P: AsRef<OsStr>>(&self, path: P) -> io::Result<()> {
-        self.delete_subkey_with_flags(path, 0)
+    pub fn set_value<T: ToRegValue, N: AsRef<OsStr>>(&self, name: N, value: &T) -> io::Result<()> {
+        self.set_raw_value(name, &value.to_reg_value())
     }
 
-    /// Delete key from the desired platform-specific view of the registry.
-    /// Key names are not case sensitive.
+    /// Write raw bytes from `RegValue` struct to a registry value.
+    /// Will set the `Default` value if `name` is an empty string.
     ///
     /// # Examples
+    ///
     /// ```no_run
     /// # use std::error::Error;
-    /// # use winreg2::RegKey;
-    /// # use winreg2::enums::*;
+    /// use winreg2::{RegKey, RegValue};
+    /// use winreg2::enums::*;
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// // delete the key from the 32-bit registry view
-    /// RegKey::predef(HKEY_LOCAL_MACHINE)
-    ///     .delete_subkey_with_flags(r"Software\MyProduct\History", KEY_WOW64_32KEY)?;
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
+    /// let bytes: Vec<u8> = vec![1, 2, 3, 5, 8, 13, 21, 34, 55, 89];
+    /// let data = RegValue{ vtype: REG_BINARY, bytes: bytes};
+    /// settings.set_raw_value("data", &data)?;
+    /// println!("Bytes: {:?}", data.bytes);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn delete_subkey_with_flags<P: AsRef<OsStr>>(
-        &self,
-        path: P,
-        perms: Registry::REG_SAM_FLAGS,
-    ) -> io::Result<()> {
-        let c_path = to_utf16(path);
+    pub fn set_raw_value<N: AsRef<OsStr>>(&self, name: N, value: &RegValue) -> io::Result<()> {
+        let c_name = to_utf16(name);
+        let t = u32::from(value.vtype.clone());
         match unsafe {
-            Registry::RegDeleteKeyExW(
+            Registry::RegSetValueExW(
                 self.hkey,
-                c_path.as_ptr(), // This parameter cannot be NULL.
-                perms,
+                c_name.as_ptr(),
                 0,
+                t,
+                value.bytes.as_ptr(),
+                value.bytes.len() as u32,
             )
         } {
             0 => Ok(()),
@@ -637,42 +2798,32 @@ impl RegKey {
         }
     }
 
-    /// Part of `transactions` feature.
-    #[cfg(feature = "transactions")]
-    pub fn delete_subkey_transacted<P: AsRef<OsStr>>(
-        &self,
-        path: P,
-        t: &Transaction,
-    ) -> io::Result<()> {
-        self.delete_subkey_transacted_with_flags(path, t, 0)
-    }
-
-    /// Part of `transactions` feature.
-    #[cfg(feature = "transactions")]
-    pub fn delete_subkey_transacted_with_flags<P: AsRef<OsStr>>(
-        &self,
-        path: P,
-        t: &Transaction,
-        perms: Registry::REG_SAM_FLAGS,
-    ) -> io::Result<()> {
-        let c_path = to_utf16(path);
-        match unsafe {
-            Registry::RegDeleteKeyTransactedW(
-                self.hkey,
-                c_path.as_ptr(), // This parameter cannot be NULL.
-                perms,
-                0,
-                t.handle,
-                ptr::null_mut(),
-            )
-        } {
+    /// Delete specified value from registry.
+    /// Will delete the `Default` value if `name` is an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
+    /// settings.delete_value("data")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn delete_value<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        let c_name = to_utf16(name);
+        match unsafe { Registry::RegDeleteValueW(self.hkey, c_name.as_ptr()) } {
             0 => Ok(()),
             err => werr!(err),
         }
     }
 
-    /// Recursively delete subkey with all its subkeys and values.
-    /// If `path` is an empty string, the subkeys and values of this key are deleted.
+    /// Write a value to the subkey `path` without opening it first,
+    /// the subkey is created if it doesn't exist.
     ///
     /// # Examples
     ///
@@ -681,23 +2832,40 @@ impl RegKey {
     /// # use winreg2::RegKey;
     /// # use winreg2::enums::*;
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// RegKey::predef(HKEY_CURRENT_USER)
-    ///     .delete_subkey_all("Software\\MyProduct")?;
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// for plugin in &["Alpha", "Beta", "Gamma"] {
+    ///     hkcu.set_value_in(format!("Software\\MyProduct\\Plugins\\{}", plugin), "Enabled", &1u32)?;
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn delete_subkey_all<P: AsRef<OsStr>>(&self, path: P) -> io::Result<()> {
-        let c_path;
-        let path_ptr = if path.as_ref().is_empty() {
-            ptr::null()
-        } else {
-            c_path = to_utf16(path);
-            c_path.as_ptr()
-        };
+    pub fn set_value_in<T: ToRegValue, P: AsRef<OsStr>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        value: &T,
+    ) -> io::Result<()> {
+        self.set_raw_value_in(path, name, &value.to_reg_value())
+    }
+
+    /// Same as `set_value_in`, writing raw bytes from `RegValue` struct.
+    pub fn set_raw_value_in<P: AsRef<OsStr>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        value: &RegValue,
+    ) -> io::Result<()> {
+        let c_path = to_utf16(path);
+        let c_name = to_utf16(name);
+        let t = u32::from(value.vtype.clone());
         match unsafe {
-            Registry::RegDeleteTreeW(
+            Registry::RegSetKeyValueW(
                 self.hkey,
-                path_ptr, //If this parameter is NULL, the subkeys and values of this key are deleted.
+                c_path.as_ptr(),
+                c_name.as_ptr(),
+                t,
+                value.bytes.as_ptr() as *const _,
+                value.bytes.len() as u32,
             )
         } {
             0 => Ok(()),
@@ -705,9 +2873,7 @@ impl RegKey {
         }
     }
 
-    /// Get a value from registry and seamlessly convert it to the specified rust type
-    /// with `FromRegValue` implemented (currently `String`, `u32` and `u64`).
-    /// Will get the `Default` value if `name` is an empty string.
+    /// Delete a value from the subkey `path` without opening it first.
     ///
     /// # Examples
     ///
@@ -717,21 +2883,27 @@ impl RegKey {
     /// # use winreg2::enums::*;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
-    /// let server: String = settings.get_value("server")?;
-    /// let port: u32 = settings.get_value("port")?;
+    /// hkcu.delete_value_in("Software\\MyProduct\\Settings", "data")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get_value<T: FromRegValue, N: AsRef<OsStr>>(&self, name: N) -> io::Result<T> {
-        match self.get_raw_value(name) {
-            Ok(ref val) => FromRegValue::from_reg_value(val),
-            Err(err) => Err(err),
+    pub fn delete_value_in<P: AsRef<OsStr>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+    ) -> io::Result<()> {
+        let c_path = to_utf16(path);
+        let c_name = to_utf16(name);
+        match unsafe { Registry::RegDeleteKeyValueW(self.hkey, c_path.as_ptr(), c_name.as_ptr()) } {
+            0 => Ok(()),
+            err => werr!(err),
         }
     }
 
-    /// Get raw bytes from registry value.
-    /// Will get the `Default` value if `name` is an empty string.
+    /// Same as `get_raw_value`, but `name` is passed with its exact length instead of
+    /// being NUL-terminated, so values whose names contain embedded or trailing NULs
+    /// (as returned by `enum_values`) can be read. The data is returned byte for byte.
+    /// Not supported on predefined keys, open a subkey first.
     ///
     /// # Examples
     ///
@@ -740,54 +2912,171 @@ impl RegKey {
     /// # use winreg2::RegKey;
     /// # use winreg2::enums::*;
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
-    /// let data = settings.get_raw_value("data")?;
-    /// println!("Bytes: {:?}", data.bytes);
+    /// let key = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\Hostile")?;
+    /// for (name, _) in key.enum_values().map(|x| x.unwrap()) {
+    ///     let value = key.get_raw_value_exact(&name)?;
+    ///     println!("{:?} = {:?}", name, value.bytes);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get_raw_value<N: AsRef<OsStr>>(&self, name: N) -> io::Result<RegValue> {
-        let c_name = to_utf16(name);
-        let mut buf_len: u32 = 2048;
-        let mut buf_type: u32 = 0;
-        let mut buf: Vec<u8> = Vec::with_capacity(buf_len as usize);
+    pub fn get_raw_value_exact<N: AsRef<OsStr>>(&self, name: N) -> io::Result<RegValue> {
+        let name: Vec<u16> = name.as_ref().encode_wide().collect();
+        let name = unicode_string(&name)?;
+        // KEY_VALUE_PARTIAL_INFORMATION: TitleIndex, Type and DataLength followed by the data
+        let mut buf: Vec<u64> = vec![0; 256];
         loop {
+            let mut len: u32 = 0;
             match unsafe {
-                Registry::RegQueryValueExW(
+                WdkRegistry::NtQueryValueKey(
                     self.hkey,
-                    c_name.as_ptr(),
-                    ptr::null_mut(),
-                    &mut buf_type,
-                    buf.as_mut_ptr(),
-                    &mut buf_len,
+                    &name,
+                    WdkRegistry::KeyValuePartialInformation,
+                    buf.as_mut_ptr() as *mut _,
+                    (buf.len() * 8) as u32,
+                    &mut len,
                 )
             } {
                 0 => {
-                    unsafe {
-                        buf.set_len(buf_len as usize);
-                    }
-                    // minimal check before transmute to RegType
-                    if buf_type > Registry::REG_QWORD {
-                        return werr!(Foundation::ERROR_BAD_FILE_TYPE);
-                    }
-                    let t: RegType = unsafe { transmute(buf_type as u8) };
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(buf.as_ptr() as *const u8, buf.len() * 8)
+                    };
+                    let field = |i: usize| {
+                        u32::from_ne_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+                    };
+                    let (vtype, data_len) = (field(4), field(8) as usize);
+                    let t = RegType::from(vtype);
                     return Ok(RegValue {
-                        bytes: buf,
+                        bytes: bytes[12..12 + data_len].to_vec(),
                         vtype: t,
                     });
                 }
-                Foundation::ERROR_MORE_DATA => {
-                    buf.reserve(buf_len as usize);
+                Foundation::STATUS_BUFFER_TOO_SMALL | Foundation::STATUS_BUFFER_OVERFLOW => {
+                    buf.resize((len as usize + 7) / 8 + 1, 0);
                 }
-                err => return werr!(err),
+                status => return werr!(unsafe { Foundation::RtlNtStatusToDosError(status) }),
             }
         }
     }
 
-    /// Seamlessly convert a value from a rust type and write it to the registry value
-    /// with `ToRegValue` trait implemented (currently `String`, `&str`, `u32` and `u64`).
-    /// Will set the `Default` value if `name` is an empty string.
+    /// Same as `set_raw_value`, but `name` is passed with its exact length,
+    /// so it may contain embedded or trailing NULs.
+    /// Not supported on predefined keys, open a subkey first.
+    pub fn set_raw_value_exact<N: AsRef<OsStr>>(
+        &self,
+        name: N,
+        value: &RegValue,
+    ) -> io::Result<()> {
+        let name: Vec<u16> = name.as_ref().encode_wide().collect();
+        let name = unicode_string(&name)?;
+        match unsafe {
+            WdkRegistry::NtSetValueKey(
+                self.hkey,
+                &name,
+                0,
+                u32::from(value.vtype.clone()),
+                value.bytes.as_ptr() as *const _,
+                value.bytes.len() as u32,
+            )
+        } {
+            0 => Ok(()),
+            status => werr!(unsafe { Foundation::RtlNtStatusToDosError(status) }),
+        }
+    }
+
+    /// Same as `delete_value`, but `name` is passed with its exact length,
+    /// so it may contain embedded or trailing NULs.
+    /// Not supported on predefined keys, open a subkey first.
+    pub fn delete_value_exact<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        let name: Vec<u16> = name.as_ref().encode_wide().collect();
+        let name = unicode_string(&name)?;
+        match unsafe { WdkRegistry::NtDeleteValueKey(self.hkey, &name) } {
+            0 => Ok(()),
+            status => werr!(unsafe { Foundation::RtlNtStatusToDosError(status) }),
+        }
+    }
+
+    /// Read a `REG_MULTI_SZ` value, let `f` modify the entries and write them back.
+    /// A missing value starts as an empty list, empty entries are dropped.
+    /// The read and the write happen in a transaction of their own; when another
+    /// transaction modifies the key at the same time, they are retried with a fresh
+    /// read, so `f` may be called more than once. Writes made without a transaction
+    /// between the read and the write are not detected.
+    /// Gives up with `ERROR_TRANSACTIONAL_CONFLICT` after 100 conflicting attempts.
+    /// Returns the written entries.
+    ///
+    /// Part of `transactions` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    /// let service = hklm.open_subkey_with_flags(
+    ///     "SYSTEM\\CurrentControlSet\\Services\\MyService",
+    ///     KEY_QUERY_VALUE | KEY_SET_VALUE,
+    /// )?;
+    /// service.multi_sz_update("DependOnService", |deps| {
+    ///     if !deps.iter().any(|d| d.eq_ignore_ascii_case("Tcpip")) {
+    ///         deps.push("Tcpip".to_owned());
+    ///     }
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "transactions")]
+    pub fn multi_sz_update<N, F>(&self, name: N, mut f: F) -> io::Result<Vec<String>>
+    where
+        N: AsRef<OsStr>,
+        F: FnMut(&mut Vec<String>),
+    {
+        let name = name.as_ref();
+        self.retry_transacted(|key| {
+            let mut entries = key.read_multi_sz(name)?;
+            f(&mut entries);
+            key.set_value(name, &entries)?;
+            Ok(entries)
+        })
+    }
+
+    /// Same as `multi_sz_update`, but the read and the write are part of the transaction `t`,
+    /// so the update is atomic once the transaction is committed.
+    ///
+    /// Part of `transactions` feature.
+    #[cfg(feature = "transactions")]
+    pub fn multi_sz_update_transacted<N, F>(
+        &self,
+        name: N,
+        t: &Transaction,
+        mut f: F,
+    ) -> io::Result<Vec<String>>
+    where
+        N: AsRef<OsStr>,
+        F: FnMut(&mut Vec<String>),
+    {
+        let key = self.open_subkey_transacted_with_flags(
+            "",
+            t,
+            Registry::KEY_QUERY_VALUE | Registry::KEY_SET_VALUE,
+        )?;
+        let mut entries = key.read_multi_sz(name.as_ref())?;
+        f(&mut entries);
+        key.set_value(name, &entries)?;
+        Ok(entries)
+    }
+
+    /// Set the value `name` to `new` only if its current value (type and data) is `expected`.
+    /// The check and the write happen in a transaction of their own; when another
+    /// transaction modifies the key at the same time, they are retried with a fresh read,
+    /// up to 100 times before failing with `ERROR_TRANSACTIONAL_CONFLICT`.
+    /// Transactions don't lock what they read, so a write made without a transaction
+    /// between the check and the write is not detected and is overwritten.
+    /// Returns `false` without writing anything if the value differs or doesn't exist.
+    ///
+    /// Part of `transactions` feature.
     ///
     /// # Examples
     ///
@@ -795,57 +3084,169 @@ impl RegKey {
     /// # use std::error::Error;
     /// # use winreg2::RegKey;
     /// # use winreg2::enums::*;
+    /// # use winreg2::types::ToRegValue;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    /// let (settings, disp) = hkcu.create_subkey("Software\\MyProduct\\Settings")?;
-    /// settings.set_value("server", &"www.example.com")?;
-    /// settings.set_value("port", &8080u32)?;
+    /// let (app, _disp) = hkcu.create_subkey("Software\\MyProduct")?;
+    /// let version: u32 = app.get_value("ConfigVersion")?;
+    /// let updated = app.compare_and_swap_value(
+    ///     "ConfigVersion",
+    ///     &version.to_reg_value(),
+    ///     &(version + 1).to_reg_value(),
+    /// )?;
+    /// if !updated {
+    ///     println!("someone else updated the configuration");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "transactions")]
+    pub fn compare_and_swap_value<N: AsRef<OsStr>>(
+        &self,
+        name: N,
+        expected: &RegValue,
+        new: &RegValue,
+    ) -> io::Result<bool> {
+        self.retry_transacted(|key| {
+            match key.get_raw_value(&name) {
+                Ok(ref current) if current == expected => {}
+                Ok(_) => return Ok(false),
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+                Err(e) => return Err(e),
+            }
+            key.set_raw_value(&name, new)?;
+            Ok(true)
+        })
+    }
+
+    /// Replace the value `name` with the result of `f`, called with the current value
+    /// or `None` if it doesn't exist, and return the written value.
+    /// The read and the write happen in a transaction of their own; when another
+    /// transaction modifies the value at the same time, they are retried with a fresh
+    /// read, so `f` may be called more than once.
+    /// Gives up with `ERROR_TRANSACTIONAL_CONFLICT` after 100 conflicting attempts.
+    ///
+    /// Part of `transactions` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    /// let (app, _disp) = hkcu.create_subkey("Software\\MyProduct")?;
+    /// let launches = app.update_value("LaunchCount", |n: Option<u32>| n.unwrap_or(0) + 1)?;
+    /// println!("launched {} times", launches);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_value<T: ToRegValue, N: AsRef<OsStr>>(&self, name: N, value: &T) -> io::Result<()> {
-        self.set_raw_value(name, &value.to_reg_value())
+    #[cfg(feature = "transactions")]
+    pub fn update_value<T, N, F>(&self, name: N, mut f: F) -> io::Result<T>
+    where
+        T: FromRegValue + ToRegValue,
+        N: AsRef<OsStr>,
+        F: FnMut(Option<T>) -> T,
+    {
+        self.retry_transacted(|key| {
+            let new = f(key.get_value_opt(&name)?);
+            key.set_value(&name, &new)?;
+            Ok(new)
+        })
     }
 
-    /// Write raw bytes from `RegValue` struct to a registry value.
-    /// Will set the `Default` value if `name` is an empty string.
+    /// Call `f` with this key opened in a new transaction and commit it,
+    /// starting over up to 100 times when the transaction conflicts with another one
+    #[cfg(feature = "transactions")]
+    fn retry_transacted<R, F>(&self, mut f: F) -> io::Result<R>
+    where
+        F: FnMut(&RegKey) -> io::Result<R>,
+    {
+        const ATTEMPTS: u32 = 100;
+        let mut attempt = 1;
+        loop {
+            let result = Transaction::new().and_then(|t| {
+                let key = self.open_subkey_transacted_with_flags(
+                    "",
+                    &t,
+                    Registry::KEY_QUERY_VALUE | Registry::KEY_SET_VALUE,
+                )?;
+                let result = f(&key)?;
+                t.commit()?;
+                Ok(result)
+            });
+            match result {
+                Err(ref e)
+                    if attempt < ATTEMPTS
+                        && e.raw_os_error()
+                            == Some(Foundation::ERROR_TRANSACTIONAL_CONFLICT as i32) =>
+                {
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Rename the value `old_name` to `new_name`, keeping its type and data.
+    /// The read, write and delete happen in a transaction of their own, so either
+    /// the old or the new name exists afterwards, never both or neither.
+    /// Fails with `ERROR_ALREADY_EXISTS` if a value named `new_name` exists,
+    /// unless the names differ only in case, which changes the case of the name.
+    ///
+    /// Part of `transactions` feature.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// # use std::error::Error;
-    /// use winreg2::{RegKey, RegValue};
-    /// use winreg2::enums::*;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
-    /// let bytes: Vec<u8> = vec![1, 2, 3, 5, 8, 13, 21, 34, 55, 89];
-    /// let data = RegValue{ vtype: REG_BINARY, bytes: bytes};
-    /// settings.set_raw_value("data", &data)?;
-    /// println!("Bytes: {:?}", data.bytes);
+    /// let app = hkcu.open_subkey_with_flags("Software\\MyProduct", KEY_READ | KEY_WRITE)?;
+    /// app.rename_value("InstallDir", "InstallPath")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_raw_value<N: AsRef<OsStr>>(&self, name: N, value: &RegValue) -> io::Result<()> {
-        let c_name = to_utf16(name);
-        let t = value.vtype.clone() as u32;
-        match unsafe {
-            Registry::RegSetValueExW(
-                self.hkey,
-                c_name.as_ptr(),
-                0,
-                t,
-                value.bytes.as_ptr(),
-                value.bytes.len() as u32,
-            )
-        } {
-            0 => Ok(()),
-            err => werr!(err),
+    #[cfg(feature = "transactions")]
+    pub fn rename_value<ON: AsRef<OsStr>, NN: AsRef<OsStr>>(
+        &self,
+        old_name: ON,
+        new_name: NN,
+    ) -> io::Result<()> {
+        let t = Transaction::new()?;
+        let key = self.open_subkey_transacted_with_flags(
+            "",
+            &t,
+            Registry::KEY_QUERY_VALUE | Registry::KEY_SET_VALUE,
+        )?;
+        let value = key.get_raw_value(&old_name)?;
+        let same_name = match (old_name.as_ref().to_str(), new_name.as_ref().to_str()) {
+            (Some(old), Some(new)) => util::name_eq(old, new),
+            _ => false,
+        };
+        if same_name {
+            // only the case changes, the value would be found under the new name
+            key.delete_value(&old_name)?;
+            key.set_raw_value(&new_name, &value)?;
+            return t.commit();
         }
+        match key.get_raw_value(&new_name) {
+            Ok(_) => return werr!(Foundation::ERROR_ALREADY_EXISTS),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        key.set_raw_value(&new_name, &value)?;
+        key.delete_value(&old_name)?;
+        t.commit()
     }
 
-    /// Delete specified value from registry.
-    /// Will delete the `Default` value if `name` is an empty string.
+    /// Write a group of values in a transaction of its own, so either all of them
+    /// are written or, if any write fails, none.
+    ///
+    /// Part of `transactions` feature.
     ///
     /// # Examples
     ///
@@ -853,19 +3254,42 @@ impl RegKey {
     /// # use std::error::Error;
     /// # use winreg2::RegKey;
     /// # use winreg2::enums::*;
+    /// # use winreg2::types::ToRegValue;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    /// let settings = hkcu.open_subkey("Software\\MyProduct\\Settings")?;
-    /// settings.delete_value("data")?;
+    /// let proxy = hkcu.open_subkey_with_flags("Software\\MyProduct\\Proxy", KEY_WRITE)?;
+    /// proxy.set_values(vec![
+    ///     ("Host", "proxy.example.com".to_reg_value()),
+    ///     ("Port", 3128u32.to_reg_value()),
+    ///     ("Enabled", 1u32.to_reg_value()),
+    /// ])?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn delete_value<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
-        let c_name = to_utf16(name);
-        match unsafe { Registry::RegDeleteValueW(self.hkey, c_name.as_ptr()) } {
-            0 => Ok(()),
-            err => werr!(err),
+    #[cfg(feature = "transactions")]
+    pub fn set_values<N: AsRef<OsStr>, I: IntoIterator<Item = (N, RegValue)>>(
+        &self,
+        values: I,
+    ) -> io::Result<()> {
+        let t = Transaction::new()?;
+        let key = self.open_subkey_transacted_with_flags("", &t, Registry::KEY_SET_VALUE)?;
+        for (name, value) in values {
+            key.set_raw_value(name, &value)?;
         }
+        t.commit()
+    }
+
+    /// Read a `REG_MULTI_SZ` value as a list, empty if the value doesn't exist
+    #[cfg(feature = "transactions")]
+    fn read_multi_sz(&self, name: &OsStr) -> io::Result<Vec<String>> {
+        let value = match self.get_raw_value(name) {
+            Ok(value) => value,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut entries: Vec<String> = FromRegValue::from_reg_value(&value)?;
+        entries.retain(|e| !e.is_empty());
+        Ok(entries)
     }
 
     /// Save `Encodable` type to a registry key.
@@ -1021,6 +3445,12 @@ impl RegKey {
     }
 
     pub(crate) fn enum_key(&self, index: u32) -> Option<io::Result<String>> {
+        self.enum_key_os(index)
+            .map(|name| name.and_then(key_name_to_string))
+    }
+
+    /// Same as `enum_key`, keeping names that are not valid UTF-16 as they are
+    fn enum_key_os(&self, index: u32) -> Option<io::Result<OsString>> {
         let mut name_len = 2048;
         #[allow(clippy::unnecessary_cast)]
         let mut name = [0 as u16; 2048];
@@ -1035,9 +3465,33 @@ impl RegKey {
                 ptr::null_mut(), // lpcClass: LPDWORD,
                 ptr::null_mut(), // lpftLastWriteTime: PFILETIME,
             )
+        } {
+            0 => Some(Ok(OsString::from_wide(&name[..name_len as usize]))),
+            Foundation::ERROR_NO_MORE_ITEMS => None,
+            err => Some(werr!(err)),
+        }
+    }
+
+    /// Same as `enum_key`, also returning the last write time of the subkey
+    fn enum_key_with_time(&self, index: u32) -> Option<io::Result<(String, FileTime)>> {
+        let mut name_len = 2048;
+        #[allow(clippy::unnecessary_cast)]
+        let mut name = [0 as u16; 2048];
+        let mut time = FileTime::default();
+        match unsafe {
+            Registry::RegEnumKeyExW(
+                self.hkey,
+                index,
+                name.as_mut_ptr(),
+                &mut name_len,
+                ptr::null_mut(), // reserved
+                ptr::null_mut(), // lpClass: LPWSTR,
+                ptr::null_mut(), // lpcClass: LPDWORD,
+                &mut time.0,
+            )
         } {
             0 => match String::from_utf16(&name[..name_len as usize]) {
-                Ok(s) => Some(Ok(s)),
+                Ok(s) => Some(Ok((s, time))),
                 Err(_) => Some(werr!(Foundation::ERROR_INVALID_BLOCK)),
             },
             Foundation::ERROR_NO_MORE_ITEMS => None,
@@ -1045,15 +3499,75 @@ impl RegKey {
         }
     }
 
-    pub(crate) fn enum_value(&self, index: u32) -> Option<io::Result<(String, RegValue)>> {
-        let mut name_len = 2048;
+    /// Same as `enum_key_with_time`, also returning the class of the subkey read into
+    /// `class`, which is grown when too small and can be reused for the next index
+    fn enum_key_with_class(
+        &self,
+        index: u32,
+        class: &mut Vec<u16>,
+    ) -> Option<io::Result<(String, FileTime, Option<String>)>> {
         #[allow(clippy::unnecessary_cast)]
         let mut name = [0 as u16; 2048];
+        let mut time = FileTime::default();
+        loop {
+            let mut name_len = name.len() as u32;
+            let mut class_len = class.len() as u32;
+            match unsafe {
+                Registry::RegEnumKeyExW(
+                    self.hkey,
+                    index,
+                    name.as_mut_ptr(),
+                    &mut name_len,
+                    ptr::null_mut(), // reserved
+                    class.as_mut_ptr(),
+                    &mut class_len,
+                    &mut time.0,
+                )
+            } {
+                0 => {
+                    let name = match String::from_utf16(&name[..name_len as usize]) {
+                        Ok(s) => s,
+                        Err(_) => return Some(werr!(Foundation::ERROR_INVALID_BLOCK)),
+                    };
+                    let class = if class_len == 0 {
+                        None
+                    } else {
+                        match String::from_utf16(&class[..class_len as usize]) {
+                            Ok(s) => Some(s),
+                            Err(_) => return Some(werr!(Foundation::ERROR_INVALID_DATA)),
+                        }
+                    };
+                    return Some(Ok((name, time, class)));
+                }
+                // key names always fit, so it's the class
+                Foundation::ERROR_MORE_DATA => {
+                    let len = class.len() * 2 + 256;
+                    class.resize(len, 0);
+                }
+                Foundation::ERROR_NO_MORE_ITEMS => return None,
+                err => return Some(werr!(err)),
+            }
+        }
+    }
 
-        let mut buf_len: u32 = 2048;
-        let mut buf_type: u32 = 0;
-        let mut buf: Vec<u8> = Vec::with_capacity(buf_len as usize);
+    #[cfg(feature = "serialization-serde")]
+    pub(crate) fn enum_value(&self, index: u32) -> Option<io::Result<(String, RegValue)>> {
+        self.enum_value_with(index, &mut vec![0; 2048], &mut vec![0; 2048])
+            .map(|value| value.and_then(value_name_to_string))
+    }
+
+    /// Same as `enum_value` without converting the name, with `name` and `buf` as scratch buffers that are grown
+    /// when too small and can be reused for the next index
+    fn enum_value_with(
+        &self,
+        index: u32,
+        name: &mut Vec<u16>,
+        buf: &mut Vec<u8>,
+    ) -> Option<io::Result<(OsString, RegValue)>> {
         loop {
+            let mut name_len = name.len() as u32;
+            let mut buf_len = buf.len() as u32;
+            let mut buf_type: u32 = 0;
             match unsafe {
                 Registry::RegEnumValueW(
                     self.hkey,
@@ -1067,27 +3581,25 @@ impl RegKey {
                 )
             } {
                 0 => {
-                    let name = match String::from_utf16(&name[..name_len as usize]) {
-                        Ok(s) => s,
-                        Err(_) => return Some(werr!(Foundation::ERROR_INVALID_DATA)),
-                    };
-                    unsafe {
-                        buf.set_len(buf_len as usize);
-                    }
-                    // minimal check before transmute to RegType
-                    if buf_type > Registry::REG_QWORD {
-                        return Some(werr!(Foundation::ERROR_BAD_FILE_TYPE));
-                    }
-                    let t: RegType = unsafe { transmute(buf_type as u8) };
+                    let name = OsString::from_wide(&name[..name_len as usize]);
+                    let t = RegType::from(buf_type);
                     let value = RegValue {
-                        bytes: buf,
+                        bytes: buf[..buf_len as usize].to_vec(),
                         vtype: t,
                     };
                     return Some(Ok((name, value)));
                 }
                 Foundation::ERROR_MORE_DATA => {
-                    name_len += 1; //for NULL char
-                    buf.reserve(buf_len as usize);
+                    // only the data length is reported, a name always fits the maximum
+                    let max_name = MAX_VALUE_NAME_LEN + 1;
+                    if buf_len as usize > buf.len() {
+                        buf.resize(buf_len as usize, 0);
+                    } else if name.len() < max_name {
+                        name.resize(max_name, 0);
+                    } else {
+                        let len = buf.len() * 2 + 2;
+                        buf.resize(len, 0);
+                    }
                 }
                 Foundation::ERROR_NO_MORE_ITEMS => return None,
                 err => return Some(werr!(err)),
@@ -1096,23 +3608,178 @@ impl RegKey {
     }
 }
 
+impl fmt::Debug for RegKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.full_path() {
+            Ok(path) => write!(f, "RegKey({})", path),
+            Err(_) => write!(f, "RegKey({:p})", self.hkey),
+        }
+    }
+}
+
+impl fmt::Display for RegKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.full_path() {
+            Ok(path) => write!(f, "{}", path),
+            Err(_) => write!(f, "{:p}", self.hkey),
+        }
+    }
+}
+
+fn key_name_to_string(name: OsString) -> io::Result<String> {
+    match name.into_string() {
+        Ok(name) => Ok(name),
+        Err(_) => werr!(Foundation::ERROR_INVALID_BLOCK),
+    }
+}
+
+fn value_name_to_string((name, value): (OsString, RegValue)) -> io::Result<(String, RegValue)> {
+    match name.into_string() {
+        Ok(name) => Ok((name, value)),
+        Err(_) => werr!(Foundation::ERROR_INVALID_DATA),
+    }
+}
+
+/// Counted string referring to `name`, which must outlive it
+fn unicode_string(name: &[u16]) -> io::Result<Foundation::UNICODE_STRING> {
+    let len = name.len() * 2;
+    if len > u16::MAX as usize {
+        return werr!(Foundation::ERROR_INVALID_PARAMETER);
+    }
+    Ok(Foundation::UNICODE_STRING {
+        Length: len as u16,
+        MaximumLength: len as u16,
+        Buffer: name.as_ptr() as *mut u16,
+    })
+}
+
+/// Convert a kernel-mode key name to the regedit form
+pub(crate) fn win32_path(nt_name: &str) -> String {
+    const MACHINE: &str = "\\REGISTRY\\MACHINE";
+    const USER: &str = "\\REGISTRY\\USER";
+    let strip = |prefix: &str| -> Option<String> {
+        let len = prefix.len();
+        if nt_name.len() >= len
+            && nt_name.is_char_boundary(len)
+            && nt_name[..len].eq_ignore_ascii_case(prefix)
+            && (nt_name.len() == len || nt_name[len..].starts_with('\\'))
+        {
+            Some(nt_name[len..].to_owned())
+        } else {
+            None
+        }
+    };
+    // HKCU is \REGISTRY\USER\<SID> of the current user
+    let current_user = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags("", KEY_QUERY_VALUE)
+        .and_then(|hkcu| hkcu.nt_name());
+    if let Some(rest) = current_user.ok().and_then(|hkcu| strip(&hkcu)) {
+        return format!("HKEY_CURRENT_USER{}", rest);
+    }
+    if let Some(rest) = strip(MACHINE) {
+        return format!("HKEY_LOCAL_MACHINE{}", rest);
+    }
+    if let Some(rest) = strip(USER) {
+        return format!("HKEY_USERS{}", rest);
+    }
+    nt_name.to_owned()
+}
+
 impl Drop for RegKey {
     fn drop(&mut self) {
         self.close_().unwrap_or(());
     }
 }
 
-/// Iterator over subkeys names
+/// A hive mounted with `RegKey::load_key`, unloaded on drop
+pub struct LoadedHive<'root> {
+    root: &'root RegKey,
+    /// Null-terminated name of the mount point
+    name: Vec<u16>,
+    /// `None` once unloaded
+    key: Option<RegKey>,
+}
+
+impl LoadedHive<'_> {
+    /// Root key of the mounted hive, opened with `KEY_ALL_ACCESS`
+    pub fn key(&self) -> &RegKey {
+        self.key.as_ref().unwrap()
+    }
+
+    /// Unload the hive, reporting failures that dropping the guard ignores.
+    /// Fails with `ERROR_ACCESS_DENIED` while other handles to its keys are open.
+    pub fn unload(mut self) -> io::Result<()> {
+        self.unload_()
+    }
+
+    fn unload_(&mut self) -> io::Result<()> {
+        if self.key.take().is_none() {
+            return Ok(());
+        }
+        let _backup = enable_privilege(Security::SE_BACKUP_NAME)?;
+        let _restore = enable_privilege(Security::SE_RESTORE_NAME)?;
+        match unsafe { Registry::RegUnLoadKeyW(self.root.hkey, self.name.as_ptr()) } {
+            0 => Ok(()),
+            err => werr!(err),
+        }
+    }
+}
+
+impl Drop for LoadedHive<'_> {
+    fn drop(&mut self) {
+        self.unload_().unwrap_or(());
+    }
+}
+
+/// Check whether the key at `path` is `ancestor` or one of its subkeys
+#[cfg(feature = "transactions")]
+fn is_within(path: &str, ancestor: &str) -> bool {
+    let mut components = path.split('\\').filter(|c| !c.is_empty());
+    ancestor
+        .split('\\')
+        .filter(|c| !c.is_empty())
+        .all(|a| components.next().map_or(false, |c| util::name_eq(c, a)))
+}
+
+/// Number of items left after `index`, with the total queried once with `query`.
+/// Exact unless items are added or deleted during the enumeration,
+/// unknown if the key can't be queried, e.g. without `KEY_QUERY_VALUE`.
+/// There is no upper bound, as items added meanwhile are enumerated too.
+fn remaining<F>(count: &Cell<Option<u32>>, index: u32, query: F) -> (usize, Option<usize>)
+where
+    F: FnOnce() -> io::Result<u32>,
+{
+    let total = match count.get() {
+        Some(total) => total,
+        None => match query() {
+            Ok(total) => {
+                count.set(Some(total));
+                total
+            }
+            Err(_) => return (0, None),
+        },
+    };
+    let remaining = total.saturating_sub(index) as usize;
+    (remaining, None)
+}
+
+/// Move `index` past `n` items, to the end if it would overflow
+fn skip_items(index: &mut u32, n: usize) {
+    *index = u32::try_from(n).map_or(u32::MAX, |n| index.saturating_add(n));
+}
+
+/// Iterator over subkeys names.
+/// Its `size_hint` is based on the number of subkeys at its first call.
 pub struct EnumKeys<'key> {
     key: &'key RegKey,
     index: u32,
+    /// Number of subkeys, queried when first needed
+    count: Cell<Option<u32>>,
 }
 
-impl Iterator for EnumKeys<'_> {
-    type Item = io::Result<String>;
-
-    fn next(&mut self) -> Option<io::Result<String>> {
-        match self.key.enum_key(self.index) {
+impl EnumKeys<'_> {
+    fn next_os(&mut self) -> Option<io::Result<OsString>> {
+        match self.key.enum_key_os(self.index) {
             v @ Some(_) => {
                 self.index += 1;
                 v
@@ -1120,24 +3787,166 @@ impl Iterator for EnumKeys<'_> {
             e @ None => e,
         }
     }
+}
+
+impl Iterator for EnumKeys<'_> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        self.next_os().map(|name| name.and_then(key_name_to_string))
+    }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        self.index += n as u32;
+        skip_items(&mut self.index, n);
         self.next()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        remaining(&self.count, self.index, || {
+            self.key.query_info().map(|info| info.sub_keys)
+        })
+    }
 }
 
-/// Iterator over values
-pub struct EnumValues<'key> {
+/// Iterator over subkeys names as `OsString`, returned by `enum_keys_os`
+pub struct EnumKeysOs<'key>(EnumKeys<'key>);
+
+impl Iterator for EnumKeysOs<'_> {
+    type Item = io::Result<OsString>;
+
+    fn next(&mut self) -> Option<io::Result<OsString>> {
+        self.0.next_os()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        skip_items(&mut self.0.index, n);
+        self.0.next_os()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Iterator over subkeys names, last write times and classes
+pub struct EnumKeysWithInfo<'key> {
     key: &'key RegKey,
     index: u32,
+    /// Buffer reused for every class, sized for the longest one
+    class: Vec<u16>,
 }
 
-impl Iterator for EnumValues<'_> {
-    type Item = io::Result<(String, RegValue)>;
+impl Iterator for EnumKeysWithInfo<'_> {
+    type Item = io::Result<(String, SystemTime, Option<String>)>;
 
-    fn next(&mut self) -> Option<io::Result<(String, RegValue)>> {
-        match self.key.enum_value(self.index) {
+    fn next(&mut self) -> Option<io::Result<(String, SystemTime, Option<String>)>> {
+        if self.class.is_empty() {
+            let max_class_len = self.key.query_info().map_or(255, |i| i.max_class_len);
+            // room for the terminating NULL
+            self.class.resize(max_class_len as usize + 1, 0);
+        }
+        let item = self.key.enum_key_with_class(self.index, &mut self.class)?;
+        self.index += 1;
+        Some(item.map(|(name, time, class)| (name, time.to_system_time(), class)))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        skip_items(&mut self.index, n);
+        self.next()
+    }
+}
+
+/// Volatile and symbolic link flags of a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyFlags {
+    /// Created with `REG_OPTION_VOLATILE`
+    pub volatile: bool,
+    /// Created with `REG_OPTION_CREATE_LINK`
+    pub link: bool,
+}
+
+/// Location of a key in the registry namespace, returned by `physical_path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalPath {
+    /// Kernel-mode path, e.g. `\REGISTRY\MACHINE\SOFTWARE\WOW6432Node\MyProduct`
+    pub path: String,
+    /// The key was redirected to the 32-bit view (`WOW6432Node`)
+    pub wow64_redirected: bool,
+    /// The key lives in the per-user `VirtualStore` instead of `HKLM`
+    pub virtualized: bool,
+}
+
+/// Subkey name with optional flags, returned by `enum_key_infos`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyInfo {
+    pub name: String,
+    pub flags: Option<KeyFlags>,
+}
+
+/// Iterator over subkeys names and flags
+pub struct EnumKeyInfos<'key> {
+    key: &'key RegKey,
+    index: u32,
+    with_flags: bool,
+}
+
+impl Iterator for EnumKeyInfos<'_> {
+    type Item = io::Result<KeyInfo>;
+
+    fn next(&mut self) -> Option<io::Result<KeyInfo>> {
+        let name = match self.key.enum_key(self.index)? {
+            Ok(name) => name,
+            Err(e) => return Some(Err(e)),
+        };
+        self.index += 1;
+        if !self.with_flags {
+            return Some(Ok(KeyInfo { name, flags: None }));
+        }
+        Some(
+            self.key
+                .open_subkey_nofollow(&name, enums::KEY_QUERY_VALUE)
+                .and_then(|k| k.key_flags())
+                .map(|flags| KeyInfo {
+                    name,
+                    flags: Some(flags),
+                }),
+        )
+    }
+}
+
+/// Iterator over values.
+/// Its `size_hint` is based on the number of values at its first call.
+pub struct EnumValues<'key> {
+    key: &'key RegKey,
+    index: u32,
+    /// Number of values, queried when first needed
+    count: Cell<Option<u32>>,
+    /// Buffers reused for every value, sized for the longest name and data
+    name: Vec<u16>,
+    buf: Vec<u8>,
+}
+
+impl EnumValues<'_> {
+    fn next_os(&mut self) -> Option<io::Result<(OsString, RegValue)>> {
+        if self.name.is_empty() {
+            let (name_len, buf_len) = match self.key.query_info() {
+                Ok(info) => {
+                    self.count.set(Some(info.values));
+                    (
+                        info.max_value_name_len as usize,
+                        info.max_value_len as usize,
+                    )
+                }
+                Err(_) => (2047, 2048),
+            };
+            // room for the terminating NULL
+            self.name.resize(name_len + 1, 0);
+            self.buf.resize(buf_len, 0);
+        }
+        match self
+            .key
+            .enum_value_with(self.index, &mut self.name, &mut self.buf)
+        {
             v @ Some(_) => {
                 self.index += 1;
                 v
@@ -1145,9 +3954,44 @@ impl Iterator for EnumValues<'_> {
             e @ None => e,
         }
     }
+}
+
+impl Iterator for EnumValues<'_> {
+    type Item = io::Result<(String, RegValue)>;
+
+    fn next(&mut self) -> Option<io::Result<(String, RegValue)>> {
+        self.next_os()
+            .map(|value| value.and_then(value_name_to_string))
+    }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        self.index += n as u32;
+        skip_items(&mut self.index, n);
         self.next()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        remaining(&self.count, self.index, || {
+            self.key.query_info().map(|info| info.values)
+        })
+    }
+}
+
+/// Iterator over values with `OsString` names, returned by `enum_values_os`
+pub struct EnumValuesOs<'key>(EnumValues<'key>);
+
+impl Iterator for EnumValuesOs<'_> {
+    type Item = io::Result<(OsString, RegValue)>;
+
+    fn next(&mut self) -> Option<io::Result<(OsString, RegValue)>> {
+        self.0.next_os()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        skip_items(&mut self.0.index, n);
+        self.0.next_os()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
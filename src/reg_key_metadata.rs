@@ -5,6 +5,7 @@
 // except according to those terms.
 use std::fmt;
 use std::ops::Deref;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use windows_sys::Win32::Foundation::FILETIME;
 use windows_sys::Win32::Foundation::SYSTEMTIME;
 use windows_sys::Win32::System::Time::FileTimeToSystemTime;
@@ -29,6 +30,20 @@ impl fmt::Debug for FileTime {
     }
 }
 
+impl FileTime {
+    /// Convert to `SystemTime`
+    pub fn to_system_time(&self) -> SystemTime {
+        // 100ns intervals between 1601-01-01 and 1970-01-01
+        const UNIX_EPOCH_INTERVALS: u64 = 116_444_736_000_000_000;
+        let intervals = (u64::from(self.0.dwHighDateTime) << 32) | u64::from(self.0.dwLowDateTime);
+        if intervals >= UNIX_EPOCH_INTERVALS {
+            UNIX_EPOCH + Duration::from_nanos((intervals - UNIX_EPOCH_INTERVALS) * 100)
+        } else {
+            UNIX_EPOCH - Duration::from_nanos((UNIX_EPOCH_INTERVALS - intervals) * 100)
+        }
+    }
+}
+
 impl Deref for FileTime {
     type Target = FILETIME;
 
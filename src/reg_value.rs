@@ -8,6 +8,10 @@ use crate::types::FromRegValue;
 use std::fmt;
 
 /// Raw registry value
+///
+/// With the `serialization-serde` feature it is serialized as a struct with the
+/// fields `vtype` and `bytes`, where human-readable formats like JSON get the bytes
+/// as a lowercase hex string and also accept an array of bytes.
 #[derive(PartialEq)]
 pub struct RegValue {
     pub bytes: Vec<u8>,
@@ -27,7 +31,7 @@ impl fmt::Display for RegValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let f_val = match self.vtype {
             REG_SZ | REG_EXPAND_SZ | REG_MULTI_SZ => format_reg_value!(self => String),
-            REG_DWORD => format_reg_value!(self => u32),
+            REG_DWORD | REG_DWORD_BIG_ENDIAN => format_reg_value!(self => u32),
             REG_QWORD => format_reg_value!(self => u64),
             _ => format!("{:?}", self.bytes), //TODO: implement more types
         };
@@ -40,3 +44,169 @@ impl fmt::Debug for RegValue {
         write!(f, "RegValue({:?}: {})", self.vtype, self)
     }
 }
+
+#[cfg(feature = "serialization-serde")]
+mod serialization_serde {
+    use super::RegValue;
+    use crate::enums::RegType;
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+    use std::fmt;
+
+    const FIELDS: &[&str] = &["vtype", "bytes"];
+
+    /// Data of a value, hex in human-readable formats
+    struct Bytes<'a>(&'a [u8]);
+
+    impl Serialize for Bytes<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&crate::decoder::to_hex(self.0))
+            } else {
+                serializer.serialize_bytes(self.0)
+            }
+        }
+    }
+
+    impl Serialize for RegValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("RegValue", 2)?;
+            s.serialize_field("vtype", &self.vtype)?;
+            s.serialize_field("bytes", &Bytes(&self.bytes))?;
+            s.end()
+        }
+    }
+
+    struct ByteBuf(Vec<u8>);
+
+    struct ByteBufVisitor;
+
+    impl<'de> Visitor<'de> for ByteBufVisitor {
+        type Value = ByteBuf;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("bytes or a hex string")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<ByteBuf, E> {
+            from_hex(v)
+                .map(ByteBuf)
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+            Ok(ByteBuf(v.to_vec()))
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+            Ok(ByteBuf(v))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(b) = seq.next_element()? {
+                bytes.push(b);
+            }
+            Ok(ByteBuf(bytes))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ByteBuf {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ByteBuf, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_any(ByteBufVisitor)
+            } else {
+                deserializer.deserialize_byte_buf(ByteBufVisitor)
+            }
+        }
+    }
+
+    fn from_hex(s: &str) -> Option<Vec<u8>> {
+        if s.len() % 2 != 0 || !s.is_ascii() {
+            return None;
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+            .collect()
+    }
+
+    enum Field {
+        VType,
+        Bytes,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Field, D::Error> {
+            struct FieldVisitor;
+
+            impl<'de> Visitor<'de> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("`vtype` or `bytes`")
+                }
+
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+                    match v {
+                        "vtype" => Ok(Field::VType),
+                        "bytes" => Ok(Field::Bytes),
+                        _ => Err(E::unknown_field(v, FIELDS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct RegValueVisitor;
+
+    impl<'de> Visitor<'de> for RegValueVisitor {
+        type Value = RegValue;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("struct RegValue")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RegValue, A::Error> {
+            let vtype: RegType = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let bytes: ByteBuf = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            Ok(RegValue {
+                bytes: bytes.0,
+                vtype,
+            })
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RegValue, A::Error> {
+            let mut vtype = None;
+            let mut bytes = None;
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::VType if vtype.is_some() => {
+                        return Err(de::Error::duplicate_field("vtype"))
+                    }
+                    Field::VType => vtype = Some(map.next_value()?),
+                    Field::Bytes if bytes.is_some() => {
+                        return Err(de::Error::duplicate_field("bytes"))
+                    }
+                    Field::Bytes => bytes = Some(map.next_value::<ByteBuf>()?.0),
+                }
+            }
+            Ok(RegValue {
+                bytes: bytes.ok_or_else(|| de::Error::missing_field("bytes"))?,
+                vtype: vtype.ok_or_else(|| de::Error::missing_field("vtype"))?,
+            })
+        }
+    }
+
+    impl<'de> Deserialize<'de> for RegValue {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RegValue, D::Error> {
+            deserializer.deserialize_struct("RegValue", FIELDS, RegValueVisitor)
+        }
+    }
+}
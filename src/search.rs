@@ -0,0 +1,441 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Find and replace over a key tree.
+//!
+//! `RegKey::find_keys` and `RegKey::find_values` lazily yield the keys and values
+//! whose paths match a `path::Pattern`.
+//!
+//! Only string data (`REG_SZ`, `REG_EXPAND_SZ` and `REG_MULTI_SZ`) is rewritten,
+//! value and key names are only renamed when enabled in the options.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!use winreg2::search::{self, ReplaceOptions};
+//!
+//!fn main() -> io::Result<()> {
+//!    let app = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\MyApp")?;
+//!    let options = ReplaceOptions::new("C:\\OldDir", "D:\\NewDir")
+//!        .ignore_case(true)
+//!        .dry_run(true);
+//!    for change in search::replace(&app, &options)? {
+//!        println!("{:?}", change);
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::common::*;
+use crate::enums::*;
+use crate::path::Pattern;
+#[cfg(feature = "transactions")]
+use crate::transaction::Transaction;
+use crate::types::{FromRegValue, ToRegValue};
+use crate::util;
+use crate::walker::{ErrorPolicy, Walk, WalkEntry, WalkOptions};
+use crate::{RegKey, RegValue};
+use std::io;
+use std::iter;
+use std::vec;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::System::Registry::REG_SAM_FLAGS;
+
+/// Options of `replace`, created with `ReplaceOptions::new(find, replace)`
+#[derive(Debug, Clone)]
+pub struct ReplaceOptions {
+    find: String,
+    replace: String,
+    ignore_case: bool,
+    value_names: bool,
+    key_names: bool,
+    dry_run: bool,
+}
+
+impl ReplaceOptions {
+    /// Replace every occurrence of `find` with `replace`
+    pub fn new(find: &str, replace: &str) -> ReplaceOptions {
+        ReplaceOptions {
+            find: find.to_owned(),
+            replace: replace.to_owned(),
+            ignore_case: false,
+            value_names: false,
+            key_names: false,
+            dry_run: false,
+        }
+    }
+
+    /// Match `find` case-insensitively
+    pub fn ignore_case(mut self, ignore: bool) -> Self {
+        self.ignore_case = ignore;
+        self
+    }
+
+    /// Also rename values whose names contain `find`. `replace` fails with
+    /// `ERROR_ALREADY_EXISTS` instead of overwriting another value with the new name.
+    pub fn value_names(mut self, rename: bool) -> Self {
+        self.value_names = rename;
+        self
+    }
+
+    /// Also rename subkeys whose names contain `find`
+    pub fn key_names(mut self, rename: bool) -> Self {
+        self.key_names = rename;
+        self
+    }
+
+    /// Only report the changes without writing anything
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Replace all matches in `s`, `None` if there are none
+    fn apply(&self, s: &str) -> Option<String> {
+        if self.find.is_empty() {
+            return None;
+        }
+        let mut result = String::with_capacity(s.len());
+        let mut rest = s;
+        let mut found = false;
+        while !rest.is_empty() {
+            match self.match_len(rest) {
+                Some(len) => {
+                    result.push_str(&self.replace);
+                    rest = &rest[len..];
+                    found = true;
+                }
+                None => {
+                    let c = rest.chars().next().unwrap();
+                    result.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        if found {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Length in bytes of the match at the start of `s`
+    fn match_len(&self, s: &str) -> Option<usize> {
+        if !self.ignore_case {
+            return if s.starts_with(&self.find) {
+                Some(self.find.len())
+            } else {
+                None
+            };
+        }
+        let mut len = 0;
+        let mut chars = s.chars();
+        for f in self.find.chars() {
+            let c = chars.next()?;
+            if !c.to_lowercase().eq(f.to_lowercase()) {
+                return None;
+            }
+            len += c.len_utf8();
+        }
+        Some(len)
+    }
+
+    /// Rewritten data of a string value, `None` for other types or if nothing matches
+    fn apply_value(&self, value: &RegValue) -> Option<RegValue> {
+        match value.vtype {
+            REG_SZ | REG_EXPAND_SZ => {
+                let s = String::from_reg_value(value).ok()?;
+                Some(RegValue {
+                    bytes: v16_to_v8(&to_utf16(self.apply(&s)?)),
+                    vtype: value.vtype.clone(),
+                })
+            }
+            REG_MULTI_SZ => {
+                let strings: Vec<String> = FromRegValue::from_reg_value(value).ok()?;
+                let mut found = false;
+                let strings: Vec<String> = strings
+                    .into_iter()
+                    .map(|s| match self.apply(&s) {
+                        Some(new) => {
+                            found = true;
+                            new
+                        }
+                        None => s,
+                    })
+                    .collect();
+                if found {
+                    Some(strings.to_reg_value())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A change made (or, in a dry run, to be made) by `replace`.
+/// Key paths are relative to the starting key, which has an empty path.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// Data of the value `name` in `key`
+    Data {
+        key: String,
+        name: String,
+        old: RegValue,
+        new: RegValue,
+    },
+    /// Value `old` in `key` renamed to `new`
+    ValueName {
+        key: String,
+        old: String,
+        new: String,
+    },
+    /// Key at `key` renamed to `new`
+    KeyName { key: String, new: String },
+}
+
+/// Rewrite matches of `options` in the values of `root` and all its subkeys.
+/// Returns the changes in the order they were made. Writes made before an error
+/// are not undone, use `replace_transacted` to make them atomic.
+pub fn replace(root: &RegKey, options: &ReplaceOptions) -> io::Result<Vec<Change>> {
+    replace_with(root, options, |path, perms| {
+        root.open_subkey_with_flags(path, perms)
+    })
+}
+
+/// Same as `replace`, but all the writes are part of the transaction `t`,
+/// so they can be committed or rolled back together.
+///
+/// Part of `transactions` feature.
+#[cfg(feature = "transactions")]
+pub fn replace_transacted(
+    root: &RegKey,
+    options: &ReplaceOptions,
+    t: &Transaction,
+) -> io::Result<Vec<Change>> {
+    replace_with(root, options, |path, perms| {
+        root.open_subkey_transacted_with_flags(path, t, perms)
+    })
+}
+
+fn replace_with<F>(root: &RegKey, options: &ReplaceOptions, open: F) -> io::Result<Vec<Change>>
+where
+    F: Fn(&str, REG_SAM_FLAGS) -> io::Result<RegKey>,
+{
+    // collect the tree first, renamed keys would break the walk
+    let mut paths = vec![String::new()];
+    for entry in root.walk() {
+        paths.push(entry?.path);
+    }
+    let perms = if options.dry_run {
+        KEY_READ
+    } else {
+        KEY_READ | KEY_SET_VALUE
+    };
+    let mut changes = Vec::new();
+    for path in &paths {
+        let key = open(path, perms)?;
+        let values = key.enum_values().collect::<io::Result<Vec<_>>>()?;
+        for (name, value) in values {
+            let new_value = options.apply_value(&value);
+            let new_name = if options.value_names {
+                options.apply(&name).filter(|new| *new != name)
+            } else {
+                None
+            };
+            // names are case-insensitive, a case-only rename writes the same value
+            let case_only = new_name
+                .as_ref()
+                .map_or(false, |new| util::name_eq(&name, new));
+            if let (Some(new_name), false) = (&new_name, case_only) {
+                match key.get_raw_value(new_name) {
+                    Ok(_) => return werr!(Foundation::ERROR_ALREADY_EXISTS),
+                    Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            if !options.dry_run {
+                match (&new_name, &new_value) {
+                    (Some(new_name), new_value) => {
+                        let new_value = new_value.as_ref().unwrap_or(&value);
+                        if case_only {
+                            key.delete_value(&name)?;
+                            key.set_raw_value(new_name, new_value)?;
+                        } else {
+                            key.set_raw_value(new_name, new_value)?;
+                            key.delete_value(&name)?;
+                        }
+                    }
+                    (None, Some(new_value)) => key.set_raw_value(&name, new_value)?,
+                    (None, None) => {}
+                }
+            }
+            if let Some(new) = new_value {
+                changes.push(Change::Data {
+                    key: path.clone(),
+                    name: name.clone(),
+                    old: value,
+                    new,
+                });
+            }
+            if let Some(new) = new_name {
+                changes.push(Change::ValueName {
+                    key: path.clone(),
+                    old: name,
+                    new,
+                });
+            }
+        }
+    }
+    if options.key_names {
+        // deepest keys first, so the paths of the remaining ones stay valid
+        for path in paths.iter().skip(1).rev() {
+            let (parent, name) = match path.rfind('\\') {
+                Some(pos) => (&path[..pos], &path[pos + 1..]),
+                None => ("", path.as_str()),
+            };
+            let new = match options.apply(name) {
+                Some(new) => new,
+                None => continue,
+            };
+            if !options.dry_run {
+                open(parent, KEY_READ | KEY_WRITE)?.rename_subkey(name, &new)?;
+            }
+            changes.push(Change::KeyName {
+                key: path.clone(),
+                new,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+/// Walk of the keys a search may find something in, unreadable keys are skipped
+fn search_walk(root: &RegKey, depth: Option<usize>) -> Walk<'_> {
+    let mut options = WalkOptions::new().error_policy(ErrorPolicy::Skip);
+    if let Some(depth) = depth {
+        options = options.max_depth(depth.max(1));
+    }
+    root.walk_with_options(options)
+}
+
+fn components(path: &str) -> impl Iterator<Item = &str> {
+    path.split('\\').filter(|c| !c.is_empty())
+}
+
+/// Iterator over the subkeys matching a pattern, returned by `RegKey::find_keys`
+pub struct FindKeys<'key> {
+    walk: Walk<'key>,
+    pattern: Pattern,
+}
+
+impl<'key> FindKeys<'key> {
+    pub(crate) fn new(root: &'key RegKey, pattern: Pattern) -> FindKeys<'key> {
+        FindKeys {
+            walk: search_walk(root, pattern.depth()),
+            pattern,
+        }
+    }
+
+    /// Paths of the keys that couldn't be read and were left out
+    pub fn skipped(&self) -> &[String] {
+        self.walk.skipped()
+    }
+}
+
+impl Iterator for FindKeys<'_> {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkEntry>> {
+        loop {
+            match self.walk.next()? {
+                Ok(entry) if !self.pattern.matches_names(components(&entry.path)) => {}
+                result => return Some(result),
+            }
+        }
+    }
+}
+
+/// A value found by `RegKey::find_values`
+#[derive(Debug, PartialEq)]
+pub struct ValueMatch {
+    /// Path of the key relative to the key the search started from
+    pub key: String,
+    pub name: String,
+    pub value: RegValue,
+}
+
+/// Iterator over the values matching a pattern, returned by `RegKey::find_values`
+pub struct FindValues<'key> {
+    root: &'key RegKey,
+    walk: Walk<'key>,
+    pattern: Pattern,
+    /// `false` until the values of the starting key are read
+    started: bool,
+    /// Key whose values are being yielded
+    path: String,
+    values: vec::IntoIter<(String, RegValue)>,
+}
+
+impl<'key> FindValues<'key> {
+    pub(crate) fn new(root: &'key RegKey, pattern: Pattern) -> FindValues<'key> {
+        // values are one level below the keys holding them
+        let depth = pattern.depth().map(|d| d.saturating_sub(1));
+        FindValues {
+            root,
+            walk: search_walk(root, depth),
+            pattern,
+            started: false,
+            path: String::new(),
+            values: Vec::new().into_iter(),
+        }
+    }
+
+    /// Paths of the keys that couldn't be read and were left out
+    pub fn skipped(&self) -> &[String] {
+        self.walk.skipped()
+    }
+
+    /// Read the values of the next key, `None` at the end of the walk
+    fn next_key(&mut self) -> Option<io::Result<()>> {
+        let values = if !self.started {
+            self.started = true;
+            self.root.enum_values().collect()
+        } else {
+            self.path = match self.walk.next()? {
+                Ok(entry) => entry.path,
+                Err(e) => return Some(Err(e)),
+            };
+            self.root
+                .open_subkey(&self.path)
+                .and_then(|key| key.enum_values().collect())
+        };
+        Some(values.map(|values: Vec<_>| self.values = values.into_iter()))
+    }
+}
+
+impl Iterator for FindValues<'_> {
+    type Item = io::Result<ValueMatch>;
+
+    fn next(&mut self) -> Option<io::Result<ValueMatch>> {
+        loop {
+            for (name, value) in &mut self.values {
+                let names = components(&self.path).chain(iter::once(name.as_str()));
+                if self.pattern.matches_names(names) {
+                    return Some(Ok(ValueMatch {
+                        key: self.path.clone(),
+                        name,
+                        value,
+                    }));
+                }
+            }
+            if let Err(e) = self.next_key()? {
+                return Some(Err(e));
+            }
+        }
+    }
+}
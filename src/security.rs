@@ -0,0 +1,201 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Security descriptors of keys.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!
+//!fn main() -> io::Result<()> {
+//!    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+//!    let template = hklm.open_subkey_with_flags("SOFTWARE\\Vendor\\Template", READ_CONTROL)?;
+//!    let app = hklm.open_subkey_with_flags("SOFTWARE\\Vendor\\App", WRITE_DAC)?;
+//!    // give the key the access rules of the template
+//!    let sd = template.get_security(DACL_SECURITY_INFORMATION)?;
+//!    app.set_security(DACL_SECURITY_INFORMATION, &sd)?;
+//!    Ok(())
+//!}
+//!```
+//!
+//! Descriptors can also be written in the
+//! [SDDL](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+//! notation, e.g. `D:P(A;OICI;KA;;;BA)(A;OICI;KR;;;BU)` for full access of
+//! administrators and read access of users.
+use crate::common::*;
+use crate::enums::*;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::ptr;
+use std::slice;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::Security;
+use windows_sys::Win32::Security::Authorization;
+
+/// Combination of `*_SECURITY_INFORMATION` flags selecting parts of a security descriptor
+pub type SecurityInformation = Security::OBJECT_SECURITY_INFORMATION;
+
+/// A self-relative security descriptor
+#[derive(Clone)]
+pub struct SecurityDescriptor {
+    /// `u64` keeps the structure aligned
+    buf: Vec<u64>,
+    len: usize,
+}
+
+impl SecurityDescriptor {
+    /// Copy a self-relative security descriptor, failing with
+    /// `ERROR_INVALID_SECURITY_DESCR` if it's malformed
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<SecurityDescriptor> {
+        let mut buf = vec![0u64; (bytes.len() + 7) / 8];
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr() as *mut u8, bytes.len())
+        };
+        let sd = SecurityDescriptor {
+            buf,
+            len: bytes.len(),
+        };
+        sd.validate()?;
+        Ok(sd)
+    }
+
+    /// Parse a descriptor in the SDDL notation, failing with `ERROR_INVALID_PARAMETER`
+    /// if the string is malformed
+    pub fn from_sddl(sddl: &str) -> io::Result<SecurityDescriptor> {
+        let c_sddl = to_utf16(sddl);
+        let mut psd: Security::PSECURITY_DESCRIPTOR = ptr::null_mut();
+        let mut len: u32 = 0;
+        if unsafe {
+            Authorization::ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                c_sddl.as_ptr(),
+                Authorization::SDDL_REVISION_1,
+                &mut psd,
+                &mut len,
+            )
+        } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        let sd = SecurityDescriptor::from_bytes(unsafe {
+            slice::from_raw_parts(psd as *const u8, len as usize)
+        });
+        unsafe { Foundation::LocalFree(psd) };
+        sd
+    }
+
+    /// Format the parts of the descriptor selected by `info` in the SDDL notation
+    pub fn to_sddl(&self, info: SecurityInformation) -> io::Result<String> {
+        let mut psddl: *mut u16 = ptr::null_mut();
+        let mut len: u32 = 0;
+        if unsafe {
+            Authorization::ConvertSecurityDescriptorToStringSecurityDescriptorW(
+                self.as_ptr(),
+                Authorization::SDDL_REVISION_1,
+                info,
+                &mut psddl,
+                &mut len,
+            )
+        } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        let words = unsafe { slice::from_raw_parts(psddl, len as usize) };
+        let sddl = String::from_utf16_lossy(words)
+            .trim_end_matches('\0')
+            .to_owned();
+        unsafe { Foundation::LocalFree(psddl as *mut _) };
+        Ok(sddl)
+    }
+
+    /// Take a descriptor written by the system into `buf`
+    pub(crate) fn from_buf(buf: Vec<u64>, len: usize) -> io::Result<SecurityDescriptor> {
+        let sd = SecurityDescriptor { buf, len };
+        sd.validate()?;
+        Ok(sd)
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        let valid = self.len >= mem::size_of::<Security::SECURITY_DESCRIPTOR_RELATIVE>()
+            && unsafe { Security::IsValidSecurityDescriptor(self.as_ptr()) } != 0
+            && unsafe { Security::GetSecurityDescriptorLength(self.as_ptr()) } as usize <= self.len;
+        if valid {
+            Ok(())
+        } else {
+            werr!(Foundation::ERROR_INVALID_SECURITY_DESCR)
+        }
+    }
+
+    /// Binary form of the descriptor
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const u8, self.len) }
+    }
+
+    /// Pointer for use with other security functions, valid as long as `self`
+    pub fn as_ptr(&self) -> Security::PSECURITY_DESCRIPTOR {
+        self.buf.as_ptr() as Security::PSECURITY_DESCRIPTOR
+    }
+
+    /// Control flags, a combination of `SE_*` constants
+    pub fn control(&self) -> io::Result<Security::SECURITY_DESCRIPTOR_CONTROL> {
+        let mut control = 0;
+        let mut revision = 0;
+        match unsafe {
+            Security::GetSecurityDescriptorControl(self.as_ptr(), &mut control, &mut revision)
+        } {
+            0 => Err(io::Error::last_os_error()),
+            _ => Ok(control),
+        }
+    }
+
+    /// Parts the descriptor contains, with `PROTECTED_DACL_SECURITY_INFORMATION`
+    /// if the DACL is protected, e.g. to set exactly those parts on a key
+    pub fn information(&self) -> io::Result<SecurityInformation> {
+        let control = self.control()?;
+        let mut info = 0;
+        let mut sid: Security::PSID = ptr::null_mut();
+        let mut defaulted = 0;
+        unsafe { Security::GetSecurityDescriptorOwner(self.as_ptr(), &mut sid, &mut defaulted) };
+        if !sid.is_null() {
+            info |= OWNER_SECURITY_INFORMATION;
+        }
+        sid = ptr::null_mut();
+        unsafe { Security::GetSecurityDescriptorGroup(self.as_ptr(), &mut sid, &mut defaulted) };
+        if !sid.is_null() {
+            info |= GROUP_SECURITY_INFORMATION;
+        }
+        if control & Security::SE_DACL_PRESENT != 0 {
+            info |= DACL_SECURITY_INFORMATION;
+            if control & Security::SE_DACL_PROTECTED != 0 {
+                info |= PROTECTED_DACL_SECURITY_INFORMATION;
+            }
+        }
+        if control & Security::SE_SACL_PRESENT != 0 {
+            info |= SACL_SECURITY_INFORMATION;
+        }
+        Ok(info)
+    }
+
+    /// Check whether the DACL is protected from inheriting entries of the parent key
+    pub fn is_dacl_protected(&self) -> io::Result<bool> {
+        Ok(self.control()? & Security::SE_DACL_PROTECTED != 0)
+    }
+}
+
+impl PartialEq for SecurityDescriptor {
+    fn eq(&self, other: &SecurityDescriptor) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for SecurityDescriptor {}
+
+impl fmt::Debug for SecurityDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecurityDescriptor({} bytes)", self.len)
+    }
+}
@@ -0,0 +1,10 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! File formats for key trees.
+//!
+//! Serialization of rust types with serde is in the `encoder` and `decoder` modules.
+pub mod reg_file;
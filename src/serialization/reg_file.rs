@@ -0,0 +1,485 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Registry Editor (`.reg`) files.
+//!
+//! Files are written in the `Windows Registry Editor Version 5.00` format,
+//! encoded as UTF-16LE with a byte order mark, like `regedit` exports them.
+//! Both that format and the older `REGEDIT4` one can be read back as a `ChangeSet`.
+//!
+//!```no_run
+//!use std::fs::File;
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!use winreg2::serialization::reg_file::{self, ExportOptions};
+//!
+//!fn main() -> io::Result<()> {
+//!    let app = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\MyApp")?;
+//!    let file = File::create("MyApp.reg")?;
+//!    reg_file::export(&app, file, &ExportOptions::new())?;
+//!    Ok(())
+//!}
+//!```
+use crate::common::*;
+use crate::enums::*;
+use crate::path::RegPath;
+use crate::types::{FromRegValue, ToRegValue};
+use crate::{RegKey, RegValue};
+use std::io::{self, Read, Write};
+use windows_sys::Win32::Foundation;
+
+/// First line of a file in the 5.00 format
+pub const HEADER_V5: &str = "Windows Registry Editor Version 5.00";
+
+/// First line of a file in the `REGEDIT4` format
+pub const HEADER_REGEDIT4: &str = "REGEDIT4";
+
+/// Maximum length of a line of hex data, longer data continues on the next line
+const MAX_LINE_LEN: usize = 80;
+
+/// Options of `export`, created with `ExportOptions::new()`
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    recursive: bool,
+    path: Option<String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> ExportOptions {
+        ExportOptions {
+            recursive: true,
+            path: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn new() -> ExportOptions {
+        ExportOptions::default()
+    }
+
+    /// Export the subkeys too, enabled by default
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Full path written for the exported key instead of its own,
+    /// e.g. to import the tree at another location
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_owned());
+        self
+    }
+}
+
+/// Write `key` (and its subkeys, unless disabled in `options`) to `writer`.
+/// Symbolic links are exported as keys without values.
+pub fn export<W: Write>(key: &RegKey, mut writer: W, options: &ExportOptions) -> io::Result<()> {
+    let root_path = match options.path {
+        Some(ref path) => path.clone(),
+        None => key.full_path()?,
+    };
+    let mut out = String::new();
+    out.push_str(HEADER_V5);
+    out.push_str("\r\n\r\n");
+    write_key(&mut out, &root_path, Some(key))?;
+    writer.write_all(&[0xff, 0xfe])?;
+    write_utf16(&mut writer, &out)?;
+    if options.recursive {
+        for entry in key.walk() {
+            let entry = entry?;
+            let subkey = key.open_subkey_nofollow(&entry.path, KEY_READ)?;
+            // the values of a link are its target, which a .reg file can't express
+            let values = if subkey.key_flags()?.link {
+                None
+            } else {
+                Some(&subkey)
+            };
+            out.clear();
+            write_key(&mut out, &format!("{}\\{}", root_path, entry.path), values)?;
+            write_utf16(&mut writer, &out)?;
+        }
+    }
+    writer.flush()
+}
+
+/// Format a single value as a `"name"=data` line, the default value has an empty name
+pub fn format_value(name: &str, value: &RegValue) -> String {
+    let mut line = if name.is_empty() {
+        "@=".to_owned()
+    } else {
+        format!("\"{}\"=", escape(name))
+    };
+    match value.vtype {
+        REG_SZ => match plain_string(value) {
+            Some(s) => {
+                line.push('"');
+                line.push_str(&escape(&s));
+                line.push('"');
+            }
+            // strings regedit can't read back, e.g. with line breaks
+            None => push_hex(&mut line, "hex(1):", &value.bytes),
+        },
+        REG_DWORD if value.bytes.len() == 4 => {
+            let dword = u32::from_reg_value(value).unwrap_or(0);
+            line.push_str(&format!("dword:{:08x}", dword));
+        }
+        REG_BINARY => push_hex(&mut line, "hex:", &value.bytes),
+        ref vtype => push_hex(
+            &mut line,
+            &format!("hex({:x}):", u32::from(vtype.clone())),
+            &value.bytes,
+        ),
+    }
+    line
+}
+
+/// Write the `[path]` header and the values of `key`, if any
+fn write_key(out: &mut String, path: &str, key: Option<&RegKey>) -> io::Result<()> {
+    out.push('[');
+    out.push_str(path);
+    out.push_str("]\r\n");
+    if let Some(key) = key {
+        for value in key.enum_values() {
+            let (name, value) = value?;
+            out.push_str(&format_value(&name, &value));
+            out.push_str("\r\n");
+        }
+    }
+    out.push_str("\r\n");
+    Ok(())
+}
+
+fn write_utf16<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    let bytes: Vec<u8> = s.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+    writer.write_all(&bytes)
+}
+
+/// Text of a `REG_SZ` value that can be written as a quoted string
+fn plain_string(value: &RegValue) -> Option<String> {
+    if value.bytes.len() % 2 != 0 {
+        return None;
+    }
+    let words: Vec<u16> = value
+        .bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    // a single terminating nul, or none
+    let words = match words.split_last() {
+        Some((0, rest)) => rest,
+        _ => &words[..],
+    };
+    let s = String::from_utf16(words).ok()?;
+    if s.contains(|c| c == '\0' || c == '\r' || c == '\n') {
+        return None;
+    }
+    Some(s)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn push_hex(line: &mut String, prefix: &str, bytes: &[u8]) {
+    line.push_str(prefix);
+    let mut line_len = line.chars().count();
+    for (i, byte) in bytes.iter().enumerate() {
+        let last = i + 1 == bytes.len();
+        // room for the byte, its comma and a continuation backslash
+        if line_len + 4 > MAX_LINE_LEN {
+            line.push_str("\\\r\n  ");
+            line_len = 2;
+        }
+        line.push_str(&format!("{:02x}", byte));
+        line_len += 2;
+        if !last {
+            line.push(',');
+            line_len += 1;
+        }
+    }
+}
+
+/// Version of a `.reg` file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `REGEDIT4`, where `hex(2)` and `hex(7)` data is ANSI text
+    Regedit4,
+    /// `Windows Registry Editor Version 5.00`, with UTF-16 text
+    V5,
+}
+
+/// An entry of a `.reg` file. Key paths are full paths as written in the file.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// `[-path]`, the key is deleted with all its subkeys
+    DeleteKey { path: String },
+    /// `[path]`, the key is created if it doesn't exist
+    CreateKey { path: String },
+    /// `"name"=data` or `@=data` for the default value
+    SetValue {
+        key: String,
+        name: String,
+        value: RegValue,
+    },
+    /// `"name"=-`
+    DeleteValue { key: String, name: String },
+}
+
+/// Contents of a `.reg` file
+#[derive(Debug, PartialEq)]
+pub struct ChangeSet {
+    pub format: Format,
+    pub changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    /// Read a file encoded as UTF-16LE with a byte order mark, or as UTF-8
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use std::fs::File;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # use winreg2::serialization::reg_file::ChangeSet;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let changes = ChangeSet::read(File::open("MyApp.reg")?)?;
+    /// changes.apply(&RegKey::predef(HKEY_CURRENT_USER))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read<R: Read>(mut reader: R) -> io::Result<ChangeSet> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        ChangeSet::parse(&decode(&bytes)?)
+    }
+
+    /// Parse the text of a file. Errors are `InvalidData` with the line number.
+    pub fn parse(text: &str) -> io::Result<ChangeSet> {
+        let mut lines = text.lines().enumerate();
+        let format = loop {
+            match lines.next() {
+                None => return Err(invalid(1, "missing header")),
+                Some((_, line)) if line.trim().is_empty() => continue,
+                Some((_, line)) if line.trim() == HEADER_V5 => break Format::V5,
+                Some((_, line)) if line.trim() == HEADER_REGEDIT4 => break Format::Regedit4,
+                Some((n, _)) => return Err(invalid(n + 1, "unknown header")),
+            }
+        };
+        let mut changes = Vec::new();
+        // `None` before the first key and after a deleted one
+        let mut key: Option<String> = None;
+        while let Some((n, line)) = lines.next() {
+            let line_no = n + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            if line.starts_with('[') {
+                let end = line
+                    .rfind(']')
+                    .ok_or_else(|| invalid(line_no, "unterminated key path"))?;
+                let path = &line[1..end];
+                if let Some(path) = path.strip_prefix('-') {
+                    changes.push(Change::DeleteKey {
+                        path: path.to_owned(),
+                    });
+                    key = None;
+                } else {
+                    changes.push(Change::CreateKey {
+                        path: path.to_owned(),
+                    });
+                    key = Some(path.to_owned());
+                }
+                continue;
+            }
+            let key = key
+                .clone()
+                .ok_or_else(|| invalid(line_no, "value outside of a key"))?;
+            // hex data continues on the next line after a trailing backslash
+            let mut line = line.to_owned();
+            while line.ends_with('\\') {
+                line.pop();
+                match lines.next() {
+                    Some((_, next)) => line.push_str(next.trim()),
+                    None => break,
+                }
+            }
+            let (name, data) = parse_name(&line).map_err(|e| invalid(line_no, e))?;
+            let data = data.trim_start();
+            if !data.starts_with('=') {
+                return Err(invalid(line_no, "expected '='"));
+            }
+            let data = data[1..].trim();
+            if data == "-" {
+                changes.push(Change::DeleteValue { key, name });
+                continue;
+            }
+            let value = parse_data(data, format).map_err(|e| invalid(line_no, e))?;
+            changes.push(Change::SetValue { key, name, value });
+        }
+        Ok(ChangeSet { format, changes })
+    }
+
+    /// Apply the changes below `root`, e.g. `RegKey::predef(HKEY_CURRENT_USER)`.
+    /// The full path of `root` is stripped from the paths in the file,
+    /// other paths fail with `ERROR_BAD_PATHNAME`. Deleting missing keys and
+    /// values is not an error.
+    pub fn apply(&self, root: &RegKey) -> io::Result<()> {
+        let base = RegPath::parse(&root.full_path()?);
+        // the key the last values were written to
+        let mut current: Option<(&str, RegKey)> = None;
+        for change in &self.changes {
+            match *change {
+                Change::DeleteKey { ref path } => {
+                    current = None;
+                    match root.delete_subkey_all(relative(&base, path)?) {
+                        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                        r => r?,
+                    }
+                }
+                Change::CreateKey { ref path } => {
+                    let (key, _disp) = root.create_subkey(relative(&base, path)?)?;
+                    current = Some((path, key));
+                }
+                Change::SetValue {
+                    ref key,
+                    ref name,
+                    ref value,
+                } => open_current(root, &base, &mut current, key)?.set_raw_value(name, value)?,
+                Change::DeleteValue { ref key, ref name } => {
+                    match open_current(root, &base, &mut current, key)?.delete_value(name) {
+                        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                        r => r?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn open_current<'a, 'c>(
+    root: &RegKey,
+    base: &RegPath,
+    current: &'c mut Option<(&'a str, RegKey)>,
+    path: &'a str,
+) -> io::Result<&'c RegKey> {
+    if current.as_ref().map_or(true, |&(p, _)| p != path) {
+        let (key, _disp) = root.create_subkey(relative(base, path)?)?;
+        *current = Some((path, key));
+    }
+    Ok(&current.as_ref().unwrap().1)
+}
+
+fn relative(base: &RegPath, path: &str) -> io::Result<String> {
+    match RegPath::parse(path).strip_prefix(base) {
+        Some(rel) => Ok(rel.relative()),
+        None => werr!(Foundation::ERROR_BAD_PATHNAME),
+    }
+}
+
+fn invalid(line: usize, msg: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line, msg),
+    )
+}
+
+fn decode(bytes: &[u8]) -> io::Result<String> {
+    if bytes.starts_with(&[0xff, 0xfe]) {
+        let words: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        return String::from_utf16(&words)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid UTF-16"));
+    }
+    let bytes = if bytes.starts_with(&[0xef, 0xbb, 0xbf]) {
+        &bytes[3..]
+    } else {
+        bytes
+    };
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Value name at the start of `s`, and the rest of `s`
+fn parse_name(s: &str) -> Result<(String, &str), &'static str> {
+    if let Some(rest) = s.strip_prefix('@') {
+        Ok((String::new(), rest))
+    } else if s.starts_with('"') {
+        parse_quoted(s)
+    } else {
+        Err("expected a quoted value name or '@'")
+    }
+}
+
+/// Unescape the quoted string at the start of `s`, and return the rest of `s`
+fn parse_quoted(s: &str) -> Result<(String, &str), &'static str> {
+    let mut result = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => {}
+            '\\' => match chars.next() {
+                Some((_, escaped)) => {
+                    result.push(escaped);
+                    continue;
+                }
+                None => break,
+            },
+            c => {
+                result.push(c);
+                continue;
+            }
+        }
+        let end = chars.next().map_or(s.len(), |(i, _)| i);
+        return Ok((result, &s[end..]));
+    }
+    Err("unterminated string")
+}
+
+fn parse_data(data: &str, format: Format) -> Result<RegValue, &'static str> {
+    if data.starts_with('"') {
+        let (s, rest) = parse_quoted(data)?;
+        if !rest.trim().is_empty() && !rest.trim_start().starts_with(';') {
+            return Err("unexpected data after string");
+        }
+        return Ok(s.to_reg_value());
+    }
+    if let Some(dword) = data.strip_prefix("dword:") {
+        return u32::from_str_radix(dword.trim(), 16)
+            .map(|dword| dword.to_reg_value())
+            .map_err(|_| "invalid dword");
+    }
+    let (vtype, bytes) = if let Some(bytes) = data.strip_prefix("hex:") {
+        (REG_BINARY, bytes)
+    } else if let Some(typed) = data.strip_prefix("hex(") {
+        let end = typed.find("):").ok_or("invalid hex type")?;
+        let vtype = u32::from_str_radix(&typed[..end], 16).map_err(|_| "invalid hex type")?;
+        (RegType::from(vtype), &typed[end + 2..])
+    } else {
+        return Err("unknown data type");
+    };
+    let mut bytes = bytes
+        .split(',')
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .map(|b| u8::from_str_radix(b, 16).map_err(|_| "invalid hex byte"))
+        .collect::<Result<Vec<u8>, _>>()?;
+    if format == Format::Regedit4 {
+        if let REG_SZ | REG_EXPAND_SZ | REG_MULTI_SZ = vtype {
+            // ANSI text, assumed to be UTF-8
+            bytes = v16_to_v8(&to_utf16(String::from_utf8_lossy(&bytes).as_ref()));
+            // `to_utf16` adds a terminator, which the data already has
+            bytes.truncate(bytes.len() - 2);
+        }
+    }
+    Ok(RegValue { bytes, vtype })
+}
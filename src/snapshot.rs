@@ -0,0 +1,98 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! In-memory copies of key trees.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!
+//!fn main() -> io::Result<()> {
+//!    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+//!    let app = hkcu.open_subkey_with_flags("Software\\MyApp", KEY_ALL_ACCESS)?;
+//!    let checkpoint = app.snapshot()?;
+//!    if let Err(e) = app.set_value("Mode", &"experimental") {
+//!        checkpoint.restore_to(&app)?;
+//!        return Err(e);
+//!    }
+//!    print!("{}", checkpoint.diff(&app.snapshot()?).to_unified("before", "after"));
+//!    Ok(())
+//!}
+//!```
+use crate::diff::{self, KeyDiff, Tree, ValueDiff};
+use crate::enums::*;
+use crate::{RegKey, RegValue};
+use std::io;
+
+/// Values of a key and all its subkeys, taken with `RegKey::snapshot`.
+/// Key paths are relative to that key, which has an empty path.
+#[derive(Debug, PartialEq)]
+pub struct RegSnapshot {
+    tree: Tree,
+}
+
+impl RegSnapshot {
+    pub(crate) fn new(key: &RegKey) -> io::Result<RegSnapshot> {
+        Ok(RegSnapshot {
+            tree: diff::read_tree(key)?,
+        })
+    }
+
+    /// Paths of all the keys, sorted, starting with the empty path of the snapshotted key
+    pub fn key_paths(&self) -> impl Iterator<Item = &str> {
+        self.tree.keys().map(String::as_str)
+    }
+
+    /// Value `name` of the key at `path`, `None` if either doesn't exist
+    pub fn get_raw_value(&self, path: &str, name: &str) -> Option<&RegValue> {
+        self.tree.get(path)?.get(name)
+    }
+
+    /// Differences from this snapshot to `other`
+    pub fn diff(&self, other: &RegSnapshot) -> KeyDiff {
+        diff::diff_trees(&self.tree, &other.tree)
+    }
+
+    /// Bring `key` to the state of the snapshot: create missing keys and values,
+    /// rewrite changed values and delete keys and values that are not in the snapshot.
+    /// Writes made before an error are not undone.
+    pub fn restore_to(&self, key: &RegKey) -> io::Result<()> {
+        let changes = diff::diff_trees(&diff::read_tree(key)?, &self.tree);
+        // sorted paths put parents first, their deletion takes the subkeys along
+        let mut deleted: Vec<&str> = Vec::new();
+        for path in &changes.removed_keys {
+            let in_deleted = deleted
+                .iter()
+                .any(|d| path.starts_with(d) && path[d.len()..].starts_with('\\'));
+            if !in_deleted {
+                key.delete_subkey_all(path)?;
+                deleted.push(path);
+            }
+        }
+        for path in &changes.added_keys {
+            key.create_subkey(path)?;
+        }
+        let mut current: Option<(&str, RegKey)> = None;
+        for value in &changes.values {
+            let path = value.key();
+            if changes.removed_keys.iter().any(|k| k == path) {
+                continue;
+            }
+            if current.as_ref().map_or(true, |(p, _)| *p != path) {
+                let target = key.open_subkey_with_flags(path, KEY_READ | KEY_WRITE)?;
+                current = Some((path, target));
+            }
+            let target = &current.as_ref().unwrap().1;
+            match value {
+                ValueDiff::Added { name, value, .. } => target.set_raw_value(name, value)?,
+                ValueDiff::Changed { name, new, .. } => target.set_raw_value(name, new)?,
+                ValueDiff::Removed { name, .. } => target.delete_value(name)?,
+            }
+        }
+        Ok(())
+    }
+}
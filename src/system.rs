@@ -0,0 +1,77 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Registry-wide size information.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::system;
+//!
+//!fn main() -> io::Result<()> {
+//!    let (used, allowed) = system::registry_quota()?;
+//!    println!("registry uses {} of {} bytes", used, allowed);
+//!    for hive in system::hive_files()? {
+//!        println!("{} ({}): {:?} bytes", hive.key, hive.file, hive.size);
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::reg_key::win32_path;
+use crate::RegKey;
+use std::fs;
+use std::io;
+use windows_sys::Win32::System::WindowsProgramming;
+
+/// Path of the key listing the loaded hives and their files relative to `HKLM`
+pub const HIVELIST_PATH: &str = "SYSTEM\\CurrentControlSet\\Control\\hivelist";
+
+/// Get the current size of the registry and its maximum size in bytes, as `(used, allowed)`.
+/// Since Windows XP the registry has no size limit and `allowed` is just a high value.
+pub fn registry_quota() -> io::Result<(u32, u32)> {
+    let mut allowed: u32 = 0;
+    let mut used: u32 = 0;
+    if unsafe { WindowsProgramming::GetSystemRegistryQuota(&mut allowed, &mut used) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok((used, allowed))
+}
+
+/// A loaded hive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiveFile {
+    /// Key the hive is loaded at, e.g. `HKEY_LOCAL_MACHINE\SOFTWARE`
+    pub key: String,
+    /// NT path of the hive file, e.g. `\Device\HarddiskVolume3\Windows\System32\config\SOFTWARE`,
+    /// empty for in-memory hives such as `HARDWARE`
+    pub file: String,
+    /// Size of the file in bytes, `None` if it has no file or its size can't be read
+    pub size: Option<u64>,
+}
+
+/// List the loaded hives with the sizes of their files
+pub fn hive_files() -> io::Result<Vec<HiveFile>> {
+    let hivelist = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(HIVELIST_PATH)?;
+    let mut hives = Vec::new();
+    for value in hivelist.enum_values() {
+        let (name, _) = value?;
+        let file: String = hivelist.get_value(&name)?;
+        let size = if file.is_empty() {
+            None
+        } else {
+            // NT device paths are accessible through the GLOBALROOT prefix
+            fs::metadata(format!("\\\\?\\GLOBALROOT{}", file))
+                .ok()
+                .map(|m| m.len())
+        };
+        hives.push(HiveFile {
+            key: win32_path(&name),
+            file,
+            size,
+        });
+    }
+    Ok(hives)
+}
@@ -38,28 +38,88 @@
 //!}
 //!```
 
+use crate::common::to_utf16;
+use crate::enums::*;
+use crate::reg_key::{EnumKeys, EnumValues, RegKey};
+use crate::reg_key_metadata::RegKeyMetadata;
+use crate::reg_value::RegValue;
+use crate::types::{FromRegValue, ToRegValue};
+use std::ffi::OsStr;
 use std::io;
+use std::mem;
 use std::ptr;
+use std::time::Duration;
 use windows_sys::Win32::Foundation;
 use windows_sys::Win32::Storage::FileSystem;
+use windows_sys::Win32::System::Registry;
 
-#[derive(Debug)]
-pub struct Transaction {
-    pub handle: Foundation::HANDLE,
+/// Outcome of a transaction returned by `Transaction::status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Neither committed nor rolled back yet
+    Active,
+    /// Committed, only reported to other handles of the transaction
+    Committed,
+    /// Rolled back, explicitly or because the timeout expired
+    Aborted,
 }
 
-impl Transaction {
-    //TODO: add arguments
-    pub fn new() -> io::Result<Transaction> {
+/// Parameters of `CreateTransaction`, returned by `Transaction::builder()`.
+/// KTM has no isolation options, the isolation level is always read committed.
+#[derive(Debug, Default)]
+pub struct TransactionBuilder {
+    description: Option<String>,
+    timeout: Option<Duration>,
+    do_not_promote: bool,
+}
+
+impl TransactionBuilder {
+    pub fn new() -> TransactionBuilder {
+        TransactionBuilder::default()
+    }
+
+    /// Description shown by tools listing the active transactions, e.g. `ktmutil list tms`
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Roll back the transaction if it isn't committed within `timeout`,
+    /// rounded up to milliseconds. No timeout by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Create the transaction with `TRANSACTION_DO_NOT_PROMOTE`,
+    /// so it can't become a distributed transaction
+    pub fn do_not_promote(mut self, do_not_promote: bool) -> Self {
+        self.do_not_promote = do_not_promote;
+        self
+    }
+
+    pub fn build(self) -> io::Result<Transaction> {
+        let c_description = self.description.as_ref().map(to_utf16);
+        let description = c_description.as_ref().map_or(ptr::null(), |d| d.as_ptr());
+        // zero means no timeout
+        let timeout = self.timeout.map_or(0, |t| {
+            let ms = (t.as_nanos() + 999_999) / 1_000_000;
+            ms.max(1).min(u128::from(u32::MAX - 1)) as u32
+        });
+        let options = if self.do_not_promote {
+            FileSystem::TRANSACTION_DO_NOT_PROMOTE
+        } else {
+            0
+        };
         unsafe {
             let handle = FileSystem::CreateTransaction(
                 ptr::null_mut(),
                 ptr::null_mut(),
+                options,
                 0,
                 0,
-                0,
-                0,
-                ptr::null_mut(),
+                timeout,
+                description,
             );
             if handle == Foundation::INVALID_HANDLE_VALUE {
                 return Err(io::Error::last_os_error());
@@ -67,6 +127,76 @@ impl Transaction {
             Ok(Transaction { handle })
         }
     }
+}
+
+#[derive(Debug)]
+pub struct Transaction {
+    pub handle: Foundation::HANDLE,
+}
+
+impl Transaction {
+    pub fn new() -> io::Result<Transaction> {
+        TransactionBuilder::new().build()
+    }
+
+    /// Create a transaction with a description, a timeout or creation options
+    pub fn builder() -> TransactionBuilder {
+        TransactionBuilder::new()
+    }
+
+    /// Take ownership of a KTM transaction handle created elsewhere, e.g. for file
+    /// operations, so registry work joins the same transaction.
+    /// The handle is closed when the returned `Transaction` is dropped,
+    /// pass a duplicate (`DuplicateHandle`) to keep the original open.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be an open transaction handle that isn't closed elsewhere.
+    pub unsafe fn from_raw_handle(handle: Foundation::HANDLE) -> Transaction {
+        Transaction { handle }
+    }
+
+    /// Transaction handle, e.g. for `CreateFileTransactedW`
+    pub fn as_raw_handle(&self) -> Foundation::HANDLE {
+        self.handle
+    }
+
+    /// Release the ownership of the handle without closing it
+    pub fn into_raw_handle(self) -> Foundation::HANDLE {
+        let handle = self.handle;
+        mem::forget(self);
+        handle
+    }
+
+    /// Query the outcome of the transaction, e.g. to find out whether its timeout expired
+    pub fn status(&self) -> io::Result<TransactionStatus> {
+        let mut outcome: u32 = 0;
+        let ok = unsafe {
+            FileSystem::GetTransactionInformation(
+                self.handle,
+                &mut outcome,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        match outcome as i32 {
+            FileSystem::TransactionOutcomeCommitted => Ok(TransactionStatus::Committed),
+            FileSystem::TransactionOutcomeAborted => Ok(TransactionStatus::Aborted),
+            _ => Ok(TransactionStatus::Active),
+        }
+    }
+
+    /// Check whether the transaction can still be used, i.e. it was neither
+    /// committed nor rolled back
+    pub fn is_active(&self) -> io::Result<bool> {
+        self.status().map(|s| s == TransactionStatus::Active)
+    }
 
     pub fn commit(self) -> io::Result<()> {
         unsafe {
@@ -107,3 +237,98 @@ impl AsRef<Transaction> for Transaction {
         self
     }
 }
+
+/// A key opened in a transaction, returned by `RegKey::transacted`.
+///
+/// Values are read and written through the transacted handle and subkeys are
+/// opened, created and deleted in the same transaction, so nothing done through
+/// it takes effect before the transaction is committed.
+#[derive(Debug)]
+pub struct TransactedKey<'t> {
+    key: RegKey,
+    t: &'t Transaction,
+}
+
+impl<'t> TransactedKey<'t> {
+    pub(crate) fn new(key: RegKey, t: &'t Transaction) -> TransactedKey<'t> {
+        TransactedKey { key, t }
+    }
+
+    /// The transaction of the key
+    pub fn transaction(&self) -> &'t Transaction {
+        self.t
+    }
+
+    /// Open a subkey for reading and writing in the transaction
+    pub fn open_subkey<P: AsRef<OsStr>>(&self, path: P) -> io::Result<TransactedKey<'t>> {
+        self.open_subkey_with_flags(path, KEY_READ | KEY_WRITE)
+    }
+
+    /// Open a subkey with the desired permissions in the transaction
+    pub fn open_subkey_with_flags<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+        perms: Registry::REG_SAM_FLAGS,
+    ) -> io::Result<TransactedKey<'t>> {
+        self.key
+            .open_subkey_transacted_with_flags(path, self.t, perms)
+            .map(|key| TransactedKey::new(key, self.t))
+    }
+
+    pub fn create_subkey<P: AsRef<OsStr>>(
+        &self,
+        path: P,
+    ) -> io::Result<(TransactedKey<'t>, RegDisposition)> {
+        self.key
+            .create_subkey_transacted(path, self.t)
+            .map(|(key, disp)| (TransactedKey::new(key, self.t), disp))
+    }
+
+    pub fn delete_subkey<P: AsRef<OsStr>>(&self, path: P) -> io::Result<()> {
+        self.key.delete_subkey_transacted(path, self.t)
+    }
+
+    /// Same as `RegKey::delete_subkey_all_transacted`
+    pub fn delete_subkey_all<P: AsRef<OsStr>>(&self, path: P) -> io::Result<()> {
+        self.key.delete_subkey_all_transacted(path, self.t)
+    }
+
+    pub fn get_value<T: FromRegValue, N: AsRef<OsStr>>(&self, name: N) -> io::Result<T> {
+        self.key.get_value(name)
+    }
+
+    pub fn get_value_opt<T: FromRegValue, N: AsRef<OsStr>>(
+        &self,
+        name: N,
+    ) -> io::Result<Option<T>> {
+        self.key.get_value_opt(name)
+    }
+
+    pub fn get_raw_value<N: AsRef<OsStr>>(&self, name: N) -> io::Result<RegValue> {
+        self.key.get_raw_value(name)
+    }
+
+    pub fn set_value<T: ToRegValue, N: AsRef<OsStr>>(&self, name: N, value: &T) -> io::Result<()> {
+        self.key.set_value(name, value)
+    }
+
+    pub fn set_raw_value<N: AsRef<OsStr>>(&self, name: N, value: &RegValue) -> io::Result<()> {
+        self.key.set_raw_value(name, value)
+    }
+
+    pub fn delete_value<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        self.key.delete_value(name)
+    }
+
+    pub fn enum_keys(&self) -> EnumKeys<'_> {
+        self.key.enum_keys()
+    }
+
+    pub fn enum_values(&self) -> EnumValues<'_> {
+        self.key.enum_values()
+    }
+
+    pub fn query_info(&self) -> io::Result<RegKeyMetadata> {
+        self.key.query_info()
+    }
+}
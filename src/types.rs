@@ -12,6 +12,7 @@ use std::convert::TryInto;
 use std::ffi::{OsStr, OsString};
 use std::io;
 use std::os::windows::ffi::OsStringExt;
+use std::path::{Path, PathBuf};
 use std::slice;
 use windows_sys::Win32::Foundation;
 
@@ -128,6 +129,192 @@ impl FromRegValue for u32 {
     }
 }
 
+/// Expands environment variables of `REG_EXPAND_SZ` values
+impl FromRegValue for PathBuf {
+    fn from_reg_value(val: &RegValue) -> io::Result<PathBuf> {
+        match val.vtype {
+            REG_SZ => OsString::from_reg_value(val).map(PathBuf::from),
+            REG_EXPAND_SZ => expand_env_strings(OsString::from_reg_value(val)?).map(PathBuf::from),
+            _ => werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        }
+    }
+}
+
+/// A path stored as `REG_EXPAND_SZ`, with `%VAR%` references left unexpanded.
+///
+/// `PathBuf` is written as `REG_SZ` and expands `REG_EXPAND_SZ` values when read,
+/// wrap it to write the expandable type or to read the value as it is stored.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ExpandablePath(pub PathBuf);
+
+impl FromRegValue for ExpandablePath {
+    fn from_reg_value(val: &RegValue) -> io::Result<ExpandablePath> {
+        match val.vtype {
+            REG_SZ | REG_EXPAND_SZ => {
+                OsString::from_reg_value(val).map(|s| ExpandablePath(PathBuf::from(s)))
+            }
+            _ => werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        }
+    }
+}
+
+/// A string stored as `REG_EXPAND_SZ`, with `%VAR%` references left unexpanded.
+///
+/// `String` is written as `REG_SZ`, wrap it to write the expandable type.
+/// Reads both `REG_SZ` and `REG_EXPAND_SZ` values.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ExpandString(pub String);
+
+impl ExpandString {
+    /// Expand the `%VAR%` references using the environment of the current process
+    pub fn expand(&self) -> io::Result<String> {
+        expand_env_strings(&self.0).map(|s| s.to_string_lossy().into_owned())
+    }
+}
+
+impl FromRegValue for ExpandString {
+    fn from_reg_value(val: &RegValue) -> io::Result<ExpandString> {
+        match val.vtype {
+            REG_SZ | REG_EXPAND_SZ => String::from_reg_value(val).map(ExpandString),
+            _ => werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        }
+    }
+}
+
+impl FromRegValue for Vec<u8> {
+    fn from_reg_value(val: &RegValue) -> io::Result<Vec<u8>> {
+        match val.vtype {
+            REG_BINARY => Ok(val.bytes.clone()),
+            _ => werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        }
+    }
+}
+
+impl<const N: usize> FromRegValue for [u8; N] {
+    fn from_reg_value(val: &RegValue) -> io::Result<[u8; N]> {
+        match val.vtype {
+            REG_BINARY => try_from_reg_value_int!(val, |a| a),
+            _ => werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        }
+    }
+}
+
+/// Seconds between 1601-01-01, the `FILETIME` epoch, and 1970-01-01
+#[cfg(any(feature = "chrono", feature = "time"))]
+const FILETIME_UNIX_EPOCH_SECS: i64 = 11_644_473_600;
+
+/// `FILETIME` of a time in nanoseconds since 1970-01-01,
+/// clamped to the range of `FILETIME` for times before 1601 or far in the future
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn filetime_from_unix_nanos(nanos: i128) -> u64 {
+    let intervals = nanos.div_euclid(100) + i128::from(FILETIME_UNIX_EPOCH_SECS) * 10_000_000;
+    intervals.max(0).min(i128::from(u64::MAX)) as u64
+}
+
+/// Reads `FILETIME` values: 100ns intervals since 1601-01-01 UTC
+/// stored as `REG_QWORD` or 8 bytes of `REG_BINARY`.
+/// Part of `chrono` feature.
+#[cfg(feature = "chrono")]
+impl FromRegValue for chrono::DateTime<chrono::Utc> {
+    fn from_reg_value(val: &RegValue) -> io::Result<chrono::DateTime<chrono::Utc>> {
+        use chrono::TimeZone;
+        let intervals = match val.vtype {
+            REG_QWORD | REG_BINARY => try_from_reg_value_int!(val, u64::from_le_bytes)?,
+            _ => return werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        };
+        let secs = (intervals / 10_000_000) as i64 - FILETIME_UNIX_EPOCH_SECS;
+        let nanos = (intervals % 10_000_000) as u32 * 100;
+        chrono::Utc
+            .timestamp_opt(secs, nanos)
+            .single()
+            .ok_or_else(invalid_data)
+    }
+}
+
+/// Reads GUIDs from `REG_SZ`, with or without braces, or from 16 bytes of `REG_BINARY`
+/// in the mixed-endian layout of the Windows `GUID` struct.
+/// Part of `uuid` feature.
+#[cfg(feature = "uuid")]
+impl FromRegValue for uuid::Uuid {
+    fn from_reg_value(val: &RegValue) -> io::Result<uuid::Uuid> {
+        match val.vtype {
+            REG_SZ => {
+                let s = String::from_reg_value(val)?;
+                let s = s.trim();
+                let s = s
+                    .strip_prefix('{')
+                    .and_then(|s| s.strip_suffix('}'))
+                    .unwrap_or(s);
+                uuid::Uuid::try_parse(s).map_err(|_| invalid_data())
+            }
+            REG_BINARY => try_from_reg_value_int!(val, uuid::Uuid::from_bytes_le),
+            _ => werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        }
+    }
+}
+
+/// Reads `FILETIME` values: 100ns intervals since 1601-01-01 UTC
+/// stored as `REG_QWORD` or 8 bytes of `REG_BINARY`. The result is in UTC.
+/// Part of `time` feature.
+#[cfg(feature = "time")]
+impl FromRegValue for time::OffsetDateTime {
+    fn from_reg_value(val: &RegValue) -> io::Result<time::OffsetDateTime> {
+        let intervals = match val.vtype {
+            REG_QWORD | REG_BINARY => try_from_reg_value_int!(val, u64::from_le_bytes)?,
+            _ => return werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        };
+        let nanos =
+            (i128::from(intervals) - i128::from(FILETIME_UNIX_EPOCH_SECS) * 10_000_000) * 100;
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| invalid_data())
+    }
+}
+
+macro_rules! from_reg_value_via {
+    ($t:ty, $via:ty, $map:expr) => {
+        impl FromRegValue for $t {
+            fn from_reg_value(val: &RegValue) -> io::Result<$t> {
+                <$via>::from_reg_value(val).and_then($map)
+            }
+        }
+    };
+}
+
+// signed integers are stored in two's complement
+from_reg_value_via!(i32, u32, |v| Ok(v as i32));
+from_reg_value_via!(i64, u64, |v| Ok(v as i64));
+from_reg_value_via!(i128, u128, |v| Ok(v as i128));
+from_reg_value_via!(u8, u32, |v| u8::try_from(v).map_err(|_| invalid_data()));
+from_reg_value_via!(u16, u32, |v| u16::try_from(v).map_err(|_| invalid_data()));
+// any non-zero DWORD is true
+from_reg_value_via!(bool, u32, |v| Ok(v != 0));
+
+fn invalid_data() -> io::Error {
+    io::Error::from_raw_os_error(Foundation::ERROR_INVALID_DATA as i32)
+}
+
+/// 128-bit integers are stored as 16 bytes of little-endian `REG_BINARY`
+impl FromRegValue for u128 {
+    fn from_reg_value(val: &RegValue) -> io::Result<u128> {
+        match val.vtype {
+            REG_BINARY => try_from_reg_value_int!(val, u128::from_le_bytes),
+            _ => werr!(Foundation::ERROR_BAD_FILE_TYPE),
+        }
+    }
+}
+
+/// A `u32` stored as `REG_DWORD_BIG_ENDIAN`.
+///
+/// `u32` can be read from both `REG_DWORD` and `REG_DWORD_BIG_ENDIAN` values
+/// but is always written as `REG_DWORD`, wrap it to write the big-endian type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BigEndian(pub u32);
+
+impl FromRegValue for BigEndian {
+    fn from_reg_value(val: &RegValue) -> io::Result<BigEndian> {
+        u32::from_reg_value(val).map(BigEndian)
+    }
+}
+
 impl FromRegValue for u64 {
     fn from_reg_value(val: &RegValue) -> io::Result<u64> {
         match val.vtype {
@@ -161,6 +348,26 @@ to_reg_value_sz!(String);
 to_reg_value_sz!(&'a str, 'a);
 to_reg_value_sz!(OsString);
 to_reg_value_sz!(&'a OsStr, 'a);
+to_reg_value_sz!(PathBuf);
+to_reg_value_sz!(&'a Path, 'a);
+
+impl ToRegValue for ExpandString {
+    fn to_reg_value(&self) -> RegValue {
+        RegValue {
+            bytes: v16_to_v8(&to_utf16(&self.0)),
+            vtype: REG_EXPAND_SZ,
+        }
+    }
+}
+
+impl ToRegValue for ExpandablePath {
+    fn to_reg_value(&self) -> RegValue {
+        RegValue {
+            bytes: v16_to_v8(&to_utf16(&self.0)),
+            vtype: REG_EXPAND_SZ,
+        }
+    }
+}
 
 macro_rules! to_reg_value_multi_sz {
     ($t:ty$(, $l:lifetime)*) => {
@@ -207,3 +414,91 @@ impl ToRegValue for u64 {
         }
     }
 }
+
+impl ToRegValue for BigEndian {
+    fn to_reg_value(&self) -> RegValue {
+        RegValue {
+            bytes: self.0.to_be_bytes().to_vec(),
+            vtype: REG_DWORD_BIG_ENDIAN,
+        }
+    }
+}
+
+impl ToRegValue for &[u8] {
+    fn to_reg_value(&self) -> RegValue {
+        RegValue {
+            bytes: self.to_vec(),
+            vtype: REG_BINARY,
+        }
+    }
+}
+
+impl ToRegValue for Vec<u8> {
+    fn to_reg_value(&self) -> RegValue {
+        self.as_slice().to_reg_value()
+    }
+}
+
+impl<const N: usize> ToRegValue for [u8; N] {
+    fn to_reg_value(&self) -> RegValue {
+        (&self[..]).to_reg_value()
+    }
+}
+
+/// Writes a `FILETIME` as `REG_QWORD`, times before 1601-01-01 are written as zero
+/// and times after the year 60055 as `u64::MAX`.
+/// Part of `chrono` feature.
+#[cfg(feature = "chrono")]
+impl ToRegValue for chrono::DateTime<chrono::Utc> {
+    fn to_reg_value(&self) -> RegValue {
+        let nanos = i128::from(self.timestamp()) * 1_000_000_000
+            + i128::from(self.timestamp_subsec_nanos());
+        filetime_from_unix_nanos(nanos).to_reg_value()
+    }
+}
+
+/// Writes a `FILETIME` as `REG_QWORD`, times before 1601-01-01 are written as zero
+/// and times after the year 60055 as `u64::MAX`.
+/// Part of `time` feature.
+#[cfg(feature = "time")]
+impl ToRegValue for time::OffsetDateTime {
+    fn to_reg_value(&self) -> RegValue {
+        filetime_from_unix_nanos(self.unix_timestamp_nanos()).to_reg_value()
+    }
+}
+
+/// Writes a GUID as `REG_SZ` in the registry format, e.g. `{6B29FC40-CA47-1067-B31D-00DD010662DA}`.
+/// Part of `uuid` feature.
+#[cfg(feature = "uuid")]
+impl ToRegValue for uuid::Uuid {
+    fn to_reg_value(&self) -> RegValue {
+        let s = self.braced().to_string().to_uppercase();
+        s.to_reg_value()
+    }
+}
+
+impl ToRegValue for u128 {
+    fn to_reg_value(&self) -> RegValue {
+        RegValue {
+            bytes: self.to_le_bytes().to_vec(),
+            vtype: REG_BINARY,
+        }
+    }
+}
+
+macro_rules! to_reg_value_via {
+    ($t:ty, $via:ty) => {
+        impl ToRegValue for $t {
+            fn to_reg_value(&self) -> RegValue {
+                (*self as $via).to_reg_value()
+            }
+        }
+    };
+}
+
+to_reg_value_via!(i32, u32);
+to_reg_value_via!(i64, u64);
+to_reg_value_via!(i128, u128);
+to_reg_value_via!(u8, u32);
+to_reg_value_via!(u16, u32);
+to_reg_value_via!(bool, u32);
@@ -0,0 +1,109 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Comparison of key and value names.
+//!
+//! The registry preserves the case of names but compares them case-insensitively,
+//! upcasing each UTF-16 code unit with the OS table. That differs from Unicode case
+//! folding (`"ß"` is not equal to `"SS"`), so `to_lowercase` comparisons can report
+//! differences the registry doesn't make, or miss ones it does.
+//!
+//!```no_run
+//!use std::collections::BTreeMap;
+//!use winreg2::util::{self, Name};
+//!
+//!assert!(util::name_eq("Path", "PATH"));
+//!let mut values = BTreeMap::new();
+//!values.insert(Name::from("Path"), 1);
+//!assert_eq!(values.get(&Name::from("PATH")), Some(&1));
+//!```
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use windows_sys::Wdk::System::SystemServices::RtlUpcaseUnicodeChar;
+
+/// Compare names the way the registry does
+pub fn name_cmp(a: &str, b: &str) -> Ordering {
+    a.encode_utf16()
+        .map(upcase)
+        .cmp(b.encode_utf16().map(upcase))
+}
+
+/// Check whether two names refer to the same key or value
+pub fn name_eq(a: &str, b: &str) -> bool {
+    name_cmp(a, b) == Ordering::Equal
+}
+
+/// Hash a name consistently with `name_eq`
+pub(crate) fn hash_name<H: Hasher>(name: &str, state: &mut H) {
+    for c in name.encode_utf16() {
+        state.write_u16(upcase(c));
+    }
+}
+
+fn upcase(c: u16) -> u16 {
+    // ASCII is by far the most common case and doesn't need a call
+    if c < 0x80 {
+        (c as u8).to_ascii_uppercase().into()
+    } else {
+        unsafe { RtlUpcaseUnicodeChar(c) }
+    }
+}
+
+/// A key or value name that keeps its case but is compared, ordered and hashed
+/// case-insensitively like in the registry, for use in maps and sets
+#[derive(Debug, Clone, Default)]
+pub struct Name(pub String);
+
+impl PartialEq for Name {
+    fn eq(&self, other: &Name) -> bool {
+        name_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Name {}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Name) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Name {
+    fn cmp(&self, other: &Name) -> Ordering {
+        name_cmp(&self.0, &other.0)
+    }
+}
+
+impl Hash for Name {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_name(&self.0, state);
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Name {
+    fn from(s: String) -> Name {
+        Name(s)
+    }
+}
+
+impl<'a> From<&'a str> for Name {
+    fn from(s: &'a str) -> Name {
+        Name(s.to_owned())
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! UAC registry virtualization.
+//!
+//! Non-elevated 32-bit processes without a UAC manifest are virtualized:
+//! their failed writes to `HKLM\SOFTWARE` silently land in the per-user
+//! `HKCU\Software\Classes\VirtualStore\MACHINE\SOFTWARE` instead,
+//! and reads merge both locations.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::virtualization;
+//!
+//!fn main() -> io::Result<()> {
+//!    if virtualization::is_process_virtualized()? {
+//!        println!("writes to HKLM\\SOFTWARE may be redirected");
+//!    }
+//!    if let Ok(key) = virtualization::read_virtualstore_counterpart("SOFTWARE\\LegacyApp") {
+//!        for (name, value) in key.enum_values().map(|v| v.unwrap()) {
+//!            println!("virtualized: {} = {}", name, value);
+//!        }
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::common::ProcessToken;
+use crate::enums::*;
+use crate::RegKey;
+use std::io;
+use std::mem;
+use windows_sys::Win32::Foundation;
+use windows_sys::Win32::Security;
+
+/// Path of the virtual store relative to `HKCU`
+pub const VIRTUAL_STORE_PATH: &str = "Software\\Classes\\VirtualStore\\MACHINE";
+
+/// Check whether the handle refers to a key in the virtual store
+/// rather than to the real `HKLM` key
+pub fn is_virtualized(key: &RegKey) -> io::Result<bool> {
+    Ok(key.physical_path()?.virtualized)
+}
+
+/// Path relative to `HKCU` where writes of a virtualized process to
+/// `HKLM\<path>` are redirected, `None` if `path` is not subject to virtualization
+pub fn virtual_store_path(path: &str) -> Option<String> {
+    let path = path.trim_matches('\\');
+    let first = path.split('\\').next().unwrap_or("");
+    if first.eq_ignore_ascii_case("SOFTWARE") {
+        Some(format!("{}\\{}", VIRTUAL_STORE_PATH, path))
+    } else {
+        None
+    }
+}
+
+/// Open the virtual store copy of `HKLM\<path>` with `KEY_READ`, so its content
+/// can be compared with or migrated to the real key
+pub fn read_virtualstore_counterpart(path: &str) -> io::Result<RegKey> {
+    match virtual_store_path(path) {
+        Some(store) => RegKey::predef(HKEY_CURRENT_USER).open_subkey(store),
+        None => werr!(Foundation::ERROR_INVALID_PARAMETER),
+    }
+}
+
+/// Check whether registry virtualization is enabled for the current process
+pub fn is_process_virtualized() -> io::Result<bool> {
+    let token = ProcessToken::open(Security::TOKEN_QUERY)?;
+    let mut enabled: u32 = 0;
+    let mut len: u32 = 0;
+    if unsafe {
+        Security::GetTokenInformation(
+            token.0,
+            Security::TokenVirtualizationEnabled,
+            &mut enabled as *mut u32 as *mut _,
+            mem::size_of::<u32>() as u32,
+            &mut len,
+        )
+    } == 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(enabled != 0)
+}
+
+/// Enable or disable registry virtualization for the current process.
+/// Disabling it makes writes to `HKLM` fail with access denied instead of being redirected.
+/// Only allowed for processes that are virtualization-eligible.
+pub fn set_process_virtualization(enabled: bool) -> io::Result<()> {
+    let token = ProcessToken::open(Security::TOKEN_ADJUST_DEFAULT)?;
+    let value = enabled as u32;
+    if unsafe {
+        Security::SetTokenInformation(
+            token.0,
+            Security::TokenVirtualizationEnabled,
+            &value as *const u32 as *const _,
+            mem::size_of::<u32>() as u32,
+        )
+    } == 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
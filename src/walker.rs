@@ -0,0 +1,507 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Recursive traversal of a key tree.
+//!
+//! Symbolic links are not followed by default: a link pointing to one of its
+//! ancestors would otherwise make the walk endless.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!use winreg2::walker::WalkOptions;
+//!
+//!fn main() -> io::Result<()> {
+//!    let system = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey("SYSTEM")?;
+//!    let options = WalkOptions::new().max_depth(2).key_flags(true);
+//!    for entry in system.walk_with_options(options) {
+//!        let entry = entry?;
+//!        let link = entry.flags.map_or(false, |f| f.link);
+//!        println!("{}{}", entry.path, if link { " -> (link)" } else { "" });
+//!    }
+//!    Ok(())
+//!}
+//!```
+use crate::enums::*;
+use crate::path::RegPath;
+use crate::reg_key::{KeyFlags, RegKey};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+#[cfg(feature = "rayon")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "rayon")]
+use std::sync::Mutex;
+
+/// Order in which a walk visits keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkOrder {
+    /// Every key is followed by its subtree, keeps a handle open per level
+    DepthFirst,
+    /// All keys of a level before the next level, keeps a single handle open
+    /// but queues the paths of a whole level
+    BreadthFirst,
+}
+
+impl Default for WalkOrder {
+    fn default() -> WalkOrder {
+        WalkOrder::DepthFirst
+    }
+}
+
+/// What a walk does when a key can't be opened or enumerated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Yield the error and end the walk
+    Abort,
+    /// Leave out the subtree of the key, its path is kept in `Walk::skipped`
+    Skip,
+    /// Leave out the subtree of the key, the error is kept in `Walk::errors`
+    Collect,
+}
+
+impl Default for ErrorPolicy {
+    fn default() -> ErrorPolicy {
+        ErrorPolicy::Abort
+    }
+}
+
+/// Options of a walk, created with `WalkOptions::new()`
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    max_depth: Option<usize>,
+    follow_links: bool,
+    key_flags: bool,
+    view: RegistryView,
+    order: WalkOrder,
+    max_open_handles: Option<usize>,
+    error_policy: ErrorPolicy,
+}
+
+impl WalkOptions {
+    pub fn new() -> WalkOptions {
+        WalkOptions::default()
+    }
+
+    /// Don't descend deeper than `depth` levels, direct subkeys have depth 1
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Descend into symbolic links. Use with `max_depth`, as links may form cycles.
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
+    /// Registry view every subkey is opened in
+    pub fn view(mut self, view: RegistryView) -> Self {
+        self.view = view;
+        self
+    }
+
+    /// Report volatile and link flags of every key in `WalkEntry::flags`
+    pub fn key_flags(mut self, report: bool) -> Self {
+        self.key_flags = report;
+        self
+    }
+
+    /// Order of the walk, depth-first by default
+    pub fn order(mut self, order: WalkOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Keep at most `max` handles of ancestor keys open during a depth-first walk.
+    /// The ones closest to the starting key are closed first and re-opened by path
+    /// when the walk returns to them, trading syscalls for handle pressure.
+    pub fn max_open_handles(mut self, max: usize) -> Self {
+        self.max_open_handles = Some(max);
+        self
+    }
+
+    /// Handling of per-key failures such as `ERROR_ACCESS_DENIED`, `Abort` by default
+    pub fn error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+}
+
+/// A key found during a walk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkEntry {
+    /// Path relative to the key the walk started from
+    pub path: String,
+    /// Name of the key, the last component of `path`
+    pub name: String,
+    /// Direct subkeys of the starting key have depth 1
+    pub depth: usize,
+    /// Set if `WalkOptions::key_flags` is enabled
+    pub flags: Option<KeyFlags>,
+}
+
+impl WalkEntry {
+    /// Path of the entry under `base`, e.g. the key a copied or migrated tree
+    /// is written to.
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use winreg2::RegKey;
+    /// # use winreg2::enums::*;
+    /// # use winreg2::path::RegPath;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let src = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\MyApp")?;
+    /// let dest = RegPath::parse("HKLM\\SOFTWARE\\MyApp");
+    /// for entry in src.walk() {
+    ///     println!("{}", entry?.reroot(&dest)); // HKEY_LOCAL_MACHINE\SOFTWARE\MyApp\...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reroot(&self, base: &RegPath) -> RegPath {
+        base.join(&self.path)
+    }
+}
+
+/// A failure to open or enumerate a key during a walk
+#[derive(Debug)]
+pub struct WalkError {
+    /// Path of the key relative to the key the walk started from
+    pub path: String,
+    pub error: io::Error,
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.error)
+    }
+}
+
+struct Frame {
+    /// `None` for the starting key, which is borrowed, and for keys closed
+    /// to save handles, which are re-opened by `path`
+    key: Option<RegKey>,
+    path: String,
+    depth: usize,
+    index: u32,
+}
+
+/// Iterator over a key tree, returned by `RegKey::walk`
+pub struct Walk<'key> {
+    root: &'key RegKey,
+    options: WalkOptions,
+    /// Keys being enumerated, the current one is the last for a depth-first walk
+    /// and the first for a breadth-first walk
+    frames: VecDeque<Frame>,
+    skipped: Vec<String>,
+    errors: Vec<WalkError>,
+}
+
+impl<'key> Walk<'key> {
+    pub(crate) fn new(root: &'key RegKey, options: WalkOptions) -> Walk<'key> {
+        Walk {
+            root,
+            options,
+            frames: vec![Frame {
+                key: None,
+                path: String::new(),
+                depth: 0,
+                index: 0,
+            }]
+            .into(),
+            skipped: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Paths of the keys left out with `ErrorPolicy::Skip`
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+
+    /// Errors collected with `ErrorPolicy::Collect`
+    pub fn errors(&self) -> &[WalkError] {
+        &self.errors
+    }
+
+    fn breadth_first(&self) -> bool {
+        self.options.order == WalkOrder::BreadthFirst
+    }
+
+    fn pop_current(&mut self) {
+        if self.breadth_first() {
+            self.frames.pop_front();
+        } else {
+            self.frames.pop_back();
+        }
+    }
+
+    /// Close the oldest open handles above the `max_open_handles` limit
+    fn limit_handles(&mut self) {
+        let max = match self.options.max_open_handles {
+            Some(max) => max,
+            None => return,
+        };
+        let mut open = self.frames.iter().filter(|f| f.key.is_some()).count();
+        for frame in self.frames.iter_mut() {
+            if open <= max {
+                break;
+            }
+            if frame.key.take().is_some() {
+                open -= 1;
+            }
+        }
+    }
+
+    /// Advance the walk, errors are returned for the policy to handle
+    fn step(&mut self) -> Option<Result<WalkEntry, WalkError>> {
+        let perms = KEY_READ | self.options.view.flags();
+        loop {
+            let frame = if self.breadth_first() {
+                self.frames.front_mut()?
+            } else {
+                self.frames.back_mut()?
+            };
+            if frame.key.is_none() && frame.depth > 0 {
+                match self.root.open_subkey_with_flags(&frame.path, perms) {
+                    Ok(key) => frame.key = Some(key),
+                    Err(error) => {
+                        let path = frame.path.clone();
+                        self.pop_current();
+                        return Some(Err(WalkError { path, error }));
+                    }
+                }
+            }
+            let parent = frame.key.as_ref().unwrap_or(self.root);
+            let name = match parent.enum_key(frame.index) {
+                None => {
+                    self.pop_current();
+                    continue;
+                }
+                Some(Err(error)) => {
+                    let path = frame.path.clone();
+                    self.pop_current();
+                    return Some(Err(WalkError { path, error }));
+                }
+                Some(Ok(name)) => name,
+            };
+            frame.index += 1;
+            let depth = frame.depth + 1;
+            let path = if frame.path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}\\{}", frame.path, name)
+            };
+            let descend = self.options.max_depth.map_or(true, |max| depth < max);
+            if !descend && !self.options.key_flags {
+                return Some(Ok(WalkEntry {
+                    path,
+                    name,
+                    depth,
+                    flags: None,
+                }));
+            }
+
+            let opened = if self.options.follow_links {
+                parent.open_subkey_with_flags(&name, perms)
+            } else {
+                // opens regular keys as usual and links as link keys
+                parent.open_subkey_nofollow(&name, perms)
+            };
+            let key = match opened {
+                Ok(key) => key,
+                Err(error) => return Some(Err(WalkError { path, error })),
+            };
+            let flags = if self.options.key_flags || !self.options.follow_links {
+                match key.key_flags() {
+                    Ok(flags) => Some(flags),
+                    Err(error) => return Some(Err(WalkError { path, error })),
+                }
+            } else {
+                None
+            };
+            let is_link = flags.map_or(false, |f| f.link);
+            let entry = WalkEntry {
+                path: path.clone(),
+                name,
+                depth,
+                flags: flags.filter(|_| self.options.key_flags),
+            };
+            if descend && (self.options.follow_links || !is_link) {
+                // queued keys of a breadth-first walk are re-opened when their turn comes
+                let key = if self.breadth_first() {
+                    None
+                } else {
+                    Some(key)
+                };
+                self.frames.push_back(Frame {
+                    key,
+                    path,
+                    depth,
+                    index: 0,
+                });
+                self.limit_handles();
+            }
+            return Some(Ok(entry));
+        }
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkEntry>> {
+        loop {
+            match self.step()? {
+                Ok(entry) => return Some(Ok(entry)),
+                Err(error) => match self.options.error_policy {
+                    ErrorPolicy::Abort => {
+                        self.frames.clear();
+                        return Some(Err(error.error));
+                    }
+                    ErrorPolicy::Skip => self.skipped.push(error.path),
+                    ErrorPolicy::Collect => self.errors.push(error),
+                },
+            }
+        }
+    }
+}
+
+/// Result of a parallel walk, returned by `RegKey::par_walk_with_options`.
+/// Part of `rayon` feature.
+#[cfg(feature = "rayon")]
+#[derive(Debug, Default)]
+pub struct ParWalk {
+    /// Keys found, in unspecified order
+    pub entries: Vec<WalkEntry>,
+    /// Paths of the keys left out with `ErrorPolicy::Skip`
+    pub skipped: Vec<String>,
+    /// Errors collected with `ErrorPolicy::Collect`
+    pub errors: Vec<WalkError>,
+}
+
+/// State shared by the tasks of a parallel walk
+#[cfg(feature = "rayon")]
+struct ParContext {
+    options: WalkOptions,
+    result: Mutex<ParWalk>,
+    /// First error of an `ErrorPolicy::Abort` walk, the remaining tasks stop early
+    error: Mutex<Option<io::Error>>,
+    aborted: AtomicBool,
+}
+
+#[cfg(feature = "rayon")]
+impl ParContext {
+    fn aborted(&self) -> bool {
+        self.aborted.load(Ordering::Relaxed)
+    }
+
+    fn fail(&self, path: String, error: io::Error) {
+        match self.options.error_policy {
+            ErrorPolicy::Abort => {
+                self.error.lock().unwrap().get_or_insert(error);
+                self.aborted.store(true, Ordering::Relaxed);
+            }
+            ErrorPolicy::Skip => self.result.lock().unwrap().skipped.push(path),
+            ErrorPolicy::Collect => self
+                .result
+                .lock()
+                .unwrap()
+                .errors
+                .push(WalkError { path, error }),
+        }
+    }
+}
+
+/// Walk `root` on a rayon pool of `threads` threads, the default pool size if 0.
+/// Every subkey is opened once and its handle is moved to the task enumerating it.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_walk(root: &RegKey, threads: usize, options: WalkOptions) -> io::Result<ParWalk> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    // `RegKey` isn't `Sync`, the tasks get a handle of their own
+    let root = root.try_clone()?;
+    let ctx = ParContext {
+        options,
+        result: Mutex::new(ParWalk::default()),
+        error: Mutex::new(None),
+        aborted: AtomicBool::new(false),
+    };
+    pool.scope(|s| par_visit(s, root, String::new(), 0, &ctx));
+    if let Some(e) = ctx.error.into_inner().unwrap() {
+        return Err(e);
+    }
+    Ok(ctx.result.into_inner().unwrap())
+}
+
+/// Report the subkeys of `key` and queue a task for every subkey to descend into
+#[cfg(feature = "rayon")]
+fn par_visit<'s>(
+    s: &rayon::Scope<'s>,
+    key: RegKey,
+    path: String,
+    depth: usize,
+    ctx: &'s ParContext,
+) {
+    let options = &ctx.options;
+    let perms = KEY_READ | options.view.flags();
+    for name in key.enum_keys() {
+        if ctx.aborted() {
+            return;
+        }
+        let name = match name {
+            Ok(name) => name,
+            Err(e) => return ctx.fail(path, e),
+        };
+        let child_path = if path.is_empty() {
+            name.clone()
+        } else {
+            format!("{}\\{}", path, name)
+        };
+        let child_depth = depth + 1;
+        let descend = options.max_depth.map_or(true, |max| child_depth < max);
+        let mut entry = WalkEntry {
+            path: child_path,
+            name,
+            depth: child_depth,
+            flags: None,
+        };
+        if !descend && !options.key_flags {
+            ctx.result.lock().unwrap().entries.push(entry);
+            continue;
+        }
+        let opened = if options.follow_links {
+            key.open_subkey_with_flags(&entry.name, perms)
+        } else {
+            key.open_subkey_nofollow(&entry.name, perms)
+        };
+        let flags = opened.and_then(|child| {
+            if options.key_flags || !options.follow_links {
+                child.key_flags().map(|flags| (child, Some(flags)))
+            } else {
+                Ok((child, None))
+            }
+        });
+        let (child, flags) = match flags {
+            Ok(opened) => opened,
+            Err(e) => {
+                ctx.fail(entry.path, e);
+                continue;
+            }
+        };
+        let is_link = flags.map_or(false, |f| f.link);
+        entry.flags = flags.filter(|_| options.key_flags);
+        if descend && (options.follow_links || !is_link) {
+            let child_path = entry.path.clone();
+            s.spawn(move |s| par_visit(s, child, child_path, child_depth, ctx));
+        }
+        ctx.result.lock().unwrap().entries.push(entry);
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Watching a key tree for changes of individual values and subkeys.
+//!
+//! Change notifications only tell that something changed, so `RegWatcher`
+//! keeps a snapshot of the tree and compares it with a new one on every
+//! notification.
+//!
+//!```no_run
+//!use std::io;
+//!use winreg2::RegKey;
+//!use winreg2::enums::*;
+//!use winreg2::watcher::{RegWatcher, WatchEvent};
+//!
+//!fn main() -> io::Result<()> {
+//!    let app = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\MyApp")?;
+//!    let mut watcher = RegWatcher::new(&app)?;
+//!    loop {
+//!        for event in watcher.wait(None)? {
+//!            if let WatchEvent::ValueSet { path, name, new, .. } = event {
+//!                println!("{}\\{} = {}", path, name, new);
+//!            }
+//!        }
+//!    }
+//!}
+//!```
+use crate::diff::{copy_value, read_tree};
+use crate::enums::*;
+use crate::notify::ChangeEvent;
+use crate::{RegKey, RegValue};
+use std::collections::BTreeMap;
+use std::io;
+use std::mem;
+use std::time::Duration;
+use windows_sys::Win32::System::Registry;
+
+/// A change found by `RegWatcher`.
+/// Key paths are relative to the watched key, which has an empty path.
+#[derive(Debug, PartialEq)]
+pub enum WatchEvent {
+    /// Value `name` in `path` was created (`old` is `None`) or modified
+    ValueSet {
+        path: String,
+        name: String,
+        old: Option<RegValue>,
+        new: RegValue,
+    },
+    /// Value `name` was deleted from `path`
+    ValueDeleted {
+        path: String,
+        name: String,
+        old: RegValue,
+    },
+    /// Key `path` was created, its values follow as `ValueSet` events
+    KeyCreated { path: String },
+    /// Key `path` was deleted along with its values
+    KeyDeleted { path: String },
+}
+
+type Snapshot = BTreeMap<String, BTreeMap<String, RegValue>>;
+
+/// Watcher of a key and all its subkeys
+pub struct RegWatcher {
+    key: RegKey,
+    snapshot: Snapshot,
+}
+
+impl RegWatcher {
+    /// Take the initial snapshot of `key`'s tree, the key is re-opened with
+    /// `KEY_READ | KEY_NOTIFY`
+    pub fn new(key: &RegKey) -> io::Result<RegWatcher> {
+        let key = key.reopen_with_flags(KEY_READ | KEY_NOTIFY)?;
+        let snapshot = read_tree(&key)?;
+        Ok(RegWatcher { key, snapshot })
+    }
+
+    /// Wait for changes, at most `timeout` if given. Returns the changes since
+    /// the previous call, an empty list on timeout.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Vec<WatchEvent>> {
+        // armed before the rescan, so changes made during it wake the wait
+        let event = ChangeEvent::new(
+            &self.key,
+            true,
+            Registry::REG_NOTIFY_CHANGE_NAME | Registry::REG_NOTIFY_CHANGE_LAST_SET,
+        )?;
+        let events = rescan(&self.key, &mut self.snapshot)?;
+        if !events.is_empty() || !event.wait(timeout)? {
+            return Ok(events);
+        }
+        rescan(&self.key, &mut self.snapshot)
+    }
+
+    /// Compare the tree with the snapshot without waiting, and update the snapshot
+    pub fn rescan(&mut self) -> io::Result<Vec<WatchEvent>> {
+        rescan(&self.key, &mut self.snapshot)
+    }
+}
+
+fn rescan(key: &RegKey, snapshot: &mut Snapshot) -> io::Result<Vec<WatchEvent>> {
+    let new = read_tree(key)?;
+    let old = mem::replace(snapshot, new);
+    Ok(diff(old, snapshot))
+}
+
+fn diff(mut old: Snapshot, new: &Snapshot) -> Vec<WatchEvent> {
+    let mut events = Vec::new();
+    for (path, new_values) in new {
+        let mut old_values = match old.remove(path) {
+            Some(values) => values,
+            None => {
+                events.push(WatchEvent::KeyCreated { path: path.clone() });
+                BTreeMap::new()
+            }
+        };
+        for (name, value) in new_values {
+            let old = old_values.remove(name);
+            if old.as_ref() != Some(value) {
+                events.push(WatchEvent::ValueSet {
+                    path: path.clone(),
+                    name: name.clone(),
+                    old,
+                    new: copy_value(value),
+                });
+            }
+        }
+        for (name, old) in old_values {
+            events.push(WatchEvent::ValueDeleted {
+                path: path.clone(),
+                name,
+                old,
+            });
+        }
+    }
+    for (path, _values) in old {
+        events.push(WatchEvent::KeyDeleted { path });
+    }
+    events
+}
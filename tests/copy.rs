@@ -0,0 +1,55 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use windows_sys::Win32::Foundation;
+use winreg2::copy::{CopyOptions, OnExisting};
+use winreg2::enums::*;
+
+mod common;
+
+#[test]
+fn test_copy_on_existing() {
+    with_key!(key, "CopyOnExisting" => {
+        let (src, _disp) = key.create_subkey("Src").unwrap();
+        src.set_value("Kept", &1u32).unwrap();
+        src.set_value("New", &2u32).unwrap();
+        let (dst, _disp) = key.create_subkey("Dst").unwrap();
+        dst.set_value("Kept", &10u32).unwrap();
+
+        let options = CopyOptions::new().on_existing(OnExisting::Error);
+        let err = key.copy_tree_with_options("Src", &dst, options).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_ALREADY_EXISTS as i32));
+
+        let options = CopyOptions::new().on_existing(OnExisting::Skip);
+        key.copy_tree_with_options("Src", &dst, options).unwrap();
+        assert_eq!(dst.get_value::<u32, _>("Kept").unwrap(), 10);
+        assert_eq!(dst.get_value::<u32, _>("New").unwrap(), 2);
+
+        key.copy_tree_with_options("Src", &dst, CopyOptions::new()).unwrap();
+        assert_eq!(dst.get_value::<u32, _>("Kept").unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_copy_progress_and_security() {
+    with_key!(key, "CopyProgress" => {
+        key.create_subkey("Src\\A\\B").unwrap();
+        key.create_subkey("Src\\C").unwrap();
+        key.open_subkey_with_flags("Src\\C", KEY_ALL_ACCESS)
+            .unwrap()
+            .set_sddl("D:P(A;;KA;;;WD)")
+            .unwrap();
+        let (dst, _disp) = key.create_subkey("Dst").unwrap();
+        let mut paths = Vec::new();
+        let options = CopyOptions::new()
+            .security(true)
+            .progress(|path| paths.push(path.to_owned()));
+        key.copy_tree_with_options("Src", &dst, options).unwrap();
+        paths.sort();
+        assert_eq!(paths, vec!["", "A", "A\\B", "C"]);
+        let copied = dst.open_subkey("C").unwrap().get_sddl().unwrap();
+        assert!(copied.contains("D:P(A;;KA;;;WD)"), "{}", copied);
+    });
+}
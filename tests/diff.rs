@@ -0,0 +1,50 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use winreg2::diff::{self, ValueDiff};
+
+mod common;
+
+#[test]
+fn test_diff_keys() {
+    with_key!(key, "DiffKeys" => {
+        let (a, _disp) = key.create_subkey("A").unwrap();
+        let (b, _disp) = key.create_subkey("B").unwrap();
+        assert!(diff::diff_keys(&a, &b).unwrap().is_empty());
+
+        a.set_value("Same", &1u32).unwrap();
+        b.set_value("Same", &1u32).unwrap();
+        a.set_value("Version", &1u32).unwrap();
+        b.set_value("Version", &2u32).unwrap();
+        a.create_subkey("Old").unwrap().0.set_value("Name", &"old").unwrap();
+        b.create_subkey("New\\Empty").unwrap();
+
+        let diff = diff::diff_keys(&a, &b).unwrap();
+        assert_eq!(diff.added_keys, vec!["New", "New\\Empty"]);
+        assert_eq!(diff.removed_keys, vec!["Old"]);
+        assert_eq!(diff.values.len(), 2);
+        match &diff.values[0] {
+            ValueDiff::Changed { key, name, .. } => assert_eq!((key.as_str(), name.as_str()), ("", "Version")),
+            other => panic!("unexpected {:?}", other),
+        }
+        match &diff.values[1] {
+            ValueDiff::Removed { key, name, .. } => assert_eq!((key.as_str(), name.as_str()), ("Old", "Name")),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let text = diff.to_unified("a", "b");
+        assert_eq!(
+            text,
+            "--- a\n+++ b\n\
+             @@ [] @@\n\
+             -\"Version\"=dword:00000001\n\
+             +\"Version\"=dword:00000002\n\
+             -[Old]\n\
+             -\"Name\"=\"old\"\n\
+             +[New]\n\
+             +[New\\Empty]\n"
+        );
+    });
+}
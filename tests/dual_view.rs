@@ -0,0 +1,31 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use winreg2::dual_view;
+use winreg2::enums::*;
+use winreg2::RegKey;
+
+#[test]
+fn test_write_both() {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let path = "Software\\WinRegRsTestDualView";
+    let mut calls = 0;
+    let results = dual_view::write_both(&hkcu, path, |key| {
+        calls += 1;
+        key.set_value("Value", &1u32)
+    });
+    assert_eq!(results.v64.is_some(), dual_view::is_64bit_os());
+    results.into_result().unwrap();
+    assert_eq!(calls, if dual_view::is_64bit_os() { 2 } else { 1 });
+    assert_eq!(
+        hkcu.open_subkey(path)
+            .unwrap()
+            .get_value::<u32, _>("Value")
+            .unwrap(),
+        1
+    );
+    dual_view::delete_both(&hkcu, path).into_result().unwrap();
+    assert!(hkcu.open_subkey(path).is_err());
+}
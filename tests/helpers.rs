@@ -0,0 +1,383 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use std::io;
+use winreg2::enums::*;
+use winreg2::helpers::app_paths::{self, Capabilities};
+use winreg2::helpers::com::{self, ThreadingModel};
+use winreg2::helpers::context_menu::{self, Target, Verb};
+use winreg2::helpers::env;
+use winreg2::helpers::eventlog;
+use winreg2::helpers::fonts;
+use winreg2::helpers::installed_programs::{self, InstallDate};
+use winreg2::helpers::network::{self, MountPoint, MountPointKind};
+use winreg2::helpers::office_addin::{self, LoadBehavior};
+use winreg2::helpers::pending_reboot;
+use winreg2::helpers::proxy::ProxyServer;
+use winreg2::helpers::security_audit::{self, Severity, UacSettings};
+use winreg2::helpers::services::{self, FailureAction, FailureActionType, FailureActions};
+use winreg2::helpers::shell_folders::{self, Folder};
+use winreg2::helpers::uninstall_entry::{UninstallEntry, UNINSTALL_PATH};
+use winreg2::helpers::windows_version::{self, Version};
+use winreg2::helpers::Scope;
+use winreg2::RegKey;
+
+#[test]
+fn test_uninstall_entry() {
+    let entry = UninstallEntry::new("WinRegRsTestUninstallEntry", "WinRegRs Test")
+        .scope(Scope::CurrentUser)
+        .display_version("1.0.0")
+        .estimated_size(42)
+        .no_modify(true);
+    entry.register().unwrap();
+    assert!(entry.exists().unwrap());
+    let key = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(format!("{}\\{}", UNINSTALL_PATH, entry.id()))
+        .unwrap();
+    let version: String = key.get_value("DisplayVersion").unwrap();
+    let size: u32 = key.get_value("EstimatedSize").unwrap();
+    let no_modify: u32 = key.get_value("NoModify").unwrap();
+    assert_eq!(version, "1.0.0");
+    assert_eq!(size, 42);
+    assert_eq!(no_modify, 1);
+
+    let entry = entry.no_modify(false);
+    entry.update().unwrap();
+    assert!(key.get_raw_value("NoModify").is_err());
+
+    entry.remove().unwrap();
+    assert!(!entry.exists().unwrap());
+}
+
+#[test]
+fn test_installed_programs() {
+    let entry = UninstallEntry::new("WinRegRsTestInstalledPrograms", "WinRegRs Installed Test")
+        .scope(Scope::CurrentUser)
+        .publisher("WinRegRs");
+    entry.register().unwrap();
+    let found = installed_programs::list()
+        .into_iter()
+        .find(|p| p.id == entry.id())
+        .unwrap();
+    assert_eq!(found.display_name, "WinRegRs Installed Test");
+    assert_eq!(found.publisher.as_deref(), Some("WinRegRs"));
+    assert_eq!(found.scope, Scope::CurrentUser);
+    entry.remove().unwrap();
+}
+
+#[test]
+fn test_install_date_parse() {
+    let date = InstallDate::parse("20230131").unwrap();
+    assert_eq!((date.year, date.month, date.day), (2023, 1, 31));
+    assert!(InstallDate::parse("2023-01-31").is_none());
+    assert!(InstallDate::parse("20231301").is_none());
+}
+
+#[test]
+fn test_env_path() {
+    let dir = "C:\\WinRegRsTestEnvPath";
+    let original = env::get(Scope::CurrentUser, "Path").unwrap_or_default();
+    assert!(env::append_to_path(Scope::CurrentUser, dir).unwrap());
+    assert!(!env::append_to_path(Scope::CurrentUser, "c:\\winregrstestenvpath\\").unwrap());
+    let path = env::get(Scope::CurrentUser, "Path").unwrap();
+    assert_eq!(env::split_path(&path).last(), Some(&dir));
+    assert!(env::remove_from_path(Scope::CurrentUser, dir).unwrap());
+    assert_eq!(
+        env::get(Scope::CurrentUser, "Path").unwrap_or_default(),
+        original
+    );
+}
+
+#[test]
+fn test_services_read() {
+    let names = services::list().unwrap();
+    assert!(names.iter().any(|n| n.eq_ignore_ascii_case("EventLog")));
+    let event_log = services::read("EventLog").unwrap();
+    assert!(event_log.start_type.is_some());
+    assert!(event_log.image_path.is_some());
+}
+
+#[test]
+fn test_failure_actions_bytes() {
+    let actions = FailureActions {
+        reset_period: 86400,
+        actions: vec![
+            FailureAction {
+                action: FailureActionType::Restart,
+                delay_ms: 60000,
+            },
+            FailureAction {
+                action: FailureActionType::None,
+                delay_ms: 0,
+            },
+        ],
+    };
+    let bytes = actions.to_bytes();
+    assert_eq!(bytes.len(), 36);
+    assert_eq!(FailureActions::from_bytes(&bytes).unwrap(), actions);
+    assert!(FailureActions::from_bytes(&bytes[..30]).is_err());
+}
+
+#[test]
+fn test_app_paths() {
+    let exe = "winregrstestapppaths.exe";
+    let full_path = "C:\\WinRegRsTest\\winregrstestapppaths.exe";
+    app_paths::register_in(
+        Scope::CurrentUser,
+        exe,
+        full_path,
+        &["C:\\WinRegRsTest\\lib"],
+    )
+    .unwrap();
+    assert_eq!(app_paths::lookup(exe).unwrap(), full_path);
+    app_paths::unregister_in(Scope::CurrentUser, exe).unwrap();
+    assert!(app_paths::lookup(exe).is_err());
+}
+
+#[test]
+fn test_capabilities() {
+    let caps = Capabilities::new(
+        "WinRegRsTestApp",
+        "Software\\WinRegRsTestCapabilities\\Capabilities",
+    )
+    .description("test")
+    .file_association(".winregrstest", "WinRegRsTest.Document");
+    caps.register(Scope::CurrentUser).unwrap();
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let registered: String = hkcu
+        .open_subkey("Software\\RegisteredApplications")
+        .unwrap()
+        .get_value("WinRegRsTestApp")
+        .unwrap();
+    assert_eq!(
+        registered,
+        "Software\\WinRegRsTestCapabilities\\Capabilities"
+    );
+    let prog_id: String = hkcu
+        .open_subkey(format!("{}\\FileAssociations", registered))
+        .unwrap()
+        .get_value(".winregrstest")
+        .unwrap();
+    assert_eq!(prog_id, "WinRegRsTest.Document");
+    caps.unregister(Scope::CurrentUser).unwrap();
+    hkcu.delete_subkey_all("Software\\WinRegRsTestCapabilities")
+        .unwrap();
+}
+
+#[test]
+fn test_context_menu() {
+    let target = Target::ProgId("WinRegRsTest.Document".to_owned());
+    Verb::new("WinRegRsTestVerb", "notepad.exe \"%1\"")
+        .label("WinRegRs Test")
+        .extended(true)
+        .register(Scope::CurrentUser, &target)
+        .unwrap();
+    assert_eq!(
+        context_menu::list(Scope::CurrentUser, &target).unwrap(),
+        vec!["WinRegRsTestVerb".to_owned()]
+    );
+    let command: String = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Software\\Classes\\WinRegRsTest.Document\\shell\\WinRegRsTestVerb\\command")
+        .unwrap()
+        .get_value("")
+        .unwrap();
+    assert_eq!(command, "notepad.exe \"%1\"");
+    context_menu::remove(Scope::CurrentUser, &target, "WinRegRsTestVerb").unwrap();
+    RegKey::predef(HKEY_CURRENT_USER)
+        .delete_subkey_all("Software\\Classes\\WinRegRsTest.Document")
+        .unwrap();
+}
+
+#[test]
+fn test_windows_version() {
+    let v = windows_version::current().unwrap();
+    assert!(v.version >= Version::new(6, 1, 7600, 0));
+    assert!(!v.product_name.is_empty());
+    assert!(Version::new(10, 0, 22631, 1) > Version::new(10, 0, 19045, 3803));
+    assert_eq!(
+        Version::new(10, 0, 19045, 3803).to_string(),
+        "10.0.19045.3803"
+    );
+}
+
+#[test]
+fn test_com_inproc_server() {
+    let clsid = "5d3c1f9e-7b2a-4c8e-9f10-2a3b4c5d6e7f";
+    let prog_id = "WinRegRsTest.ComServer.1";
+    com::register_inproc_server(
+        Scope::CurrentUser,
+        false,
+        clsid,
+        "C:\\WinRegRsTest\\server.dll",
+        ThreadingModel::Apartment,
+        Some(prog_id),
+    )
+    .unwrap();
+    let classes = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Software\\Classes")
+        .unwrap();
+    let server = classes
+        .open_subkey("CLSID\\{5D3C1F9E-7B2A-4C8E-9F10-2A3B4C5D6E7F}\\InprocServer32")
+        .unwrap();
+    let model: String = server.get_value("ThreadingModel").unwrap();
+    assert_eq!(model, "Apartment");
+    let owner: String = classes
+        .open_subkey(format!("{}\\CLSID", prog_id))
+        .unwrap()
+        .get_value("")
+        .unwrap();
+    assert_eq!(owner, "{5D3C1F9E-7B2A-4C8E-9F10-2A3B4C5D6E7F}");
+
+    com::unregister_server(Scope::CurrentUser, false, clsid, Some(prog_id)).unwrap();
+    assert!(classes.open_subkey(prog_id).is_err());
+    assert!(com::register_inproc_server(
+        Scope::CurrentUser,
+        false,
+        "not-a-guid",
+        "x.dll",
+        ThreadingModel::Both,
+        None
+    )
+    .is_err());
+}
+
+#[test]
+fn test_proxy_server_parse() {
+    let single = ProxyServer::parse("proxy:8080");
+    assert_eq!(single.for_protocol("https"), Some("proxy:8080"));
+    assert_eq!(single.to_string(), "proxy:8080");
+
+    let multi = ProxyServer::parse("http=web:80; HTTPS=secure:443;;");
+    assert_eq!(multi.for_protocol("http"), Some("web:80"));
+    assert_eq!(multi.for_protocol("https"), Some("secure:443"));
+    assert_eq!(multi.for_protocol("ftp"), None);
+    assert_eq!(multi.to_string(), "http=web:80;https=secure:443");
+}
+
+#[test]
+fn test_pending_reboot() {
+    let reasons = pending_reboot::check().unwrap();
+    assert_eq!(
+        reasons.is_pending(),
+        reasons.component_based_servicing
+            || reasons.windows_update
+            || reasons.update_exe_volatile
+            || !reasons.file_rename_operations.is_empty()
+            || reasons.computer_rename
+    );
+
+    let ops = pending_reboot::parse_file_rename_operations(&[
+        "\\??\\C:\\a.tmp".to_owned(),
+        "".to_owned(),
+        "\\??\\C:\\b.tmp".to_owned(),
+        "!\\??\\C:\\b.dll".to_owned(),
+        "\\??\\C:\\c.tmp".to_owned(),
+    ]);
+    assert_eq!(ops.len(), 3);
+    assert_eq!(ops[0].destination, None);
+    assert_eq!(ops[1].destination.as_deref(), Some("!\\??\\C:\\b.dll"));
+    assert_eq!(ops[2].destination, None);
+}
+
+#[test]
+fn test_security_audit() {
+    let findings = security_audit::audit().unwrap();
+    assert!(findings.windows(2).all(|w| w[0].severity >= w[1].severity));
+
+    let mut findings = Vec::new();
+    let uac = UacSettings {
+        enable_lua: Some(false),
+        consent_prompt_behavior_admin: Some(5),
+        ..Default::default()
+    };
+    security_audit::audit_uac(&uac, &mut findings);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::High);
+    assert!(findings[0].location.ends_with("\\EnableLUA"));
+}
+
+#[test]
+fn test_shell_folders() {
+    let documents = shell_folders::get(&Folder::Documents).unwrap();
+    assert!(documents.is_absolute());
+    assert!(!documents.to_string_lossy().contains('%'));
+    let unexpanded = shell_folders::get_unexpanded(&Folder::Documents).unwrap();
+    assert!(!unexpanded.is_empty());
+}
+
+#[test]
+fn test_network() {
+    for drive in network::mapped_drives().unwrap() {
+        assert!(drive.letter.is_ascii_uppercase());
+        assert!(!drive.remote_path.is_empty());
+    }
+    network::mount_points().unwrap();
+    network::map_network_drive_mru().unwrap();
+
+    assert_eq!(
+        MountPoint::from_key_name("##server#share").kind,
+        MountPointKind::Network("\\\\server\\share".to_owned())
+    );
+    assert_eq!(
+        MountPoint::from_key_name("{0b5c5c1e-0000-0000-0000-100000000000}").kind,
+        MountPointKind::Volume("{0b5c5c1e-0000-0000-0000-100000000000}".to_owned())
+    );
+}
+
+#[test]
+fn test_fonts_unregister_missing() {
+    let err =
+        fonts::unregister("WinRegRsTest Missing Font (TrueType)", Scope::CurrentUser).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_office_addin() {
+    let app = "WinRegRsTestApp";
+    office_addin::register(
+        Scope::CurrentUser,
+        false,
+        app,
+        "WinRegRsTest.Addin",
+        "Test Add-in",
+        LoadBehavior::Startup,
+        Some("C:\\Test\\Addin.vsto"),
+    )
+    .unwrap();
+    let key = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(format!(
+            "{}\\{}\\Addins\\WinRegRsTest.Addin",
+            office_addin::OFFICE_PATH,
+            app
+        ))
+        .unwrap();
+    assert_eq!(key.get_raw_value("LoadBehavior").unwrap().vtype, REG_DWORD);
+
+    let addin = office_addin::read(Scope::CurrentUser, false, app, "WinRegRsTest.Addin").unwrap();
+    assert_eq!(addin.load_behavior, Some(LoadBehavior::Startup));
+    assert_eq!(
+        addin.manifest.as_deref(),
+        Some("C:\\Test\\Addin.vsto|vstolocal")
+    );
+    assert_eq!(
+        office_addin::list(Scope::CurrentUser, false, app)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    office_addin::unregister(Scope::CurrentUser, false, app, "WinRegRsTest.Addin").unwrap();
+    assert!(office_addin::list(Scope::CurrentUser, false, app)
+        .unwrap()
+        .is_empty());
+    RegKey::predef(HKEY_CURRENT_USER)
+        .delete_subkey_all(format!("{}\\{}", office_addin::OFFICE_PATH, app))
+        .unwrap();
+}
+
+#[test]
+fn test_eventlog_source_exists() {
+    assert!(!eventlog::source_exists("Application", "WinRegRsTest Missing Source").unwrap());
+}
@@ -0,0 +1,136 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use tempfile::tempdir;
+use windows_sys::Win32::Foundation;
+use winreg2::enums::*;
+use winreg2::hive::Hive;
+use winreg2::types::ToRegValue;
+use winreg2::{RegKey, RegValue};
+
+#[test]
+fn test_read_hive() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("RustHiveTest.dat");
+    let big = RegValue {
+        bytes: (0..40000u32).map(|i| i as u8).collect(),
+        vtype: REG_BINARY,
+    };
+    {
+        let key = RegKey::load_app_key(&file_path, true).unwrap();
+        key.set_value("", &"default").unwrap();
+        key.set_value("Dword", &42u32).unwrap();
+        key.set_value("Ünicode", &"text").unwrap();
+        key.set_raw_value(
+            "Small",
+            &RegValue {
+                bytes: vec![1, 2],
+                vtype: REG_BINARY,
+            },
+        )
+        .unwrap();
+        key.set_raw_value("Big", &big).unwrap();
+        for name in &["One", "Two", "Three"] {
+            let (sub, _disp) = key.create_subkey(name).unwrap();
+            sub.set_value("Name", name).unwrap();
+        }
+        key.create_subkey("Two\\Nested").unwrap();
+    }
+
+    let hive = Hive::open(&file_path).unwrap();
+    let root = hive.root().unwrap();
+    assert_eq!(root.get_value::<String, _>("").unwrap(), "default");
+    assert_eq!(root.get_value::<u32, _>("dword").unwrap(), 42);
+    assert_eq!(root.get_value::<String, _>("Ünicode").unwrap(), "text");
+    assert_eq!(root.get_raw_value("Small").unwrap().bytes, vec![1, 2]);
+    assert_eq!(root.get_raw_value("Big").unwrap(), big);
+    assert_eq!(
+        root.get_raw_value("Missing").unwrap_err().raw_os_error(),
+        Some(Foundation::ERROR_FILE_NOT_FOUND as i32)
+    );
+    assert_eq!(root.enum_values().count(), 5);
+    assert_eq!(
+        root.enum_values()
+            .find(|v| v.as_ref().unwrap().0 == "Dword")
+            .unwrap()
+            .unwrap()
+            .1,
+        42u32.to_reg_value()
+    );
+
+    let mut names = root
+        .enum_keys()
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    names.sort();
+    assert_eq!(names, vec!["One", "Three", "Two"]);
+    let nested = root.open_subkey("two\\NESTED").unwrap();
+    assert_eq!(nested.name(), "Nested");
+    assert_eq!(nested.enum_keys().count(), 0);
+    assert_eq!(
+        root.open_subkey("One")
+            .unwrap()
+            .get_value::<String, _>("Name")
+            .unwrap(),
+        "One"
+    );
+    assert!(root.open_subkey("Four").is_err());
+}
+
+#[test]
+fn test_not_a_hive() {
+    let err = Hive::from_bytes(vec![0; 8192]).err().unwrap();
+    assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_BADDB as i32));
+}
+
+/// A hive with only a root key named `Root`, `patch` adjusts the data of its `nk` cell
+fn fake_hive<F: FnOnce(&mut [u8])>(patch: F) -> Hive {
+    const ROOT: usize = 0x20;
+    let mut data = vec![0u8; 0x2000];
+    data[..4].copy_from_slice(b"regf");
+    data[0x24..0x28].copy_from_slice(&(ROOT as u32).to_le_bytes());
+    let cell = 0x1000 + ROOT;
+    data[cell..cell + 4].copy_from_slice(&(-0x58i32).to_le_bytes());
+    let nk = &mut data[cell + 4..cell + 0x58];
+    nk[..2].copy_from_slice(b"nk");
+    nk[0x02..0x04].copy_from_slice(&0x20u16.to_le_bytes());
+    for offset in &[0x1c, 0x28, 0x30] {
+        nk[*offset..*offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    }
+    nk[0x48..0x4a].copy_from_slice(&4u16.to_le_bytes());
+    nk[0x4c..0x50].copy_from_slice(b"Root");
+    patch(nk);
+    Hive::from_bytes(data).unwrap()
+}
+
+#[test]
+fn test_corrupt_hive() {
+    let hive = fake_hive(|_| {});
+    let root = hive.root().unwrap();
+    assert_eq!(root.name(), "Root");
+    assert_eq!(root.enum_keys().count(), 0);
+    assert_eq!(root.enum_values().count(), 0);
+
+    // counts far beyond the lists
+    let hive = fake_hive(|nk| {
+        nk[0x14..0x18].copy_from_slice(&u32::MAX.to_le_bytes());
+        nk[0x1c..0x20].copy_from_slice(&0x100u32.to_le_bytes());
+        nk[0x24..0x28].copy_from_slice(&u32::MAX.to_le_bytes());
+        nk[0x28..0x2c].copy_from_slice(&0x100u32.to_le_bytes());
+    });
+    let root = hive.root().unwrap();
+    let err = root.enum_keys().next().unwrap().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_BADDB as i32));
+    let err = root.enum_values().next().unwrap().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_BADDB as i32));
+
+    // a list offset past the end of the address space
+    let hive = fake_hive(|nk| {
+        nk[0x14..0x18].copy_from_slice(&1u32.to_le_bytes());
+        nk[0x1c..0x20].copy_from_slice(&(u32::MAX - 1).to_le_bytes());
+    });
+    let err = hive.root().unwrap().open_subkey("Sub").err().unwrap();
+    assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_BADDB as i32));
+}
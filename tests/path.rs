@@ -0,0 +1,161 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use winreg2::enums::*;
+use winreg2::path::{self, Pattern, RegPath, Violation};
+use winreg2::RegKey;
+
+#[test]
+fn test_parse() {
+    let path = RegPath::parse("HKEY_LOCAL_MACHINE\\SOFTWARE\\\\Vendor\\");
+    assert_eq!(path.root(), Some(HKEY_LOCAL_MACHINE));
+    assert_eq!(path.components(), ["SOFTWARE", "Vendor"]);
+    assert_eq!(path.to_string(), "HKEY_LOCAL_MACHINE\\SOFTWARE\\Vendor");
+
+    let short = RegPath::parse("hkcu\\Software");
+    assert_eq!(short.root(), Some(HKEY_CURRENT_USER));
+    assert_eq!(short.root_name(), Some("HKEY_CURRENT_USER"));
+
+    let regedit = RegPath::parse("Computer\\HKEY_USERS\\.DEFAULT");
+    assert_eq!(
+        regedit.to_parts(),
+        Some((HKEY_USERS, ".DEFAULT".to_owned()))
+    );
+
+    let relative = RegPath::parse("\\Software\\a/b");
+    assert!(!relative.is_absolute());
+    assert_eq!(relative.components(), ["Software", "a/b"]);
+    assert_eq!(relative.to_parts(), None);
+}
+
+#[test]
+fn test_eq_ignore_case() {
+    use std::collections::HashSet;
+
+    let a = RegPath::parse("HKLM\\Software\\Vendor");
+    let b = RegPath::parse("HKEY_LOCAL_MACHINE\\SOFTWARE\\vendor");
+    assert_eq!(a, b);
+    assert_ne!(a, RegPath::parse("HKCU\\Software\\Vendor"));
+    assert_ne!(a, RegPath::parse("HKLM\\Software"));
+    assert_ne!(a, RegPath::parse("Software\\Vendor"));
+    let set: HashSet<RegPath> = vec![a, b].into_iter().collect();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn test_from_parts() {
+    let path = RegPath::from_parts(HKEY_CURRENT_USER, "Software").unwrap();
+    assert_eq!(path.to_string(), "HKEY_CURRENT_USER\\Software");
+    let hkey = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Software")
+        .unwrap();
+    assert_eq!(RegPath::from_parts(hkey.raw_handle(), "Vendor"), None);
+}
+
+#[test]
+fn test_join_parent() {
+    let base = RegPath::from_parts(HKEY_LOCAL_MACHINE, "SOFTWARE").unwrap();
+    let app = base.join("Vendor\\\\App");
+    assert_eq!(app.relative(), "SOFTWARE\\Vendor\\App");
+    assert_eq!(app.name(), Some("App"));
+    assert!(app.starts_with(&RegPath::parse("HKLM\\software")));
+    assert!(!base.starts_with(&app));
+
+    let parent = app.parent().unwrap();
+    assert_eq!(parent.to_string(), "HKEY_LOCAL_MACHINE\\SOFTWARE\\Vendor");
+    let root = RegPath::parse("HKLM");
+    assert_eq!(root.to_string(), "HKEY_LOCAL_MACHINE");
+    assert_eq!(root.parent(), None);
+    assert_eq!(root.name(), None);
+}
+
+#[test]
+fn test_validate() {
+    assert!(path::validate("HKLM\\SOFTWARE\\Vendor").is_empty());
+    let long = "a".repeat(path::MAX_KEY_NAME_LEN + 1);
+    assert_eq!(
+        path::validate(&format!("Software\\{}\\a\0b", long)),
+        [
+            Violation::KeyNameTooLong {
+                index: 1,
+                len: path::MAX_KEY_NAME_LEN + 1
+            },
+            Violation::EmbeddedNul { index: Some(2) },
+        ]
+    );
+    let deep = vec!["k"; path::MAX_KEY_DEPTH + 1].join("\\");
+    assert_eq!(
+        path::validate(&deep),
+        [Violation::TooDeep {
+            depth: path::MAX_KEY_DEPTH + 1
+        }]
+    );
+
+    assert!(path::validate_value_name("").is_empty());
+    let long = "v".repeat(path::MAX_VALUE_NAME_LEN + 1);
+    assert_eq!(
+        path::validate_value_name(&long),
+        [Violation::ValueNameTooLong {
+            len: path::MAX_VALUE_NAME_LEN + 1
+        }]
+    );
+    assert_eq!(
+        path::validate_value_name("a\0"),
+        [Violation::EmbeddedNul { index: None }]
+    );
+}
+
+#[test]
+fn test_root_names() {
+    assert_eq!(root_name(HKEY_LOCAL_MACHINE), Some("HKEY_LOCAL_MACHINE"));
+    assert_eq!(
+        root_name(HKEY_CURRENT_USER_LOCAL_SETTINGS),
+        Some("HKEY_CURRENT_USER_LOCAL_SETTINGS")
+    );
+    assert_eq!(root_from_name("HKLM"), Some(HKEY_LOCAL_MACHINE));
+    assert_eq!(root_from_name("hkey_users"), Some(HKEY_USERS));
+    assert_eq!(root_from_name("Hkcr"), Some(HKEY_CLASSES_ROOT));
+    assert_eq!(root_from_name("SOFTWARE"), None);
+}
+
+#[test]
+fn test_pattern() {
+    let pattern = Pattern::new("Software\\*\\App?");
+    assert!(pattern.matches("software\\Vendor\\APP1"));
+    assert!(!pattern.matches("Software\\Vendor\\App"));
+    assert!(!pattern.matches("Software\\A\\B\\App1"));
+    assert!(!pattern.matches("HKCU\\Software\\Vendor\\App1"));
+
+    let deep = Pattern::new("HKLM\\**\\Run");
+    assert!(deep.matches("HKLM\\Run"));
+    assert!(deep.matches("HKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Run"));
+    assert!(!deep.matches("HKLM\\SOFTWARE\\RunOnce"));
+    assert!(!deep.matches("HKCU\\Run"));
+
+    let star = Pattern::new("a*b*c");
+    assert!(star.matches("abc"));
+    assert!(star.matches("aXbYbZc"));
+    assert!(!star.matches("aXbYcZ"));
+    assert!(Pattern::new("**").matches(""));
+    assert!(Pattern::new("**").matches("a\\b"));
+    assert_eq!(star.to_string(), "a*b*c");
+}
+
+#[test]
+fn test_reroot() {
+    let from = RegPath::parse("HKCU\\Software\\App");
+    let to = RegPath::parse("HKLM\\SOFTWARE\\App");
+    let key = RegPath::parse("HKCU\\Software\\app\\Settings\\Window");
+    assert_eq!(
+        key.strip_prefix(&from).unwrap().relative(),
+        "Settings\\Window"
+    );
+    assert_eq!(
+        key.reroot(&from, &to).unwrap().to_string(),
+        "HKEY_LOCAL_MACHINE\\SOFTWARE\\App\\Settings\\Window"
+    );
+    assert_eq!(to.strip_prefix(&from), None);
+    assert_eq!(from.reroot(&from, &to), Some(to));
+}
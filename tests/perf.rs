@@ -0,0 +1,132 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use std::mem;
+use std::slice;
+use windows_sys::Win32::System::Performance::*;
+use winreg2::perf::{PerfSnapshot, TitleDatabase};
+
+#[test]
+fn test_title_database() {
+    let titles = TitleDatabase::load().unwrap();
+    assert_eq!(titles.name(238), Some("Processor"));
+    assert!(titles.index_of("processor").is_some());
+}
+
+#[test]
+fn test_perf_snapshot() {
+    let titles = TitleDatabase::load().unwrap();
+    let snapshot = PerfSnapshot::query("238", &titles).unwrap();
+    assert!(snapshot.perf_freq > 0);
+    let processor = snapshot.object("Processor").unwrap();
+    let time = processor.counter_position("% Processor Time").unwrap();
+    let total = processor.instance("_Total").unwrap();
+    assert!(total.values[time].as_u64().is_some());
+    assert!(processor
+        .instances
+        .iter()
+        .all(|i| i.values.len() == processor.counters.len()));
+}
+
+#[test]
+fn test_perf_parse_invalid() {
+    assert!(PerfSnapshot::parse(&[0; 16], None).is_err());
+    assert!(PerfSnapshot::parse(b"P\0E\0R\0F\0", None).is_err());
+}
+
+/// Structures of a data block with one object, counter and instance
+struct PerfData {
+    block: PERF_DATA_BLOCK,
+    object: PERF_OBJECT_TYPE,
+    counter: PERF_COUNTER_DEFINITION,
+    instance: PERF_INSTANCE_DEFINITION,
+    counter_block: PERF_COUNTER_BLOCK,
+}
+
+impl PerfData {
+    fn new() -> PerfData {
+        let name_len = 8; // "a\0" and padding
+        let counters_len = 16; // counter block and value, 8-byte aligned
+        let mut data: PerfData = unsafe { mem::zeroed() };
+        data.block.Signature = [b'P' as u16, b'E' as u16, b'R' as u16, b'F' as u16];
+        data.block.HeaderLength = mem::size_of::<PERF_DATA_BLOCK>() as u32;
+        data.block.NumObjectTypes = 1;
+        data.object.HeaderLength = mem::size_of::<PERF_OBJECT_TYPE>() as u32;
+        data.object.DefinitionLength =
+            data.object.HeaderLength + mem::size_of::<PERF_COUNTER_DEFINITION>() as u32;
+        data.object.TotalByteLength = data.object.DefinitionLength
+            + mem::size_of::<PERF_INSTANCE_DEFINITION>() as u32
+            + name_len
+            + counters_len;
+        data.object.NumCounters = 1;
+        data.object.NumInstances = 1;
+        data.counter.ByteLength = mem::size_of::<PERF_COUNTER_DEFINITION>() as u32;
+        data.counter.CounterSize = 4;
+        data.counter.CounterOffset = mem::size_of::<PERF_COUNTER_BLOCK>() as u32;
+        data.instance.ByteLength = mem::size_of::<PERF_INSTANCE_DEFINITION>() as u32 + name_len;
+        data.instance.NameOffset = mem::size_of::<PERF_INSTANCE_DEFINITION>() as u32;
+        data.instance.NameLength = 4;
+        data.counter_block.ByteLength = counters_len;
+        data
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        fn raw<T>(value: &T) -> &[u8] {
+            unsafe { slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) }
+        }
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(raw(&self.block));
+        bytes.extend_from_slice(raw(&self.object));
+        bytes.extend_from_slice(raw(&self.counter));
+        bytes.extend_from_slice(raw(&self.instance));
+        bytes.extend_from_slice(&[b'a', 0, 0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(raw(&self.counter_block));
+        bytes.extend_from_slice(&[42, 0, 0, 0, 0, 0, 0, 0]);
+        bytes
+    }
+
+    fn parse_with(change: impl FnOnce(&mut PerfData)) -> std::io::Result<PerfSnapshot> {
+        let mut data = PerfData::new();
+        change(&mut data);
+        PerfSnapshot::parse(&data.bytes(), None)
+    }
+}
+
+#[test]
+fn test_perf_parse_corrupt() {
+    let snapshot = PerfData::parse_with(|_| {}).unwrap();
+    let object = &snapshot.objects[0];
+    assert_eq!(object.instances[0].name.as_deref(), Some("a"));
+    assert_eq!(object.instances[0].values[0].as_u64(), Some(42));
+
+    let invalid = Some(windows_sys::Win32::Foundation::ERROR_INVALID_DATA as i32);
+    let corruptions: Vec<fn(&mut PerfData)> = vec![
+        // counts larger than the data
+        |d| d.block.NumObjectTypes = u32::MAX,
+        |d| d.object.NumCounters = u32::MAX,
+        |d| d.object.NumInstances = i32::MAX,
+        // lengths that don't advance to the next structure
+        |d| {
+            d.block.NumObjectTypes = 2;
+            d.object.TotalByteLength = 0;
+        },
+        |d| d.counter.ByteLength = 0,
+        |d| d.instance.ByteLength = 0,
+        |d| d.counter_block.ByteLength = 0,
+        // offsets out of the data
+        |d| d.object.HeaderLength = u32::MAX,
+        |d| d.object.DefinitionLength = u32::MAX,
+        |d| d.instance.NameOffset = u32::MAX,
+        |d| d.counter.CounterOffset = u32::MAX,
+        |d| {
+            d.counter.CounterSize = u32::MAX;
+            d.counter.CounterOffset = u32::MAX;
+        },
+    ];
+    for corrupt in corruptions {
+        let err = PerfData::parse_with(corrupt).unwrap_err();
+        assert_eq!(err.raw_os_error(), invalid);
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use winreg2::enums::*;
+use winreg2::serialization::reg_file::{self, Change, ChangeSet, ExportOptions, Format};
+use winreg2::types::ToRegValue;
+use winreg2::RegValue;
+
+mod common;
+
+fn decode(bytes: &[u8]) -> String {
+    assert_eq!(&bytes[..2], &[0xff, 0xfe]);
+    let words: Vec<u16> = bytes[2..]
+        .chunks(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16(&words).unwrap()
+}
+
+#[test]
+fn test_format_value() {
+    assert_eq!(
+        reg_file::format_value("", &"C:\\Dir \"x\"".to_reg_value()),
+        "@=\"C:\\\\Dir \\\"x\\\"\""
+    );
+    assert_eq!(
+        reg_file::format_value("Count", &0x1234u32.to_reg_value()),
+        "\"Count\"=dword:00001234"
+    );
+    assert_eq!(
+        reg_file::format_value("Big", &1u64.to_reg_value()),
+        "\"Big\"=hex(b):01,00,00,00,00,00,00,00"
+    );
+    assert_eq!(
+        reg_file::format_value("List", &vec!["a", "b"].to_reg_value()),
+        "\"List\"=hex(7):61,00,00,00,62,00,00,00,00,00"
+    );
+    let expand = RegValue {
+        bytes: vec![0x25, 0, 0x25, 0, 0, 0],
+        vtype: REG_EXPAND_SZ,
+    };
+    assert_eq!(
+        reg_file::format_value("Path", &expand),
+        "\"Path\"=hex(2):25,00,25,00,00,00"
+    );
+    assert_eq!(
+        reg_file::format_value("Lines", &"a\nb".to_reg_value()),
+        "\"Lines\"=hex(1):61,00,0a,00,62,00,00,00"
+    );
+    let vendor = RegValue {
+        bytes: vec![1, 2],
+        vtype: RegType::from(0x4000_0001),
+    };
+    assert_eq!(
+        reg_file::format_value("Vendor", &vendor),
+        "\"Vendor\"=hex(40000001):01,02"
+    );
+    let long = RegValue {
+        bytes: vec![0xab; 30],
+        vtype: REG_BINARY,
+    };
+    let line = reg_file::format_value("Data", &long);
+    assert!(line.split("\r\n").all(|l| l.len() <= 80));
+    assert_eq!(line.replace("\\\r\n  ", "").matches("ab").count(), 30);
+}
+
+#[test]
+fn test_export() {
+    with_key!(key, "Export" => {
+        key.set_value("", &"root").unwrap();
+        let (sub, _disp) = key.create_subkey("Sub").unwrap();
+        sub.set_value("Count", &1u32).unwrap();
+
+        let mut out = Vec::new();
+        reg_file::export(&key, &mut out, &ExportOptions::new()).unwrap();
+        assert_eq!(
+            decode(&out),
+            "Windows Registry Editor Version 5.00\r\n\r\n\
+             [HKEY_CURRENT_USER\\Software\\WinRegRsTestExport]\r\n\
+             @=\"root\"\r\n\r\n\
+             [HKEY_CURRENT_USER\\Software\\WinRegRsTestExport\\Sub]\r\n\
+             \"Count\"=dword:00000001\r\n\r\n"
+        );
+
+        let mut out = Vec::new();
+        let options = ExportOptions::new().recursive(false).path("HKEY_CURRENT_USER\\Software\\Other");
+        reg_file::export(&key, &mut out, &options).unwrap();
+        assert_eq!(
+            decode(&out),
+            "Windows Registry Editor Version 5.00\r\n\r\n\
+             [HKEY_CURRENT_USER\\Software\\Other]\r\n\
+             @=\"root\"\r\n\r\n"
+        );
+    });
+}
+
+#[test]
+fn test_export_link() {
+    with_key!(key, "ExportLink" => {
+        let target = "\\REGISTRY\\MACHINE\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion";
+        key.create_link("Link", target).unwrap();
+        let mut out = Vec::new();
+        reg_file::export(&key, &mut out, &ExportOptions::new()).unwrap();
+        key.open_subkey_nofollow("Link", KEY_ALL_ACCESS)
+            .unwrap()
+            .delete_link()
+            .unwrap();
+        assert_eq!(
+            decode(&out),
+            "Windows Registry Editor Version 5.00\r\n\r\n\
+             [HKEY_CURRENT_USER\\Software\\WinRegRsTestExportLink]\r\n\r\n\
+             [HKEY_CURRENT_USER\\Software\\WinRegRsTestExportLink\\Link]\r\n\r\n"
+        );
+    });
+}
+
+#[test]
+fn test_parse() {
+    let text = "Windows Registry Editor Version 5.00\r\n\
+                \r\n\
+                ; comment\r\n\
+                [-HKEY_CURRENT_USER\\Software\\Old]\r\n\
+                \r\n\
+                [HKEY_CURRENT_USER\\Software\\New]\r\n\
+                @=\"C:\\\\Dir \\\"x\\\"\"\r\n\
+                \"Count\"=dword:0000002a\r\n\
+                \"Data\"=hex:01,02,\\\r\n  03\r\n\
+                \"Path\"=hex(2):25,00,00,00\r\n\
+                \"Gone\"=-\r\n";
+    let set = ChangeSet::parse(text).unwrap();
+    assert_eq!(set.format, Format::V5);
+    let key = "HKEY_CURRENT_USER\\Software\\New".to_owned();
+    assert_eq!(
+        set.changes,
+        vec![
+            Change::DeleteKey {
+                path: "HKEY_CURRENT_USER\\Software\\Old".to_owned()
+            },
+            Change::CreateKey { path: key.clone() },
+            Change::SetValue {
+                key: key.clone(),
+                name: "".to_owned(),
+                value: "C:\\Dir \"x\"".to_reg_value(),
+            },
+            Change::SetValue {
+                key: key.clone(),
+                name: "Count".to_owned(),
+                value: 42u32.to_reg_value(),
+            },
+            Change::SetValue {
+                key: key.clone(),
+                name: "Data".to_owned(),
+                value: RegValue {
+                    bytes: vec![1, 2, 3],
+                    vtype: REG_BINARY,
+                },
+            },
+            Change::SetValue {
+                key: key.clone(),
+                name: "Path".to_owned(),
+                value: RegValue {
+                    bytes: vec![0x25, 0, 0, 0],
+                    vtype: REG_EXPAND_SZ,
+                },
+            },
+            Change::DeleteValue {
+                key,
+                name: "Gone".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_regedit4() {
+    let set = ChangeSet::parse("REGEDIT4\n\n[HKEY_CURRENT_USER\\X]\n\"E\"=hex(2):25,00\n").unwrap();
+    assert_eq!(set.format, Format::Regedit4);
+    match set.changes[1] {
+        Change::SetValue { ref value, .. } => assert_eq!(value.bytes, vec![0x25, 0, 0, 0]),
+        ref other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(ChangeSet::parse("").is_err());
+    assert!(ChangeSet::parse("REGEDIT5\n").is_err());
+    let err = ChangeSet::parse("REGEDIT4\n\"Orphan\"=\"x\"\n").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().starts_with("line 2"));
+    assert!(ChangeSet::parse("REGEDIT4\n[HKEY_CURRENT_USER\\X]\n\"a\"=hex:zz\n").is_err());
+}
+
+#[test]
+fn test_export_import_roundtrip() {
+    with_key!(key, "Import" => {
+        let (src, _disp) = key.create_subkey("Src").unwrap();
+        src.set_value("", &"root").unwrap();
+        src.set_value("List", &vec!["a", "b"]).unwrap();
+        let (sub, _disp) = src.create_subkey("Sub").unwrap();
+        sub.set_value("Count", &1u64).unwrap();
+
+        let dest_path = "HKEY_CURRENT_USER\\Software\\WinRegRsTestImport\\Dest";
+        let mut out = Vec::new();
+        reg_file::export(&src, &mut out, &ExportOptions::new().path(dest_path)).unwrap();
+        let set = ChangeSet::read(&out[..]).unwrap();
+        set.apply(&key).unwrap();
+
+        let dest = key.open_subkey("Dest").unwrap();
+        assert_eq!(dest.get_value::<String, _>("").unwrap(), "root");
+        assert_eq!(dest.get_value::<Vec<String>, _>("List").unwrap(), vec!["a", "b"]);
+        assert_eq!(dest.open_subkey("Sub").unwrap().get_value::<u64, _>("Count").unwrap(), 1);
+
+        let delete = format!("Windows Registry Editor Version 5.00\r\n\r\n[-{}\\Sub]\r\n", dest_path);
+        ChangeSet::parse(&delete).unwrap().apply(&key).unwrap();
+        assert!(dest.open_subkey("Sub").is_err());
+
+        let outside = ChangeSet::parse("REGEDIT4\n[HKEY_LOCAL_MACHINE\\SOFTWARE\\X]\n").unwrap();
+        assert!(outside.apply(&key).is_err());
+    });
+}
@@ -9,7 +9,7 @@ use std::ffi::{OsStr, OsString};
 use tempfile::tempdir;
 use windows_sys::Win32::Foundation;
 use winreg2::enums::*;
-use winreg2::types::FromRegValue;
+use winreg2::types::{BigEndian, ExpandString, ExpandablePath, FromRegValue, ToRegValue};
 use winreg2::{RegKey, RegValue};
 
 mod common;
@@ -121,6 +121,137 @@ fn test_copy_tree() {
     });
 }
 
+#[cfg(feature = "transactions")]
+#[test]
+fn test_move_tree() {
+    with_key!(key, "MoveTree" => {
+        let (sub_tree, _disp) = key.create_subkey("Src\\Sub\\Tree").unwrap();
+        sub_tree.set_value("one", &1u32).unwrap();
+        key.open_subkey_with_flags("Src", KEY_ALL_ACCESS)
+            .unwrap()
+            .create_link("Link", "\\Registry\\Machine\\SOFTWARE")
+            .unwrap();
+        let (dst, _disp) = key.create_subkey("Dst").unwrap();
+        key.move_tree("Src", &dst).unwrap();
+        assert!(key.open_subkey("Src").is_err());
+        let moved = dst.open_subkey("Sub\\Tree").unwrap();
+        assert_eq!(moved.get_value::<u32, _>("one").unwrap(), 1);
+        let link = dst.open_subkey_nofollow("Link", KEY_READ).unwrap();
+        assert_eq!(link.read_link().unwrap(), "\\Registry\\Machine\\SOFTWARE");
+        // a missing source changes nothing
+        assert!(key.move_tree("Src", &dst).is_err());
+        assert!(dst.open_subkey("Sub").is_ok());
+
+        let invalid = Some(Foundation::ERROR_INVALID_PARAMETER as i32);
+        // into its own subtree, with another case
+        let err = key.move_tree("Dst", &dst).unwrap_err();
+        assert_eq!(err.raw_os_error(), invalid);
+        let sub = dst.open_subkey("Sub\\Tree").unwrap();
+        let err = key.move_tree("DST", &sub).unwrap_err();
+        assert_eq!(err.raw_os_error(), invalid);
+        // the contents of the key itself
+        let (other, _disp) = key.create_subkey("Other").unwrap();
+        let err = dst.move_tree("", &other).unwrap_err();
+        assert_eq!(err.raw_os_error(), invalid);
+        assert_eq!(dst.move_tree("\\", &other).unwrap_err().raw_os_error(), invalid);
+        assert!(dst.open_subkey("Sub\\Tree").is_ok());
+        // a sibling with a common prefix is fine
+        let (dst2, _disp) = key.create_subkey("Dst2").unwrap();
+        key.move_tree("Dst", &dst2).unwrap();
+        assert!(dst2.open_subkey("Sub\\Tree").is_ok());
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_tree_transacted() {
+    use winreg2::transaction::Transaction;
+    with_key!(key, "TreeTransacted" => {
+        let (sub_tree, _disp) = key.create_subkey("Src\\Sub").unwrap();
+        sub_tree.set_value("one", &1u32).unwrap();
+        let (dst, _disp) = key.create_subkey("Dst").unwrap();
+
+        let t = Transaction::new().unwrap();
+        key.copy_tree_transacted("Src", &dst, &t).unwrap();
+        key.delete_subkey_all_transacted("Src", &t).unwrap();
+        t.rollback().unwrap();
+        assert!(dst.open_subkey("Sub").is_err());
+        assert!(key.open_subkey("Src\\Sub").is_ok());
+
+        let t = Transaction::new().unwrap();
+        key.copy_tree_transacted("Src", &dst, &t).unwrap();
+        key.delete_subkey_all_transacted("Src", &t).unwrap();
+        t.commit().unwrap();
+        assert_eq!(dst.open_subkey("Sub").unwrap().get_value::<u32, _>("one").unwrap(), 1);
+        assert!(key.open_subkey("Src").is_err());
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_transaction_builder() {
+    use winreg2::transaction::{Transaction, TransactionStatus};
+    with_key!(key, "TransactionBuilder" => {
+        let t = Transaction::builder()
+            .description("winreg test")
+            .do_not_promote(true)
+            .build()
+            .unwrap();
+        assert!(t.is_active().unwrap());
+        key.open_subkey_transacted_with_flags("", &t, KEY_WRITE)
+            .unwrap()
+            .set_value("A", &1u32)
+            .unwrap();
+        t.commit().unwrap();
+        assert_eq!(key.get_value::<u32, _>("A").unwrap(), 1);
+
+        let t = Transaction::builder()
+            .timeout(std::time::Duration::from_millis(1))
+            .build()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(t.status().unwrap(), TransactionStatus::Aborted);
+        assert!(t.commit().is_err());
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_transacted_key() {
+    use winreg2::transaction::Transaction;
+    with_key!(key, "TransactedKey" => {
+        key.create_subkey("Old").unwrap();
+        let t = Transaction::new().unwrap();
+        let tkey = key.transacted(&t).unwrap();
+        let (sub, _disp) = tkey.create_subkey("New").unwrap();
+        sub.set_value("A", &1u32).unwrap();
+        tkey.delete_subkey_all("Old").unwrap();
+        assert_eq!(tkey.open_subkey("New").unwrap().get_value::<u32, _>("A").unwrap(), 1);
+        assert!(key.open_subkey("New").is_err());
+        assert!(key.open_subkey("Old").is_ok());
+        drop(sub);
+        drop(tkey);
+        t.commit().unwrap();
+        assert_eq!(key.open_subkey("New").unwrap().get_value::<u32, _>("A").unwrap(), 1);
+        assert!(key.open_subkey("Old").is_err());
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_transaction_raw_handle() {
+    use winreg2::transaction::Transaction;
+    with_key!(key, "TransactionRawHandle" => {
+        let handle = Transaction::new().unwrap().into_raw_handle();
+        let t = unsafe { Transaction::from_raw_handle(handle) };
+        assert_eq!(t.as_raw_handle(), handle);
+        key.transacted(&t).unwrap().set_value("A", &1u32).unwrap();
+        assert!(key.get_raw_value("A").is_err());
+        t.commit().unwrap();
+        assert_eq!(key.get_value::<u32, _>("A").unwrap(), 1);
+    });
+}
+
 #[test]
 fn test_long_value() {
     with_key!(key, "LongValue" => {
@@ -211,6 +342,201 @@ fn test_u32_value() {
     });
 }
 
+macro_rules! test_value_int {
+    ($f:ident, $name:expr, $val:expr => $t:ty, $vtype:expr) => {
+        #[test]
+        fn $f() {
+            with_key!(key, $name => {
+                let name = "RustIntVal";
+                let val1: $t = $val;
+                key.set_value(name, &val1).unwrap();
+                assert_eq!(key.get_raw_value(name).unwrap().vtype, $vtype);
+                let val2: $t = key.get_value(name).unwrap();
+                assert_eq!(val1, val2);
+            });
+        }
+    };
+}
+
+test_value_int!(test_i32_value, "I32Value", -1_234_567_890 => i32, REG_DWORD);
+test_value_int!(test_i64_value, "I64Value", -1_234_567_891_011_121_314 => i64, REG_QWORD);
+test_value_int!(test_u128_value, "U128Value", 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10 => u128, REG_BINARY);
+test_value_int!(test_i128_value, "I128Value", -1_234_567_891_011_121_314_151_617 => i128, REG_BINARY);
+test_value_int!(test_u8_value, "U8Value", 200 => u8, REG_DWORD);
+test_value_int!(test_u16_value, "U16Value", 60_000 => u16, REG_DWORD);
+test_value_int!(test_bool_value, "BoolValue", true => bool, REG_DWORD);
+
+#[test]
+fn test_small_int_out_of_range() {
+    with_key!(key, "SmallIntRange" => {
+        key.set_value("Big", &70_000u32).unwrap();
+        assert!(key.get_value::<u16, _>("Big").is_err());
+        assert_eq!(key.get_value::<u32, _>("Big").unwrap(), 70_000);
+        assert!(key.get_value::<bool, _>("Big").unwrap());
+        key.set_value("Neg", &-1i32).unwrap();
+        assert_eq!(key.get_value::<u32, _>("Neg").unwrap(), u32::MAX);
+    });
+}
+
+#[test]
+fn test_binary_value() {
+    with_key!(key, "BinaryValue" => {
+        let blob = vec![0u8, 1, 2, 0xfe, 0xff];
+        key.set_value("Vec", &blob).unwrap();
+        key.set_value("Slice", &&blob[1..3]).unwrap();
+        key.set_value("Array", &[7u8; 4]).unwrap();
+        assert_eq!(key.get_raw_value("Vec").unwrap().vtype, REG_BINARY);
+        assert_eq!(key.get_value::<Vec<u8>, _>("Vec").unwrap(), blob);
+        assert_eq!(key.get_value::<[u8; 2], _>("Slice").unwrap(), [1, 2]);
+        assert_eq!(key.get_value::<[u8; 4], _>("Array").unwrap(), [7; 4]);
+        assert!(key.get_value::<[u8; 3], _>("Array").is_err());
+        key.set_value("Dword", &1u32).unwrap();
+        assert!(key.get_value::<Vec<u8>, _>("Dword").is_err());
+    });
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_value() {
+    use chrono::{TimeZone, Utc};
+    with_key!(key, "ChronoValue" => {
+        let time = Utc.timestamp_opt(1_600_000_000, 123_456_700).unwrap();
+        key.set_value("Time", &time).unwrap();
+        assert_eq!(key.get_value::<u64, _>("Time").unwrap(), 132_444_736_001_234_567);
+        assert_eq!(key.get_value::<chrono::DateTime<Utc>, _>("Time").unwrap(), time);
+        key.set_value("Blob", &132_444_736_001_234_567u64.to_le_bytes()).unwrap();
+        assert_eq!(key.get_value::<chrono::DateTime<Utc>, _>("Blob").unwrap(), time);
+        key.set_value("Early", &Utc.with_ymd_and_hms(1000, 1, 1, 0, 0, 0).unwrap()).unwrap();
+        assert_eq!(key.get_value::<u64, _>("Early").unwrap(), 0);
+        key.set_value("Late", &chrono::DateTime::<Utc>::MAX_UTC).unwrap();
+        assert_eq!(key.get_value::<u64, _>("Late").unwrap(), u64::MAX);
+    });
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_time_value() {
+    use time::OffsetDateTime;
+    with_key!(key, "TimeValue" => {
+        let time = OffsetDateTime::from_unix_timestamp_nanos(1_600_000_000_123_456_700).unwrap();
+        key.set_value("Time", &time).unwrap();
+        assert_eq!(key.get_value::<u64, _>("Time").unwrap(), 132_444_736_001_234_567);
+        assert_eq!(key.get_value::<OffsetDateTime, _>("Time").unwrap(), time);
+        key.set_value("Blob", &132_444_736_001_234_567u64.to_le_bytes()).unwrap();
+        assert_eq!(key.get_value::<OffsetDateTime, _>("Blob").unwrap(), time);
+        let early = OffsetDateTime::from_unix_timestamp(-20_000_000_000).unwrap();
+        key.set_value("Early", &early).unwrap();
+        assert_eq!(key.get_value::<u64, _>("Early").unwrap(), 0);
+    });
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_uuid_value() {
+    let id = uuid::Uuid::parse_str("6b29fc40-ca47-1067-b31d-00dd010662da").unwrap();
+    with_key!(key, "UuidValue" => {
+        key.set_value("Braced", &id).unwrap();
+        assert_eq!(
+            key.get_value::<String, _>("Braced").unwrap(),
+            "{6B29FC40-CA47-1067-B31D-00DD010662DA}"
+        );
+        assert_eq!(key.get_value::<uuid::Uuid, _>("Braced").unwrap(), id);
+        key.set_value("Plain", &"6b29fc40-ca47-1067-b31d-00dd010662da").unwrap();
+        assert_eq!(key.get_value::<uuid::Uuid, _>("Plain").unwrap(), id);
+        key.set_value("Binary", &id.to_bytes_le()).unwrap();
+        assert_eq!(key.get_value::<uuid::Uuid, _>("Binary").unwrap(), id);
+        key.set_value("Short", &[0u8; 4]).unwrap();
+        assert!(key.get_value::<uuid::Uuid, _>("Short").is_err());
+        key.set_value("Invalid", &"{not-a-guid}").unwrap();
+        assert!(key.get_value::<uuid::Uuid, _>("Invalid").is_err());
+    });
+}
+
+#[test]
+fn test_path_value() {
+    with_key!(key, "PathValue" => {
+        let path = std::path::PathBuf::from("C:\\Program Files\\MyApp");
+        key.set_value("Path", &path).unwrap();
+        assert_eq!(key.get_raw_value("Path").unwrap().vtype, REG_SZ);
+        assert_eq!(key.get_value::<std::path::PathBuf, _>("Path").unwrap(), path);
+
+        let raw = std::path::PathBuf::from("%SystemRoot%\\System32");
+        key.set_value("Expand", &ExpandablePath(raw.clone())).unwrap();
+        assert_eq!(key.get_raw_value("Expand").unwrap().vtype, REG_EXPAND_SZ);
+        assert_eq!(key.get_value::<ExpandablePath, _>("Expand").unwrap(), ExpandablePath(raw));
+        let expanded = std::path::PathBuf::from(std::env::var_os("SystemRoot").unwrap()).join("System32");
+        assert_eq!(key.get_value::<std::path::PathBuf, _>("Expand").unwrap(), expanded);
+    });
+}
+
+#[test]
+fn test_expand_string_value() {
+    with_key!(key, "ExpandStringValue" => {
+        let val1 = ExpandString("%SystemRoot%\\Temp".to_owned());
+        key.set_value("Expand", &val1).unwrap();
+        assert_eq!(key.get_raw_value("Expand").unwrap().vtype, REG_EXPAND_SZ);
+        let val2: ExpandString = key.get_value("Expand").unwrap();
+        assert_eq!(val1, val2);
+        let root = std::env::var("SystemRoot").unwrap();
+        assert_eq!(val2.expand().unwrap(), format!("{}\\Temp", root));
+        key.set_value("Plain", &"text").unwrap();
+        assert_eq!(key.get_value::<ExpandString, _>("Plain").unwrap().0, "text");
+    });
+}
+
+#[test]
+fn test_big_endian_value() {
+    with_key!(key, "BigEndianValue" => {
+        let name = "RustBigEndianVal";
+        key.set_value(name, &BigEndian(0x0102_0304)).unwrap();
+        let raw = key.get_raw_value(name).unwrap();
+        assert_eq!(raw.vtype, REG_DWORD_BIG_ENDIAN);
+        assert_eq!(raw.bytes, vec![1, 2, 3, 4]);
+        let val: u32 = key.get_value(name).unwrap();
+        assert_eq!(val, 0x0102_0304);
+        let val: BigEndian = key.get_value(name).unwrap();
+        assert_eq!(val, BigEndian(0x0102_0304));
+    });
+}
+
+#[test]
+fn test_get_value_opt() {
+    with_key!(key, "GetValueOpt" => {
+        key.set_value("Port", &8080u32).unwrap();
+        assert_eq!(key.get_value_opt::<u32, _>("Port").unwrap(), Some(8080));
+        assert_eq!(key.get_value_opt::<u32, _>("Missing").unwrap(), None);
+        assert!(key.get_value_opt::<String, _>("Port").is_err());
+    });
+}
+
+#[test]
+fn test_get_value_or() {
+    with_key!(key, "GetValueOr" => {
+        key.set_value("Timeout", &10u32).unwrap();
+        assert_eq!(key.get_value_or("Timeout", 30u32).unwrap(), 10);
+        assert_eq!(key.get_value_or("Missing", 30u32).unwrap(), 30);
+        assert_eq!(key.get_value_or_default::<String, _>("Missing").unwrap(), "");
+        assert!(key.get_value_or_default::<String, _>("Timeout").is_err());
+    });
+}
+
+#[test]
+fn test_get_values() {
+    with_key!(key, "GetValues" => {
+        key.set_value("A", &1u32).unwrap();
+        key.set_value("B", &2u32).unwrap();
+        let values = key.get_values::<u32>().unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["A"], 1);
+        assert_eq!(values["B"], 2);
+        key.set_value("C", &"three").unwrap();
+        assert!(key.get_values::<u32>().is_err());
+        let raw = key.get_all_raw_values().unwrap();
+        assert_eq!(raw.len(), 3);
+        assert_eq!(raw["C"], "three".to_reg_value());
+    });
+}
+
 #[test]
 fn test_u64_value() {
     with_key!(key, "U64Value" => {
@@ -283,3 +609,731 @@ fn test_enum_long_values() {
         }
     });
 }
+
+#[test]
+fn test_symbolic_link() {
+    with_key!(key, "SymbolicLink" => {
+        let target = "\\REGISTRY\\MACHINE\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion";
+        key.create_link("Link", target).unwrap();
+        let followed = key.open_subkey("Link").unwrap();
+        assert!(followed.get_value::<String, _>("ProductName").is_ok());
+        assert!(!followed.is_symlink().unwrap());
+        let link = key.open_subkey_nofollow("Link", KEY_ALL_ACCESS).unwrap();
+        assert!(link.is_symlink().unwrap());
+        assert_eq!(link.read_link().unwrap(), target);
+        link.delete_link().unwrap();
+        assert!(key.open_subkey("Link").is_err());
+    });
+}
+
+#[test]
+fn test_volatile_subkey() {
+    with_key!(key, "VolatileSubkey" => {
+        let (volatile, _disp) = key.create_volatile_subkey("Volatile").unwrap();
+        assert!(volatile.is_volatile().unwrap());
+        let (stable, _disp) = key.create_subkey("Stable").unwrap();
+        assert!(!stable.is_volatile().unwrap());
+    });
+}
+
+#[test]
+fn test_registry_view() {
+    let path = "Software\\WinRegRsTestView";
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _disp) = hkcu.create_subkey_view(path, RegistryView::V32).unwrap();
+    key.create_subkey("Sub").unwrap();
+    hkcu.open_subkey_view(path, KEY_READ, RegistryView::V32)
+        .unwrap();
+    // HKCU\Software is shared between the views
+    hkcu.open_subkey_view(path, KEY_READ, RegistryView::V64)
+        .unwrap();
+    assert!(hkcu.delete_subkey_view(path, RegistryView::V32).is_err());
+    hkcu.delete_subkey_all_view(path, RegistryView::V32)
+        .unwrap();
+    assert!(hkcu.open_subkey(path).is_err());
+}
+
+#[test]
+fn test_physical_path() {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let native = hklm
+        .open_subkey_view("SOFTWARE\\Microsoft", KEY_READ, RegistryView::V64)
+        .unwrap()
+        .physical_path()
+        .unwrap();
+    assert!(native
+        .path
+        .eq_ignore_ascii_case("\\REGISTRY\\MACHINE\\SOFTWARE\\Microsoft"));
+    assert!(!native.wow64_redirected);
+    let redirected = hklm
+        .open_subkey_view("SOFTWARE\\Microsoft", KEY_READ, RegistryView::V32)
+        .unwrap()
+        .physical_path()
+        .unwrap();
+    assert!(redirected.wow64_redirected);
+    assert!(!redirected.virtualized);
+}
+
+#[cfg(feature = "legacy-reflection")]
+#[test]
+fn test_reflection() {
+    with_key!(key, "Reflection" => {
+        // not supported by 32-bit Windows
+        if key.is_reflection_disabled().is_ok() {
+            key.disable_reflection().unwrap();
+            key.enable_reflection().unwrap();
+        }
+    });
+}
+
+#[test]
+fn test_open_current_user() {
+    winreg2::disable_predefined_cache().unwrap();
+    let hkcu = RegKey::open_current_user(KEY_READ).unwrap();
+    assert!(hkcu.open_subkey("Software").is_ok());
+}
+
+#[test]
+fn test_open_path() {
+    with_key!(key, "OpenPath" => {
+        key.set_value("Value", &1u32).unwrap();
+        let opened = winreg2::open_path("HKCU\\Software\\\\WinRegRsTestOpenPath", KEY_READ).unwrap();
+        assert_eq!(opened.get_value::<u32, _>("Value").unwrap(), 1);
+        let opened = RegKey::open_path("hkey_current_user\\Software\\WinRegRsTestOpenPath", KEY_READ).unwrap();
+        assert_eq!(opened.get_value::<u32, _>("Value").unwrap(), 1);
+        let err = RegKey::open_path("Software\\WinRegRsTestOpenPath", KEY_READ).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_BAD_PATHNAME as i32));
+    });
+}
+
+#[test]
+fn test_full_path() {
+    with_key!(key, "FullPath" => {
+        let (sub, _disp) = key.create_subkey("Sub").unwrap();
+        let expected = "HKEY_CURRENT_USER\\Software\\WinRegRsTestFullPath\\Sub";
+        assert_eq!(sub.full_path().unwrap(), expected);
+        assert_eq!(sub.to_string(), expected);
+        assert_eq!(format!("{:?}", sub), format!("RegKey({})", expected));
+        assert_eq!(RegKey::predef(HKEY_LOCAL_MACHINE).to_string(), "HKEY_LOCAL_MACHINE");
+        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey("SOFTWARE").unwrap();
+        assert!(hklm.full_path().unwrap().eq_ignore_ascii_case("HKEY_LOCAL_MACHINE\\SOFTWARE"));
+    });
+}
+
+#[test]
+fn test_values_with_embedded_nuls() {
+    with_key!(key, "EmbeddedNuls" => {
+        let hostile = "Name\0Hidden\0";
+        // "a\0b" followed by two NULs
+        let value = RegValue {
+            bytes: vec![b'a', 0, 0, 0, b'b', 0, 0, 0, 0, 0],
+            vtype: REG_SZ,
+        };
+        key.set_raw_value_exact(hostile, &value).unwrap();
+        key.set_value("Name", &"visible").unwrap();
+
+        let names: Vec<_> = key.enum_values().map(|v| v.unwrap().0).collect();
+        assert!(names.iter().any(|n| n == hostile));
+        assert_eq!(key.get_raw_value_exact(hostile).unwrap(), value);
+        assert_eq!(key.get_value::<String, _>("Name").unwrap(), "visible");
+        assert_eq!(
+            key.get_raw_value_exact("Name").unwrap(),
+            key.get_raw_value("Name").unwrap()
+        );
+
+        key.delete_value_exact(hostile).unwrap();
+        let err = key.get_raw_value_exact(hostile).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(key.get_raw_value("Name").is_ok());
+    });
+}
+
+#[test]
+fn test_key_class() {
+    with_key!(key, "KeyClass" => {
+        let (sub, _disp) = key
+            .create_subkey_with_class("Src\\Child", "MyClass", REG_OPTION_NON_VOLATILE, KEY_ALL_ACCESS)
+            .unwrap();
+        assert_eq!(sub.class().unwrap(), "MyClass");
+        assert_eq!(key.class().unwrap(), "");
+
+        let (dest, _disp) = key.create_subkey("Dest").unwrap();
+        key.copy_tree("Src", &dest).unwrap();
+        assert_eq!(dest.open_subkey("Child").unwrap().class().unwrap(), "MyClass");
+    });
+}
+
+#[test]
+fn test_reopen_with_flags() {
+    with_key!(key, "Reopen" => {
+        let read_only = key.reopen_with_flags(KEY_READ).unwrap();
+        assert!(read_only.set_value("Value", &1u32).is_err());
+        let writable = read_only.reopen_with_flags(KEY_SET_VALUE).unwrap();
+        writable.set_value("Value", &1u32).unwrap();
+        assert_eq!(read_only.get_value::<u32, _>("Value").unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_recently_modified() {
+    with_key!(key, "RecentlyModified" => {
+        key.create_subkey("Old\\Deep").unwrap();
+        let since = std::time::SystemTime::now() - std::time::Duration::from_secs(60);
+        let all = key.recently_modified(since, None).unwrap();
+        let paths: Vec<_> = all.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&"Old") && paths.contains(&"Old\\Deep"));
+        assert!(all.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        assert_eq!(key.recently_modified(since, Some(1)).unwrap().len(), 1);
+        let future = std::time::SystemTime::now() + std::time::Duration::from_secs(3600);
+        assert!(key.recently_modified(future, None).unwrap().is_empty());
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_multi_sz_update() {
+    with_key!(key, "MultiSzUpdate" => {
+        let entries = key
+            .multi_sz_update("List", |e| e.push("a".to_owned()))
+            .unwrap();
+        assert_eq!(entries, vec!["a"]);
+        key.multi_sz_update("List", |e| e.push("b".to_owned())).unwrap();
+        let list: Vec<String> = key.get_value("List").unwrap();
+        assert_eq!(list, vec!["a", "b"]);
+
+        // concurrent updates conflict and are retried, none is lost
+        let threads: Vec<_> = (0..4)
+            .map(|t| {
+                std::thread::spawn(move || {
+                    let key = RegKey::predef(HKEY_CURRENT_USER)
+                        .open_subkey_with_flags("Software\\WinRegRsTestMultiSzUpdate", KEY_ALL_ACCESS)
+                        .unwrap();
+                    for i in 0..10 {
+                        key.multi_sz_update("List", |e| e.push(format!("{}-{}", t, i)))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+        let list: Vec<String> = key.get_value("List").unwrap();
+        assert_eq!(list.len(), 42);
+
+        key.set_value("Number", &1u32).unwrap();
+        assert!(key.multi_sz_update("Number", |_| {}).is_err());
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_multi_sz_update_transacted() {
+    with_key!(key, "MultiSzUpdateTransacted" => {
+        let t = winreg2::transaction::Transaction::new().unwrap();
+        key.multi_sz_update_transacted("List", &t, |e| e.push("a".to_owned()))
+            .unwrap();
+        assert!(key.get_raw_value("List").is_err());
+        t.commit().unwrap();
+        let list: Vec<String> = key.get_value("List").unwrap();
+        assert_eq!(list, vec!["a"]);
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_compare_and_swap_value() {
+    use winreg2::types::ToRegValue;
+    with_key!(key, "CompareAndSwap" => {
+        assert!(!key
+            .compare_and_swap_value("Version", &1u32.to_reg_value(), &2u32.to_reg_value())
+            .unwrap());
+        key.set_value("Version", &1u32).unwrap();
+        assert!(key
+            .compare_and_swap_value("Version", &1u32.to_reg_value(), &2u32.to_reg_value())
+            .unwrap());
+        assert_eq!(key.get_value::<u32, _>("Version").unwrap(), 2);
+        assert!(!key
+            .compare_and_swap_value("Version", &1u32.to_reg_value(), &3u32.to_reg_value())
+            .unwrap());
+        // same data with another type doesn't match
+        assert!(!key
+            .compare_and_swap_value("Version", &2u64.to_reg_value(), &3u32.to_reg_value())
+            .unwrap());
+        assert_eq!(key.get_value::<u32, _>("Version").unwrap(), 2);
+
+        // of concurrent swaps from the same version exactly one succeeds
+        let threads: Vec<_> = (0..4u32)
+            .map(|t| {
+                std::thread::spawn(move || {
+                    let key = RegKey::predef(HKEY_CURRENT_USER)
+                        .open_subkey_with_flags("Software\\WinRegRsTestCompareAndSwap", KEY_ALL_ACCESS)
+                        .unwrap();
+                    key.compare_and_swap_value(
+                        "Version",
+                        &2u32.to_reg_value(),
+                        &(10 + t).to_reg_value(),
+                    )
+                    .unwrap()
+                })
+            })
+            .collect();
+        let swapped = threads
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .filter(|&swapped| swapped)
+            .count();
+        assert_eq!(swapped, 1);
+        assert!(key.get_value::<u32, _>("Version").unwrap() >= 10);
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_set_values() {
+    with_key!(key, "SetValues" => {
+        key.set_values(vec![("A", 1u32.to_reg_value()), ("B", "b".to_reg_value())]).unwrap();
+        assert_eq!(key.get_value::<u32, _>("A").unwrap(), 1);
+        assert_eq!(key.get_value::<String, _>("B").unwrap(), "b");
+        // a name longer than the limit fails the group
+        let long = "x".repeat(20_000);
+        let values = vec![("C".to_owned(), 3u32.to_reg_value()), (long, 4u32.to_reg_value())];
+        assert!(key.set_values(values).is_err());
+        assert!(key.get_raw_value("C").is_err());
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_update_value() {
+    with_key!(key, "UpdateValue" => {
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    let key = RegKey::predef(HKEY_CURRENT_USER)
+                        .open_subkey_with_flags("Software\\WinRegRsTestUpdateValue", KEY_ALL_ACCESS)
+                        .unwrap();
+                    for _ in 0..10 {
+                        key.update_value("Counter", |n: Option<u32>| n.unwrap_or(0) + 1)
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+        assert_eq!(key.get_value::<u32, _>("Counter").unwrap(), 40);
+    });
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_rename_value() {
+    with_key!(key, "RenameValue" => {
+        key.set_value("InstallDir", &"C:\\App").unwrap();
+        key.rename_value("InstallDir", "InstallPath").unwrap();
+        assert!(key.get_raw_value("InstallDir").is_err());
+        assert_eq!(key.get_value::<String, _>("InstallPath").unwrap(), "C:\\App");
+        key.set_value("Other", &1u32).unwrap();
+        let err = key.rename_value("Other", "InstallPath").unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_ALREADY_EXISTS as i32));
+        // nothing changed on failure
+        assert_eq!(key.get_value::<u32, _>("Other").unwrap(), 1);
+        assert!(key.rename_value("Missing", "New").is_err());
+        assert!(key.get_raw_value("New").is_err());
+
+        key.rename_value("Other", "OTHER").unwrap();
+        let names: Vec<String> = key.enum_values().map(|v| v.unwrap().0).collect();
+        assert!(names.contains(&"OTHER".to_owned()));
+        assert!(!names.contains(&"Other".to_owned()));
+        assert_eq!(key.get_value::<u32, _>("OTHER").unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_tree_operations_do_not_follow_links() {
+    with_key!(key, "TreeLinks" => {
+        let (target, _disp) = key.create_subkey("Target").unwrap();
+        target.set_value("Keep", &1u32).unwrap();
+        let target_path = target.physical_path().unwrap().path;
+
+        let (tree, _disp) = key.create_subkey("Tree").unwrap();
+        tree.create_link("Link", &target_path).unwrap();
+
+        let (copy, _disp) = key.create_subkey("Copy").unwrap();
+        key.copy_tree("Tree", &copy).unwrap();
+        let copied = copy.open_subkey_nofollow("Link", KEY_READ).unwrap();
+        assert!(copied.key_flags().unwrap().link);
+        assert_eq!(copied.read_link().unwrap(), target_path);
+
+        key.delete_subkey_all("Tree").unwrap();
+        key.delete_subkey_all("Copy").unwrap();
+        assert_eq!(target.get_value::<u32, _>("Keep").unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_watch() {
+    with_key!(key, "Watch" => {
+        let path = "Software\\WinRegRsTestWatch";
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            let key = RegKey::predef(HKEY_CURRENT_USER)
+                .open_subkey_with_flags(path, KEY_SET_VALUE)
+                .unwrap();
+            key.set_value("Changed", &1u32).unwrap();
+        });
+        key.watch(NotifyFilter::REG_NOTIFY_CHANGE_LAST_SET, false).unwrap();
+        writer.join().unwrap();
+        assert_eq!(key.get_value::<u32, _>("Changed").unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_watch_event() {
+    use std::time::Duration;
+    with_key!(key, "WatchEvent" => {
+        let event = key
+            .watch_event(NotifyFilter::REG_NOTIFY_CHANGE_LAST_SET, true)
+            .unwrap();
+        assert!(!event.wait(Some(Duration::from_millis(10))).unwrap());
+        let (sub, _disp) = key.create_subkey("Sub").unwrap();
+        sub.set_value("Changed", &1u32).unwrap();
+        assert!(event.wait(Some(Duration::from_secs(1))).unwrap());
+        // a notification fires once until re-armed
+        assert!(!event.wait(Some(Duration::from_millis(10))).unwrap());
+        event.rearm().unwrap();
+        sub.set_value("Changed", &2u32).unwrap();
+        assert!(event.wait(Some(Duration::from_secs(1))).unwrap());
+    });
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_watch_async() {
+    use futures_core::Stream;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    with_key!(key, "WatchAsync" => {
+        let mut changes = key
+            .watch_async(NotifyFilter::REG_NOTIFY_CHANGE_LAST_SET, false)
+            .unwrap();
+        let path = "Software\\WinRegRsTestWatchAsync";
+        let writer = std::thread::spawn(move || {
+            let key = RegKey::predef(HKEY_CURRENT_USER)
+                .open_subkey_with_flags(path, KEY_SET_VALUE)
+                .unwrap();
+            for i in 0..2u32 {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                key.set_value("Changed", &i).unwrap();
+            }
+        });
+        block_on(changes.changed()).unwrap();
+        let mut changes = std::pin::Pin::new(&mut changes);
+        block_on(std::future::poll_fn(|cx| changes.as_mut().poll_next(cx)))
+            .unwrap()
+            .unwrap();
+        writer.join().unwrap();
+    });
+}
+
+#[test]
+fn test_connect_local() {
+    let hklm = RegKey::connect("", HKEY_LOCAL_MACHINE).unwrap();
+    assert_eq!(hklm.full_path().unwrap(), "HKEY_LOCAL_MACHINE");
+    let cur_ver = hklm
+        .open_subkey("SOFTWARE\\Microsoft\\Windows\\CurrentVersion")
+        .unwrap();
+    let _: String = cur_ver.get_value("ProgramFilesDir").unwrap();
+}
+
+#[test]
+fn test_save_and_restore() {
+    with_key!(key, "SaveRestore" => {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("RustSaveRestoreTest.hiv");
+        key.set_value("Kept", &1u32).unwrap();
+        key.create_subkey("Sub").unwrap();
+        match key.save_to_file(&file_path, HiveFormat::Latest) {
+            // needs an elevated process
+            Err(ref e) if e.raw_os_error() == Some(Foundation::ERROR_PRIVILEGE_NOT_HELD as i32) => {
+                return;
+            }
+            r => r.unwrap(),
+        }
+        let err = key.save_to_file(&file_path, HiveFormat::Latest).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_ALREADY_EXISTS as i32));
+
+        key.set_value("Kept", &2u32).unwrap();
+        key.set_value("Added", &3u32).unwrap();
+        key.delete_subkey("Sub").unwrap();
+        key.restore_from_file(&file_path, REG_FORCE_RESTORE).unwrap();
+        assert_eq!(key.get_value::<u32, _>("Kept").unwrap(), 1);
+        assert!(key.get_raw_value("Added").is_err());
+        assert!(key.open_subkey("Sub").is_ok());
+    });
+}
+
+#[test]
+fn test_load_key() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("RustLoadKeyTest.dat");
+    {
+        let key = RegKey::load_app_key(&file_path, false).unwrap();
+        key.set_value("Loaded", &1u32).unwrap();
+    }
+    let hku = RegKey::predef(HKEY_USERS);
+    let hive = match hku.load_key("WinRegRsTestLoadKey", &file_path) {
+        // needs an elevated process
+        Err(ref e) if e.raw_os_error() == Some(Foundation::ERROR_PRIVILEGE_NOT_HELD as i32) => {
+            return;
+        }
+        r => r.unwrap(),
+    };
+    assert_eq!(hive.key().get_value::<u32, _>("Loaded").unwrap(), 1);
+    hive.key().set_value("Written", &2u32).unwrap();
+    {
+        let other = hku.open_subkey("WinRegRsTestLoadKey").unwrap();
+        assert_eq!(other.get_value::<u32, _>("Written").unwrap(), 2);
+    }
+    hive.unload().unwrap();
+    assert!(hku.open_subkey("WinRegRsTestLoadKey").is_err());
+
+    let key = RegKey::load_app_key(&file_path, false).unwrap();
+    assert_eq!(key.get_value::<u32, _>("Written").unwrap(), 2);
+}
+
+#[cfg(feature = "transactions")]
+#[test]
+fn test_registry_view_transacted() {
+    with_key!(key, "ViewTransacted" => {
+        let t = winreg2::transaction::Transaction::new().unwrap();
+        let (sub, _disp) = key
+            .create_subkey_transacted_view("Sub", &t, RegistryView::V32)
+            .unwrap();
+        sub.set_value("Value", &1u32).unwrap();
+        key.open_subkey_transacted_view("Sub", &t, KEY_READ, RegistryView::V32)
+            .unwrap();
+        assert!(key.open_subkey("Sub").is_err());
+        drop(sub);
+        key.delete_subkey_transacted_view("Sub", &t, RegistryView::V32)
+            .unwrap();
+        t.commit().unwrap();
+        assert!(key.open_subkey("Sub").is_err());
+    });
+}
+
+#[test]
+fn test_get_value_with_flags() {
+    with_key!(key, "GetValueWithFlags" => {
+        let (sub, _disp) = key.create_subkey("Sub").unwrap();
+        sub.set_value("Number", &42u32).unwrap();
+        sub.set_raw_value(
+            "Expand",
+            &RegValue {
+                bytes: "%SystemRoot%\0".encode_utf16().flat_map(|c| c.to_le_bytes()).collect(),
+                vtype: REG_EXPAND_SZ,
+            },
+        )
+        .unwrap();
+
+        let n: u32 = key.get_value_with_flags("Sub", "Number", RRF_RT_REG_DWORD).unwrap();
+        assert_eq!(n, 42);
+        let err = key.get_raw_value_with_flags("Sub", "Number", RRF_RT_REG_SZ).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_UNSUPPORTED_TYPE as i32));
+
+        let expanded = key.get_raw_value_with_flags("Sub", "Expand", RRF_RT_REG_SZ).unwrap();
+        assert_eq!(expanded.vtype, REG_SZ);
+        assert!(!String::from_reg_value(&expanded).unwrap().contains('%'));
+        let raw: String = key
+            .get_value_with_flags("Sub", "Expand", RRF_RT_REG_EXPAND_SZ | RRF_NOEXPAND)
+            .unwrap();
+        assert_eq!(raw, "%SystemRoot%");
+
+        let own: u32 = sub.get_value_with_flags("", "Number", RRF_RT_ANY).unwrap();
+        assert_eq!(own, 42);
+    });
+}
+
+#[test]
+fn test_set_delete_value_in() {
+    with_key!(key, "ValueIn" => {
+        key.set_value_in("New\\Sub", "Count", &7u32).unwrap();
+        let sub = key.open_subkey("New\\Sub").unwrap();
+        assert_eq!(sub.get_value::<u32, _>("Count").unwrap(), 7);
+        key.set_raw_value_in("New\\Sub", "Raw", &RegValue { bytes: vec![1], vtype: REG_BINARY })
+            .unwrap();
+        assert_eq!(sub.get_raw_value("Raw").unwrap().bytes, vec![1]);
+        key.delete_value_in("New\\Sub", "Count").unwrap();
+        assert!(sub.get_raw_value("Count").is_err());
+        assert!(key.delete_value_in("New\\Sub", "Count").is_err());
+        assert!(key.delete_value_in("Missing", "Count").is_err());
+    });
+}
+
+#[test]
+fn test_query_multiple_values() {
+    with_key!(key, "QueryMultiple" => {
+        key.set_value("Name", &"MyProduct").unwrap();
+        key.set_value("Version", &3u32).unwrap();
+        let long = "x".repeat(4096);
+        key.set_value("Path", &long).unwrap();
+        let values = key.query_multiple_values(&["Name", "Version", "Path"]).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(String::from_reg_value(&values[0]).unwrap(), "MyProduct");
+        assert_eq!(u32::from_reg_value(&values[1]).unwrap(), 3);
+        assert_eq!(String::from_reg_value(&values[2]).unwrap(), long);
+        assert!(key.query_multiple_values(&["Name", "Missing"]).is_err());
+    });
+}
+
+#[test]
+fn test_flush() {
+    with_key!(key, "Flush" => {
+        key.set_value("Checkpoint", &42u64).unwrap();
+        key.flush().unwrap();
+        assert_eq!(key.get_value::<u64, _>("Checkpoint").unwrap(), 42);
+    });
+}
+
+#[test]
+fn test_try_clone() {
+    with_key!(key, "TryClone" => {
+        key.set_value("Name", &"MyProduct").unwrap();
+        let clone = key.try_clone().unwrap();
+        let worker = std::thread::spawn(move || clone.get_value::<String, _>("Name"));
+        assert_eq!(worker.join().unwrap().unwrap(), "MyProduct");
+        // the original handle stays usable after the clone is dropped
+        assert_eq!(key.get_value::<String, _>("Name").unwrap(), "MyProduct");
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER).try_clone().unwrap();
+        assert_eq!(hkcu.raw_handle(), HKEY_CURRENT_USER);
+    });
+}
+
+#[test]
+fn test_enum_size_hint() {
+    with_key!(key, "EnumSizeHint" => {
+        for name in &["A", "B", "C"] {
+            key.create_subkey(name).unwrap();
+            key.set_value(name, &1u32).unwrap();
+        }
+        let mut keys = key.enum_keys();
+        assert_eq!(keys.size_hint(), (3, None));
+        keys.next().unwrap().unwrap();
+        assert_eq!(keys.size_hint(), (2, None));
+        assert_eq!(keys.nth(1).unwrap().unwrap(), "C");
+        assert_eq!(keys.size_hint(), (0, None));
+        assert!(keys.next().is_none());
+        let mut values = key.enum_values().skip(2);
+        assert_eq!(values.size_hint(), (1, None));
+        assert_eq!(values.next().unwrap().unwrap().0, "C");
+        assert!(key.enum_values().nth(usize::MAX).is_none());
+
+        // keys added during the enumeration exceed the first count
+        let mut keys = key.enum_keys();
+        assert_eq!(keys.size_hint(), (3, None));
+        key.create_subkey("D").unwrap();
+        assert_eq!(keys.by_ref().count(), 4);
+        assert_eq!(keys.size_hint(), (0, None));
+
+        // the number of subkeys can't be queried
+        let key = key.open_subkey_with_flags("", KEY_ENUMERATE_SUB_KEYS).unwrap();
+        assert_eq!(key.enum_keys().size_hint(), (0, None));
+        assert_eq!(key.enum_keys().count(), 4);
+    });
+}
+
+#[test]
+fn test_enum_values_grow_buffers() {
+    with_key!(key, "EnumValuesGrow" => {
+        let small = RegValue { vtype: REG_BINARY, bytes: vec![1; 10] };
+        key.set_raw_value("A", &small).unwrap();
+        key.set_raw_value("B", &small).unwrap();
+        let mut values = key.enum_values();
+        assert_eq!(values.next().unwrap().unwrap().1, small);
+        // longer than the name and data seen when the enumeration started
+        let long_name = "C".repeat(300);
+        let big = RegValue { vtype: REG_BINARY, bytes: vec![2; 100_000] };
+        key.set_raw_value(&long_name, &big).unwrap();
+        let rest: Vec<_> = values.map(|v| v.unwrap()).collect();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[1], (long_name, big));
+    });
+}
+
+#[test]
+fn test_enum_os_names() {
+    use std::os::windows::ffi::OsStringExt;
+    with_key!(key, "EnumOsNames" => {
+        // unpaired surrogate, legal in the registry but not valid UTF-16
+        let name = OsString::from_wide(&[0xD800, 'x' as u16]);
+        key.set_value(&name, &1u32).unwrap();
+        key.create_subkey(&name).unwrap();
+        assert!(key.enum_values().next().unwrap().is_err());
+        assert!(key.enum_keys().next().unwrap().is_err());
+        let values: Vec<_> = key.enum_values_os().map(|v| v.unwrap().0).collect();
+        assert_eq!(values, vec![name.clone()]);
+        let keys: Vec<_> = key.enum_keys_os().map(|k| k.unwrap()).collect();
+        assert_eq!(keys, vec![name.clone()]);
+        key.delete_subkey(&name).unwrap();
+    });
+}
+
+#[test]
+fn test_enum_keys_with_info() {
+    use std::time::{Duration, SystemTime};
+    with_key!(key, "EnumKeysWithInfo" => {
+        let before = SystemTime::now() - Duration::from_secs(60);
+        key.create_subkey("Plain").unwrap();
+        key.create_subkey_with_class("Classy", "PerfClass", REG_OPTION_NON_VOLATILE, KEY_ALL_ACCESS)
+            .unwrap();
+        let mut infos: Vec<_> = key.enum_keys_with_info().map(|i| i.unwrap()).collect();
+        infos.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].0, "Classy");
+        assert_eq!(infos[0].2.as_deref(), Some("PerfClass"));
+        assert_eq!(infos[1].0, "Plain");
+        assert_eq!(infos[1].2, None);
+        assert!(infos.iter().all(|i| i.1 > before));
+    });
+}
+
+#[test]
+fn test_unknown_value_type() {
+    assert_eq!(RegType::from(11), REG_QWORD);
+    assert!(matches!(RegType::from(0x4000_0001), RegType::Unknown(t) if t.code() == 0x4000_0001));
+    assert_eq!(u32::from(RegType::from(0x4000_0001)), 0x4000_0001);
+    assert_eq!(u32::from(REG_MULTI_SZ), 7);
+    with_key!(key, "UnknownType" => {
+        let vendor = RegValue { bytes: vec![1, 2, 3], vtype: RegType::from(0x4000_0001) };
+        key.set_raw_value("Vendor", &vendor).unwrap();
+        assert_eq!(key.get_raw_value("Vendor").unwrap(), vendor);
+        let (name, value) = key.enum_values().next().unwrap().unwrap();
+        assert_eq!((name.as_str(), value), ("Vendor", vendor));
+    });
+}
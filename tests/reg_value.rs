@@ -14,3 +14,9 @@ macro_rules! test_display {
 test_display!(test_display_string, "Test\\123");
 test_display!(test_display_u32, 1234u32);
 test_display!(test_display_u64, 1234567890u64);
+
+#[test]
+fn test_display_big_endian() {
+    let rval = winreg2::types::BigEndian(1234).to_reg_value();
+    assert_eq!(rval.to_string(), "1234");
+}
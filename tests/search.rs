@@ -0,0 +1,127 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use windows_sys::Win32::Foundation;
+use winreg2::search::{self, Change, ReplaceOptions};
+
+mod common;
+
+#[test]
+fn test_replace() {
+    with_key!(key, "Replace" => {
+        let (sub, _disp) = key.create_subkey("Old\\Sub").unwrap();
+        key.set_value("Path", &"C:\\OLD\\bin").unwrap();
+        sub.set_value("List", &vec!["c:\\old\\a", "unchanged"]).unwrap();
+        sub.set_value("OldName", &1u32).unwrap();
+
+        let options = ReplaceOptions::new("c:\\old", "D:\\New")
+            .ignore_case(true)
+            .value_names(true)
+            .key_names(true);
+        let dry = search::replace(&key, &options.clone().dry_run(true)).unwrap();
+        assert_eq!(dry.len(), 2);
+        assert_eq!(key.get_value::<String, _>("Path").unwrap(), "C:\\OLD\\bin");
+
+        let changes = search::replace(&key, &options).unwrap();
+        assert_eq!(changes, dry);
+        assert_eq!(key.get_value::<String, _>("Path").unwrap(), "D:\\New\\bin");
+        let sub = key.open_subkey("Old\\Sub").unwrap();
+        let list: Vec<String> = sub.get_value("List").unwrap();
+        assert_eq!(list, vec!["D:\\New\\a", "unchanged"]);
+
+        let options = ReplaceOptions::new("Old", "New").key_names(true).value_names(true);
+        let changes = search::replace(&key, &options).unwrap();
+        assert!(changes.contains(&Change::KeyName {
+            key: "Old".to_owned(),
+            new: "New".to_owned(),
+        }));
+        assert!(changes.contains(&Change::ValueName {
+            key: "Old\\Sub".to_owned(),
+            old: "OldName".to_owned(),
+            new: "NewName".to_owned(),
+        }));
+        let sub = key.open_subkey("New\\Sub").unwrap();
+        assert_eq!(sub.get_value::<u32, _>("NewName").unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_replace_value_names() {
+    with_key!(key, "ReplaceValueNames" => {
+        key.set_value("Path", &"C:\\bin").unwrap();
+        key.set_value("x1", &1u32).unwrap();
+
+        // a case-only rename keeps the value
+        let options = ReplaceOptions::new("path", "PATH").ignore_case(true).value_names(true);
+        let changes = search::replace(&key, &options).unwrap();
+        assert_eq!(changes, vec![Change::ValueName {
+            key: String::new(),
+            old: "Path".to_owned(),
+            new: "PATH".to_owned(),
+        }]);
+        assert_eq!(key.get_value::<String, _>("PATH").unwrap(), "C:\\bin");
+        let names: Vec<String> = key.enum_values().map(|v| v.unwrap().0).collect();
+        assert!(names.contains(&"PATH".to_owned()));
+
+        // replacing a name with itself renames nothing
+        let options = ReplaceOptions::new("x", "x").value_names(true);
+        assert!(search::replace(&key, &options).unwrap().is_empty());
+        assert_eq!(key.get_value::<u32, _>("x1").unwrap(), 1);
+
+        // another value with the new name is not overwritten
+        key.set_value("y1", &2u32).unwrap();
+        let options = ReplaceOptions::new("x", "y").value_names(true);
+        for options in &[options.clone().dry_run(true), options] {
+            let err = search::replace(&key, options).unwrap_err();
+            assert_eq!(err.raw_os_error(), Some(Foundation::ERROR_ALREADY_EXISTS as i32));
+        }
+        assert_eq!(key.get_value::<u32, _>("x1").unwrap(), 1);
+        assert_eq!(key.get_value::<u32, _>("y1").unwrap(), 2);
+    });
+}
+
+#[test]
+fn test_find_keys() {
+    with_key!(key, "FindKeys" => {
+        key.create_subkey("Vendor1\\Uninstall\\AppA\\Deep").unwrap();
+        key.create_subkey("Vendor2\\Uninstall\\AppB").unwrap();
+        key.create_subkey("Vendor2\\Other\\AppC").unwrap();
+        let mut found: Vec<String> = key
+            .find_keys("*\\uninstall\\*")
+            .map(|e| e.unwrap().path)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec!["Vendor1\\Uninstall\\AppA", "Vendor2\\Uninstall\\AppB"]);
+        let deep: Vec<String> = key.find_keys("**\\De?p").map(|e| e.unwrap().path).collect();
+        assert_eq!(deep, vec!["Vendor1\\Uninstall\\AppA\\Deep"]);
+        // rooted patterns never match relative paths
+        assert_eq!(key.find_keys("HKCU\\*").count(), 0);
+    });
+}
+
+#[test]
+fn test_find_values() {
+    with_key!(key, "FindValues" => {
+        key.set_value("InstallPath", &"C:\\App").unwrap();
+        let (sub, _disp) = key.create_subkey("Plugins\\One").unwrap();
+        sub.set_value("DataPath", &"C:\\Data").unwrap();
+        sub.set_value("Version", &1u32).unwrap();
+        let mut found: Vec<(String, String)> = key
+            .find_values("**\\*path")
+            .map(|v| v.map(|v| (v.key, v.name)).unwrap())
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("".to_owned(), "InstallPath".to_owned()),
+                ("Plugins\\One".to_owned(), "DataPath".to_owned()),
+            ]
+        );
+        let version: Vec<_> = key.find_values("*\\*\\Version").map(|v| v.unwrap()).collect();
+        assert_eq!(version.len(), 1);
+        assert_eq!(version[0].value.bytes, vec![1, 0, 0, 0]);
+    });
+}
@@ -0,0 +1,64 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use windows_sys::Win32::Foundation;
+use winreg2::enums::*;
+use winreg2::security::SecurityDescriptor;
+
+mod common;
+
+#[test]
+fn test_get_set_security() {
+    with_key!(key, "Security" => {
+        let (src, _disp) = key.create_subkey("Src").unwrap();
+        let (dest, _disp) = key.create_subkey("Dest").unwrap();
+        let sd = src.get_security(DACL_SECURITY_INFORMATION).unwrap();
+        assert!(!sd.is_dacl_protected().unwrap());
+        let copy = SecurityDescriptor::from_bytes(sd.as_bytes()).unwrap();
+        assert_eq!(sd, copy);
+
+        dest.set_security(DACL_SECURITY_INFORMATION | PROTECTED_DACL_SECURITY_INFORMATION, &copy)
+            .unwrap();
+        let protected = dest.get_security(DACL_SECURITY_INFORMATION).unwrap();
+        assert!(protected.is_dacl_protected().unwrap());
+
+        let read_only = key.open_subkey_with_flags("Dest", KEY_READ).unwrap();
+        assert!(read_only.set_security(DACL_SECURITY_INFORMATION, &sd).is_err());
+    });
+}
+
+#[test]
+fn test_invalid_security_descriptor() {
+    let err = SecurityDescriptor::from_bytes(&[1, 2, 3]).unwrap_err();
+    assert_eq!(
+        err.raw_os_error(),
+        Some(Foundation::ERROR_INVALID_SECURITY_DESCR as i32)
+    );
+}
+
+#[test]
+fn test_sddl() {
+    with_key!(key, "Sddl" => {
+        let (sub, _disp) = key.create_subkey("Locked").unwrap();
+        let sddl = sub.get_sddl().unwrap();
+        assert!(sddl.starts_with("O:"));
+        assert!(sddl.contains("D:"));
+
+        sub.set_sddl("D:P(A;OICI;KA;;;BA)(A;OICI;KA;;;SY)(A;OICI;KA;;;OW)").unwrap();
+        let sd = sub.get_security(DACL_SECURITY_INFORMATION).unwrap();
+        assert!(sd.is_dacl_protected().unwrap());
+        assert_eq!(
+            sd.to_sddl(DACL_SECURITY_INFORMATION).unwrap(),
+            "D:P(A;OICI;KA;;;BA)(A;OICI;KA;;;SY)(A;OICI;KA;;;OW)"
+        );
+
+        let parsed = SecurityDescriptor::from_sddl("O:BAD:(A;;KR;;;WD)").unwrap();
+        assert_eq!(
+            parsed.information().unwrap(),
+            OWNER_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION
+        );
+        assert!(SecurityDescriptor::from_sddl("D:(bogus)").is_err());
+    });
+}
@@ -158,3 +158,363 @@ fn test_serialization_all_transacted() {
         assert_eq!(v2, v1);
     });
 }
+
+#[test]
+fn test_serialization_transacted_rollback() {
+    use serde::Serialize;
+    use winreg2::encoder::Encoder;
+    use winreg2::transaction::Transaction;
+    let v1 = AllFields::test_val();
+
+    with_key!(key, "SerializationTransactedRollback" => {
+        let transaction = Transaction::new().unwrap();
+        let mut encoder = Encoder::from_key_transacted(&key, &transaction).unwrap();
+        v1.serialize(&mut encoder).unwrap();
+        transaction.rollback().unwrap();
+        assert!(key.get_raw_value("t_string").is_err());
+        assert!(key.open_subkey("t_struct").is_err());
+        assert_eq!(key.enum_keys().count(), 0);
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Profiles {
+    active: String,
+    profiles: std::collections::BTreeMap<String, Rectangle>,
+}
+
+#[test]
+fn test_serialization_nested_maps() {
+    let mut profiles = std::collections::BTreeMap::new();
+    profiles.insert(
+        "small".to_owned(),
+        Rectangle {
+            coords: Coords { x: 1, y: 2 },
+            size: Size { w: 3, h: 4 },
+        },
+    );
+    profiles.insert(
+        "large".to_owned(),
+        Rectangle {
+            coords: Coords { x: 10, y: 20 },
+            size: Size { w: 300, h: 400 },
+        },
+    );
+    let v1 = Profiles {
+        active: "small".to_owned(),
+        profiles,
+    };
+
+    with_key!(key, "SerializationNestedMaps" => {
+        key.encode(&v1).unwrap();
+        let size = key.open_subkey("profiles\\large\\size").unwrap();
+        assert_eq!(size.get_value::<u32, _>("w").unwrap(), 300);
+        let v2: Profiles = key.decode().unwrap();
+        assert_eq!(v2, v1);
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sequences {
+    names: Vec<String>,
+    with_empty: Vec<String>,
+    ports: Vec<u32>,
+    points: Vec<Coords>,
+    none: Vec<u32>,
+}
+
+impl Sequences {
+    fn test_val() -> Self {
+        Sequences {
+            names: vec!["one".to_owned(), "two".to_owned()],
+            with_empty: vec!["a".to_owned(), "".to_owned()],
+            ports: vec![80, 443],
+            points: vec![Coords { x: 1, y: 2 }, Coords { x: 3, y: 4 }],
+            none: vec![],
+        }
+    }
+}
+
+#[test]
+fn test_serialization_seq() {
+    use winreg2::enums::*;
+    let v1 = Sequences::test_val();
+
+    with_key!(key, "SerializationSeq" => {
+        key.encode(&v1).unwrap();
+        assert_eq!(key.get_raw_value("names").unwrap().vtype, REG_MULTI_SZ);
+        let with_empty = key.open_subkey("with_empty").unwrap();
+        assert_eq!(with_empty.get_value::<String, _>("1").unwrap(), "");
+        let ports = key.open_subkey("ports").unwrap();
+        assert_eq!(ports.get_value::<u32, _>("1").unwrap(), 443);
+        let point = key.open_subkey("points\\1").unwrap();
+        assert_eq!(point.get_value::<u32, _>("x").unwrap(), 3);
+        let v2: Sequences = key.decode().unwrap();
+        assert_eq!(v2, v1);
+    });
+}
+
+#[test]
+fn test_serialization_seq_indexed() {
+    use serde::Serialize;
+    use winreg2::encoder::{Encoder, EncoderOptions, SeqEncoding};
+    let v1 = Sequences::test_val();
+
+    with_key!(key, "SerializationSeqIndexed" => {
+        let options = EncoderOptions::new().seq_encoding(SeqEncoding::Indexed);
+        let mut encoder = Encoder::from_key_with_options(&key, options).unwrap();
+        v1.serialize(&mut encoder).unwrap();
+        encoder.commit().unwrap();
+        let names = key.open_subkey("names").unwrap();
+        assert_eq!(names.get_value::<String, _>("0").unwrap(), "one");
+        let v2: Sequences = key.decode().unwrap();
+        assert_eq!(v2, v1);
+    });
+}
+
+#[test]
+fn test_serialization_root_seq() {
+    let v1 = vec![Coords { x: 1, y: 2 }, Coords { x: 3, y: 4 }];
+
+    with_key!(key, "SerializationRootSeq" => {
+        key.encode(&v1).unwrap();
+        assert!(key.open_subkey("1").is_ok());
+        let v2: Vec<Coords> = key.decode().unwrap();
+        assert_eq!(v2, v1);
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    Off,
+    Fixed(u32),
+    Range(u32, u32),
+    Window { coords: Coords, title: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Modes {
+    off: Mode,
+    fixed: Mode,
+    range: Mode,
+    window: Mode,
+    list: Vec<Mode>,
+}
+
+#[test]
+fn test_serialization_enum() {
+    use winreg2::enums::*;
+    let v1 = Modes {
+        off: Mode::Off,
+        fixed: Mode::Fixed(5),
+        range: Mode::Range(1, 10),
+        window: Mode::Window {
+            coords: Coords { x: 1, y: 2 },
+            title: "main".to_owned(),
+        },
+        list: vec![Mode::Off, Mode::Fixed(7)],
+    };
+
+    with_key!(key, "SerializationEnum" => {
+        key.encode(&v1).unwrap();
+        assert_eq!(key.get_value::<String, _>("off").unwrap(), "Off");
+        let fixed = key.open_subkey("fixed").unwrap();
+        assert_eq!(fixed.get_value::<String, _>("").unwrap(), "Fixed");
+        assert_eq!(fixed.get_value::<u32, _>("0").unwrap(), 5);
+        let window = key.open_subkey("window").unwrap();
+        assert_eq!(window.get_value::<String, _>("title").unwrap(), "main");
+        let v2: Modes = key.decode().unwrap();
+        assert_eq!(v2, v1);
+    });
+
+    with_key!(key, "SerializationEnumList" => {
+        let v1 = Modes {
+            off: Mode::Off,
+            fixed: Mode::Off,
+            range: Mode::Off,
+            window: Mode::Off,
+            list: vec![Mode::Off, Mode::Off],
+        };
+        key.encode(&v1).unwrap();
+        assert_eq!(key.get_raw_value("list").unwrap().vtype, REG_MULTI_SZ);
+        let v2: Modes = key.decode().unwrap();
+        assert_eq!(v2, v1);
+    });
+}
+
+#[test]
+fn test_deserialize_any() {
+    use serde::Deserialize;
+    use winreg2::decoder::{BinaryEncoding, Decoder, DecoderOptions};
+    use winreg2::types::BigEndian;
+
+    with_key!(key, "DeserializeAny" => {
+        key.set_value("dword", &7u32).unwrap();
+        key.set_value("big_endian", &BigEndian(8)).unwrap();
+        key.set_value("qword", &9u64).unwrap();
+        key.set_value("string", &"text").unwrap();
+        key.set_value("multi", &vec!["a", "b"]).unwrap();
+        key.set_value("binary", &vec![0xDEu8, 0xAD, 0xBE, 0xEF]).unwrap();
+        let (sub, _disp) = key.create_subkey("sub").unwrap();
+        sub.set_value("x", &1u32).unwrap();
+
+        let json = serde_json::Value::deserialize(&mut Decoder::from_key(&key).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "dword": 7,
+                "big_endian": 8,
+                "qword": 9,
+                "string": "text",
+                "multi": ["a", "b"],
+                "binary": [0xDE, 0xAD, 0xBE, 0xEF],
+                "sub": { "x": 1 },
+            })
+        );
+
+        let options = DecoderOptions::new().binary_encoding(BinaryEncoding::Hex);
+        let mut decoder = Decoder::from_key_with_options(&key, options).unwrap();
+        let json = serde_json::Value::deserialize(&mut decoder).unwrap();
+        assert_eq!(json["binary"], "deadbeef");
+        let options = DecoderOptions::new().binary_encoding(BinaryEncoding::Base64);
+        let mut decoder = Decoder::from_key_with_options(&key, options).unwrap();
+        let json = serde_json::Value::deserialize(&mut decoder).unwrap();
+        assert_eq!(json["binary"], "3q2+7w==");
+    });
+}
+
+#[test]
+fn test_serialization_u64_as_string() {
+    use serde::Serialize;
+    use winreg2::encoder::{Encoder, EncoderOptions};
+
+    with_key!(key, "SerializationU64AsString" => {
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        struct Big {
+            w: u64,
+        }
+        let options = EncoderOptions::new().u64_as_string(true);
+        let mut encoder = Encoder::from_key_with_options(&key, options).unwrap();
+        Big { w: u64::MAX }.serialize(&mut encoder).unwrap();
+        encoder.commit().unwrap();
+        assert_eq!(key.get_value::<String, _>("w").unwrap(), u64::MAX.to_string());
+        let v: Big = key.decode().unwrap();
+        assert_eq!(v, Big { w: u64::MAX });
+    });
+}
+
+#[test]
+fn test_decoder_options() {
+    use serde::Deserialize;
+    use winreg2::decoder::{Decoder, DecoderOptions, UnknownFields};
+    use winreg2::enums::*;
+    use winreg2::RegValue;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Settings {
+        path: String,
+        count: u32,
+    }
+
+    with_key!(key, "DecoderOptions" => {
+        let path = RegValue {
+            bytes: "%SystemRoot%\\x\0".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            vtype: REG_EXPAND_SZ,
+        };
+        key.set_raw_value("Path", &path).unwrap();
+        key.set_value("Count", &3u32).unwrap();
+        key.set_value("Other", &"x").unwrap();
+
+        // `Path` and `Count` don't match the fields
+        assert!(Settings::deserialize(&mut Decoder::from_key(&key).unwrap()).is_err());
+
+        let options = DecoderOptions::new().case_insensitive(true);
+        let v = Settings::deserialize(&mut Decoder::from_key_with_options(&key, options).unwrap())
+            .unwrap();
+        assert_eq!(v, Settings { path: "%SystemRoot%\\x".to_owned(), count: 3 });
+
+        let options = options.expand_strings(true);
+        let v = Settings::deserialize(&mut Decoder::from_key_with_options(&key, options).unwrap())
+            .unwrap();
+        assert!(!v.path.contains('%'));
+        assert!(v.path.ends_with("\\x"));
+
+        let options = options.unknown_fields(UnknownFields::Error);
+        let mut decoder = Decoder::from_key_with_options(&key, options).unwrap();
+        assert!(Settings::deserialize(&mut decoder).is_err());
+    });
+}
+
+#[test]
+fn test_serialization_naming_convention() {
+    use serde::{Deserialize, Serialize};
+    use winreg2::decoder::{Decoder, DecoderOptions};
+    use winreg2::encoder::{Encoder, EncoderOptions, NamingConvention};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Server {
+        server_name: String,
+        max_connections: u32,
+        window_size: Size,
+    }
+    let v1 = Server {
+        server_name: "localhost".to_owned(),
+        max_connections: 10,
+        window_size: Size { w: 640, h: 480 },
+    };
+
+    assert_eq!(
+        NamingConvention::PascalCase.apply("server_name"),
+        "ServerName"
+    );
+    assert_eq!(
+        NamingConvention::CamelCase.apply("server_name"),
+        "serverName"
+    );
+    assert_eq!(NamingConvention::AsIs.apply("server_name"), "server_name");
+
+    with_key!(key, "SerializationNamingConvention" => {
+        let options = EncoderOptions::new().naming_convention(NamingConvention::PascalCase);
+        let mut encoder = Encoder::from_key_with_options(&key, options).unwrap();
+        v1.serialize(&mut encoder).unwrap();
+        encoder.commit().unwrap();
+        assert_eq!(key.get_value::<String, _>("ServerName").unwrap(), "localhost");
+        let size = key.open_subkey("WindowSize").unwrap();
+        assert_eq!(size.get_value::<u32, _>("W").unwrap(), 640);
+
+        let options = DecoderOptions::new().naming_convention(NamingConvention::PascalCase);
+        let mut decoder = Decoder::from_key_with_options(&key, options).unwrap();
+        let v2 = Server::deserialize(&mut decoder).unwrap();
+        assert_eq!(v2, v1);
+    });
+}
+
+#[test]
+fn test_reg_value_serde() {
+    use winreg2::enums::*;
+    use winreg2::RegValue;
+
+    let value = RegValue {
+        bytes: vec![0xDE, 0xAD, 0xBE, 0xEF],
+        vtype: REG_BINARY,
+    };
+    let json = serde_json::to_value(&value).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "vtype": "REG_BINARY", "bytes": "deadbeef" })
+    );
+    assert_eq!(serde_json::from_value::<RegValue>(json).unwrap(), value);
+
+    let json = serde_json::json!({ "vtype": 0x1234, "bytes": [1, 2] });
+    let value: RegValue = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(value.vtype, RegType::from(0x1234));
+    assert!(matches!(value.vtype, RegType::Unknown(t) if t.code() == 0x1234));
+    assert_eq!(value.bytes, vec![1, 2]);
+    assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        serde_json::json!({ "vtype": 0x1234, "bytes": "0102" })
+    );
+
+    assert!(serde_json::from_str::<RegValue>(r#"{"vtype":"REG_XX","bytes":""}"#).is_err());
+    assert!(serde_json::from_str::<RegValue>(r#"{"vtype":"REG_SZ","bytes":"abc"}"#).is_err());
+}
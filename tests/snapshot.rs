@@ -0,0 +1,33 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+mod common;
+
+#[test]
+fn test_snapshot_restore() {
+    with_key!(key, "SnapshotRestore" => {
+        key.set_value("Mode", &"stable").unwrap();
+        key.set_value("Kept", &1u32).unwrap();
+        key.create_subkey("Cache\\Entries").unwrap().0.set_value("A", &1u32).unwrap();
+        key.create_subkey("Empty").unwrap();
+        let before = key.snapshot().unwrap();
+        assert_eq!(before.key_paths().collect::<Vec<_>>(), vec!["", "Cache", "Cache\\Entries", "Empty"]);
+        assert_eq!(before.get_raw_value("Cache\\Entries", "A").unwrap().bytes, vec![1, 0, 0, 0]);
+
+        key.set_value("Mode", &"experimental").unwrap();
+        key.delete_value("Kept").unwrap();
+        key.set_value("Extra", &2u32).unwrap();
+        key.delete_subkey_all("Cache").unwrap();
+        key.delete_subkey("Empty").unwrap();
+        key.create_subkey("New\\Sub").unwrap();
+        let after = key.snapshot().unwrap();
+        assert!(!before.diff(&after).is_empty());
+
+        before.restore_to(&key).unwrap();
+        let restored = key.snapshot().unwrap();
+        assert!(before.diff(&restored).is_empty(), "{:?}", before.diff(&restored));
+        assert_eq!(restored, before);
+    });
+}
@@ -0,0 +1,24 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use winreg2::system;
+
+#[test]
+fn test_registry_quota() {
+    let (used, allowed) = system::registry_quota().unwrap();
+    assert!(used > 0);
+    assert!(used <= allowed);
+}
+
+#[test]
+fn test_hive_files() {
+    let hives = system::hive_files().unwrap();
+    let software = hives
+        .iter()
+        .find(|h| h.key.eq_ignore_ascii_case("HKEY_LOCAL_MACHINE\\SOFTWARE"))
+        .unwrap();
+    assert!(software.file.to_uppercase().ends_with("\\CONFIG\\SOFTWARE"));
+    assert!(software.size.unwrap() > 0);
+}
@@ -0,0 +1,31 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+use winreg2::util::{self, Name};
+
+#[test]
+fn test_name_eq() {
+    assert!(util::name_eq("Path", "PATH"));
+    assert!(util::name_eq("straße", "STRAßE"));
+    assert!(util::name_eq("ÄÖÜ", "äöü"));
+    assert!(!util::name_eq("straße", "STRASSE"));
+    assert!(!util::name_eq("Path", "Paths"));
+    assert_eq!(util::name_cmp("a", "B"), Ordering::Less);
+    assert_eq!(util::name_cmp("b", "A"), Ordering::Greater);
+}
+
+#[test]
+fn test_name_collections() {
+    let names: HashSet<Name> = vec!["Path", "PATH", "path", "Other"]
+        .into_iter()
+        .map(Name::from)
+        .collect();
+    assert_eq!(names.len(), 2);
+    let sorted: BTreeSet<Name> = vec!["b", "A", "c"].into_iter().map(Name::from).collect();
+    let sorted: Vec<_> = sorted.iter().map(|n| n.to_string()).collect();
+    assert_eq!(sorted, vec!["A", "b", "c"]);
+}
@@ -0,0 +1,28 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use winreg2::enums::*;
+use winreg2::virtualization;
+use winreg2::RegKey;
+
+#[test]
+fn test_virtual_store_path() {
+    assert_eq!(
+        virtualization::virtual_store_path("SOFTWARE\\LegacyApp").as_deref(),
+        Some("Software\\Classes\\VirtualStore\\MACHINE\\SOFTWARE\\LegacyApp")
+    );
+    assert_eq!(virtualization::virtual_store_path("SYSTEM\\Setup"), None);
+    assert!(virtualization::read_virtualstore_counterpart("SYSTEM\\Setup").is_err());
+}
+
+#[test]
+fn test_is_virtualized() {
+    // test binaries are 64-bit and never virtualized
+    assert!(!virtualization::is_process_virtualized().unwrap());
+    let software = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey("SOFTWARE\\Microsoft")
+        .unwrap();
+    assert!(!virtualization::is_virtualized(&software).unwrap());
+}
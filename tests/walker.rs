@@ -0,0 +1,198 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use winreg2::enums::*;
+use winreg2::path::RegPath;
+use winreg2::walker::{ErrorPolicy, WalkOptions, WalkOrder};
+use winreg2::RegKey;
+
+mod common;
+
+#[test]
+fn test_enum_key_infos() {
+    with_key!(key, "EnumKeyInfos" => {
+        key.create_subkey("Regular").unwrap();
+        key.create_volatile_subkey("Volatile").unwrap();
+        let mut infos: Vec<_> = key.enum_key_infos(true).map(|i| i.unwrap()).collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(infos.len(), 2);
+        assert!(!infos[0].flags.unwrap().volatile);
+        assert!(infos[1].flags.unwrap().volatile);
+        assert!(key.enum_key_infos(false).all(|i| i.unwrap().flags.is_none()));
+    });
+}
+
+#[test]
+fn test_walk() {
+    with_key!(key, "Walk" => {
+        key.create_subkey("A\\B\\C").unwrap();
+        key.create_subkey("D").unwrap();
+        let mut paths: Vec<_> = key.walk().map(|e| e.unwrap().path).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["A", "A\\B", "A\\B\\C", "D"]);
+
+        let shallow: Vec<_> = key
+            .walk_with_options(WalkOptions::new().max_depth(1))
+            .map(|e| e.unwrap())
+            .collect();
+        assert_eq!(shallow.len(), 2);
+        assert!(shallow.iter().all(|e| e.depth == 1 && e.flags.is_none()));
+    });
+}
+
+#[test]
+fn test_walk_breadth_first() {
+    with_key!(key, "WalkBreadthFirst" => {
+        key.create_subkey("A\\B\\C").unwrap();
+        key.create_subkey("D\\E").unwrap();
+        let options = WalkOptions::new().order(WalkOrder::BreadthFirst);
+        let depths: Vec<_> = key
+            .walk_with_options(options)
+            .map(|e| e.unwrap().depth)
+            .collect();
+        assert_eq!(depths, vec![1, 1, 2, 2, 3]);
+    });
+}
+
+#[test]
+fn test_walk_max_open_handles() {
+    with_key!(key, "WalkMaxOpenHandles" => {
+        key.create_subkey("A\\B\\C\\D").unwrap();
+        key.create_subkey("A\\E").unwrap();
+        key.create_subkey("F").unwrap();
+        let mut expected: Vec<_> = key.walk().map(|e| e.unwrap().path).collect();
+        expected.sort();
+        for max in 0..3 {
+            let options = WalkOptions::new().max_open_handles(max);
+            let mut paths: Vec<_> = key
+                .walk_with_options(options)
+                .map(|e| e.unwrap().path)
+                .collect();
+            paths.sort();
+            assert_eq!(paths, expected);
+        }
+    });
+}
+
+#[test]
+fn test_walk_error_policy() {
+    with_key!(key, "WalkErrorPolicy" => {
+        for &policy in &[ErrorPolicy::Abort, ErrorPolicy::Skip, ErrorPolicy::Collect] {
+            key.create_subkey("A\\B").unwrap();
+            key.create_subkey("C").unwrap();
+            let options = WalkOptions::new()
+                .order(WalkOrder::BreadthFirst)
+                .error_policy(policy);
+            let mut walk = key.walk_with_options(options);
+            assert_eq!(walk.by_ref().take(2).filter(|e| e.is_ok()).count(), 2);
+            // queued keys are re-opened by path, so this fails when "A" is enumerated
+            key.delete_subkey_all("A").unwrap();
+            let rest: Vec<_> = walk.by_ref().collect();
+            match policy {
+                ErrorPolicy::Abort => {
+                    assert_eq!(rest.len(), 1);
+                    assert!(rest[0].is_err());
+                }
+                ErrorPolicy::Skip => {
+                    assert!(rest.is_empty());
+                    assert_eq!(walk.skipped(), ["A"]);
+                }
+                ErrorPolicy::Collect => {
+                    assert!(rest.is_empty());
+                    assert_eq!(walk.errors().len(), 1);
+                    assert_eq!(walk.errors()[0].path, "A");
+                    assert_eq!(walk.errors()[0].error.kind(), std::io::ErrorKind::NotFound);
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn test_walk_reroot() {
+    with_key!(key, "WalkReroot" => {
+        key.create_subkey("A\\B").unwrap();
+        let base = RegPath::parse("HKLM\\SOFTWARE\\Dest");
+        let paths: Vec<_> = key
+            .walk()
+            .map(|e| e.unwrap().reroot(&base).to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "HKEY_LOCAL_MACHINE\\SOFTWARE\\Dest\\A",
+                "HKEY_LOCAL_MACHINE\\SOFTWARE\\Dest\\A\\B"
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_walk_does_not_follow_links() {
+    with_key!(key, "WalkLinks" => {
+        let target = "\\REGISTRY\\MACHINE\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion";
+        key.create_link("Link", target).unwrap();
+        let entries: Vec<_> = key
+            .walk_with_options(WalkOptions::new().key_flags(true))
+            .map(|e| e.unwrap())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].flags.unwrap().link);
+        key.open_subkey_nofollow("Link", KEY_ALL_ACCESS)
+            .unwrap()
+            .delete_link()
+            .unwrap();
+    });
+}
+
+#[test]
+fn test_walk_view() {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let software = hklm
+        .open_subkey_view(
+            "SOFTWARE\\Microsoft\\Windows NT",
+            KEY_READ,
+            RegistryView::V32,
+        )
+        .unwrap();
+    let options = WalkOptions::new().max_depth(1).view(RegistryView::V32);
+    assert!(software.walk_with_options(options).any(|e| e.is_ok()));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_walk() {
+    with_key!(key, "ParWalk" => {
+        for path in &["A\\B\\C", "A\\D", "E", "F\\G"] {
+            key.create_subkey(path).unwrap();
+        }
+        let target = "\\REGISTRY\\MACHINE\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion";
+        key.open_subkey_with_flags("F", KEY_ALL_ACCESS)
+            .unwrap()
+            .create_link("Link", target)
+            .unwrap();
+        let mut sequential: Vec<_> = key.walk().map(|e| e.unwrap()).collect();
+        let mut parallel = key.par_walk(4).unwrap();
+        sequential.sort_by(|a, b| a.path.cmp(&b.path));
+        parallel.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(parallel, sequential);
+        assert_eq!(key.par_walk(0).unwrap().len(), sequential.len());
+
+        let options = WalkOptions::new()
+            .max_depth(2)
+            .key_flags(true)
+            .error_policy(ErrorPolicy::Collect);
+        let mut sequential: Vec<_> = key
+            .walk_with_options(options.clone())
+            .map(|e| e.unwrap())
+            .collect();
+        let mut parallel = key.par_walk_with_options(2, options).unwrap();
+        sequential.sort_by(|a, b| a.path.cmp(&b.path));
+        parallel.entries.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(parallel.entries, sequential);
+        assert!(parallel.errors.is_empty());
+        assert!(parallel.skipped.is_empty());
+    });
+}
@@ -0,0 +1,81 @@
+// Copyright 2023, Igor Shaula
+// Licensed under the MIT License <LICENSE or
+// http://opensource.org/licenses/MIT>. This file
+// may not be copied, modified, or distributed
+// except according to those terms.
+use std::time::Duration;
+use winreg2::types::ToRegValue;
+use winreg2::watcher::{RegWatcher, WatchEvent};
+
+mod common;
+
+#[test]
+fn test_watcher_rescan() {
+    with_key!(key, "WatcherRescan" => {
+        key.set_value("Kept", &1u32).unwrap();
+        key.set_value("Modified", &1u32).unwrap();
+        key.set_value("Deleted", &1u32).unwrap();
+        key.create_subkey("Old").unwrap();
+        let mut watcher = RegWatcher::new(&key).unwrap();
+        assert!(watcher.rescan().unwrap().is_empty());
+
+        key.set_value("Modified", &2u32).unwrap();
+        key.delete_value("Deleted").unwrap();
+        key.delete_subkey("Old").unwrap();
+        let (new, _disp) = key.create_subkey("New").unwrap();
+        new.set_value("Created", &"x").unwrap();
+        assert_eq!(
+            watcher.rescan().unwrap(),
+            vec![
+                WatchEvent::ValueSet {
+                    path: "".to_owned(),
+                    name: "Modified".to_owned(),
+                    old: Some(1u32.to_reg_value()),
+                    new: 2u32.to_reg_value(),
+                },
+                WatchEvent::ValueDeleted {
+                    path: "".to_owned(),
+                    name: "Deleted".to_owned(),
+                    old: 1u32.to_reg_value(),
+                },
+                WatchEvent::KeyCreated {
+                    path: "New".to_owned(),
+                },
+                WatchEvent::ValueSet {
+                    path: "New".to_owned(),
+                    name: "Created".to_owned(),
+                    old: None,
+                    new: "x".to_reg_value(),
+                },
+                WatchEvent::KeyDeleted {
+                    path: "Old".to_owned(),
+                },
+            ]
+        );
+        assert!(watcher.rescan().unwrap().is_empty());
+    });
+}
+
+#[test]
+fn test_watcher_wait() {
+    with_key!(key, "WatcherWait" => {
+        let (sub, _disp) = key.create_subkey("Sub").unwrap();
+        let mut watcher = RegWatcher::new(&key).unwrap();
+        assert!(watcher.wait(Some(Duration::from_millis(10))).unwrap().is_empty());
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            sub.set_value("Value", &1u32).unwrap();
+        });
+        let events = watcher.wait(Some(Duration::from_secs(5))).unwrap();
+        writer.join().unwrap();
+        assert_eq!(
+            events,
+            vec![WatchEvent::ValueSet {
+                path: "Sub".to_owned(),
+                name: "Value".to_owned(),
+                old: None,
+                new: 1u32.to_reg_value(),
+            }]
+        );
+    });
+}